@@ -3,8 +3,8 @@ use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 use glam::vec3;
 
 use smesh::prelude::*;
-use smesh::test_utils::edge_onering;
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Selection {
     Vertex(VertexId),
@@ -64,11 +64,11 @@ fn debug_draw_smesh_system(q_smesh: Query<(&DebugRenderSMesh, &Transform)>, mut
 fn debug_draw_smesh(
     debug_smesh: &DebugRenderSMesh,
     t: &Transform,
-    mut gizmos: &mut Gizmos,
+    gizmos: &mut Gizmos,
 ) -> SMeshResult<()> {
     let mesh = &debug_smesh.mesh;
     // Verts
-    for (v_id, v) in mesh.vertices().iter() {
+    for v_id in mesh.vertices().keys() {
         let v_pos = t.transform_point(*mesh.positions.get(v_id).unwrap());
         let color = if debug_smesh.selection == Selection::Vertex(v_id) {
             Color::ORANGE_RED
@@ -78,7 +78,7 @@ fn debug_draw_smesh(
         gizmos.sphere(v_pos, Quat::IDENTITY, 0.08, color);
     }
     // Halfedges
-    for (he_id, he) in mesh.halfedges().iter() {
+    for he_id in mesh.halfedges().keys() {
         let he = he_id;
         let opposite = he.opposite().run(mesh);
         let v_src = he.src_vert().run(mesh);
@@ -90,13 +90,13 @@ fn debug_draw_smesh(
         } else {
             Color::TURQUOISE
         };
-        draw_halfedge(&mut gizmos, v_src_pos, v_dst_pos, color);
+        draw_halfedge(gizmos, v_src_pos, v_dst_pos, color);
         let color = if debug_smesh.selection == Selection::Halfedge(opposite?) {
             Color::ORANGE_RED
         } else {
             Color::TURQUOISE
         };
-        draw_halfedge(&mut gizmos, v_dst_pos, v_src_pos, color);
+        draw_halfedge(gizmos, v_dst_pos, v_src_pos, color);
     }
     // Faces
     for face_id in mesh.faces().keys() {
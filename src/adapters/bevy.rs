@@ -9,18 +9,46 @@ use crate::prelude::*;
 
 impl From<SMesh> for Mesh {
     fn from(smesh: SMesh) -> Self {
-        let buffers = smesh.to_buffers().unwrap();
+        smesh.to_buffers().unwrap().into()
+    }
+}
 
-        Mesh::new(
+impl From<VertexIndexUvBuffers> for Mesh {
+    fn from(buffers: VertexIndexUvBuffers) -> Self {
+        let mut mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         )
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, buffers.positions)
-        // .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, buffers.uvs)
-        // .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, buffers.normals)
-        .with_inserted_indices(Indices::U32(buffers.indices))
-        .with_duplicated_vertices()
-        .with_computed_flat_normals()
+        .with_inserted_indices(Indices::U32(buffers.indices));
+        if !buffers.uvs.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, buffers.uvs);
+        }
+        let mesh = mesh.with_duplicated_vertices();
+        if buffers.normals.is_empty() {
+            mesh.with_computed_flat_normals()
+        } else {
+            mesh.with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, buffers.normals)
+        }
+    }
+}
+
+impl From<&RenderMesh> for Mesh {
+    fn from(render_mesh: &RenderMesh) -> Self {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, render_mesh.positions().to_vec())
+        .with_inserted_indices(Indices::U32(render_mesh.indices().to_vec()));
+        if !render_mesh.uvs().is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, render_mesh.uvs().to_vec());
+        }
+        if render_mesh.normals().is_empty() {
+            mesh.with_duplicated_vertices().with_computed_flat_normals()
+        } else {
+            mesh.with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, render_mesh.normals().to_vec())
+        }
     }
 }
 
@@ -39,31 +67,67 @@ pub struct VertexIndexUvBuffers {
 }
 
 impl SMesh {
+    /// One bevy mesh per material index, sorted by index, so differently
+    /// textured parts of the mesh can be rendered with their own material.
+    /// See [`SMesh::faces_by_material`].
+    pub fn to_meshes_by_material(&self) -> SMeshResult<Vec<(u32, Mesh)>> {
+        self.faces_by_material()
+            .into_iter()
+            .map(|(index, faces)| Ok((index, self.faces_to_buffers(faces)?.into())))
+            .collect()
+    }
+
     fn to_buffers(&self) -> Result<VertexIndexUvBuffers, SMeshError> {
+        self.faces_to_buffers(self.faces().keys())
+    }
+
+    /// Triangulated buffers of the faces with one vertex per face corner. Normals are taken
+    /// from the halfedge normals if present, then from the vertex normals and the face normals,
+    /// corners without a stored normal, e.g. of faces created after the normals were computed,
+    /// get the current normal of their face. Corners without a uv get zero.
+    fn faces_to_buffers(
+        &self,
+        faces: impl IntoIterator<Item = FaceId>,
+    ) -> Result<VertexIndexUvBuffers, SMeshError> {
+        let has_normals = self.halfedge_normals.is_some()
+            || self.vertex_normals.is_some()
+            || self.face_normals.is_some();
         let mut positions = vec![];
         let mut uvs = vec![];
         let mut normals = vec![];
 
-        for (face_id, _face) in self.faces() {
-            let face_normal = self.face_normals.as_ref().map(|n| n[face_id]);
-            let vertices: Vec<VertexId> = face_id.vertices(&self).collect();
+        for face_id in faces {
+            let face_normal = self
+                .face_normals
+                .as_ref()
+                .and_then(|n| n.get(face_id).copied());
+            // corners of the face, each at the source vertex of its halfedge
+            let corners: Vec<(HalfedgeId, VertexId)> = face_id
+                .halfedges(self)
+                .map(|h| h.src_vert().run(self).map(|v| (h, v)))
+                .collect::<Result<_, _>>()?;
 
-            let v1 = vertices[0];
+            let c1 = corners[0];
 
-            for (&v2, &v3) in vertices[1..].iter().tuple_windows() {
-                positions.push(self.positions[v1]);
-                positions.push(self.positions[v2]);
-                positions.push(self.positions[v3]);
-
-                if let Some(mesh_uvs) = self.uvs.as_ref() {
-                    uvs.push(mesh_uvs[v1.halfedge().run(self)?]);
-                    uvs.push(mesh_uvs[v2.halfedge().run(self)?]);
-                    uvs.push(mesh_uvs[v3.halfedge().run(self)?]);
-                }
-                if let Some(normal) = face_normal {
-                    normals.push(normal);
-                    normals.push(normal);
-                    normals.push(normal);
+            for (&c2, &c3) in corners[1..].iter().tuple_windows() {
+                for (h, v) in [c1, c2, c3] {
+                    positions.push(self.positions[v]);
+                    if let Some(mesh_uvs) = self.uvs.as_ref() {
+                        uvs.push(mesh_uvs.get(h).copied().unwrap_or(Vec2::ZERO));
+                    }
+                    if !has_normals {
+                        continue;
+                    }
+                    let normal = self
+                        .halfedge_normals
+                        .as_ref()
+                        .and_then(|n| n.get(h).copied())
+                        .or_else(|| self.vertex_normals.as_ref().and_then(|n| n.get(v).copied()))
+                        .or(face_normal);
+                    normals.push(match normal {
+                        Some(normal) => normal,
+                        None => face_id.normal(self)?,
+                    });
                 }
             }
         }
@@ -76,3 +140,71 @@ impl SMesh {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+
+    #[test]
+    fn buffer_normals() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        assert!(mesh.to_buffers()?.normals.is_empty());
+
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let buffers = mesh.to_buffers()?;
+        assert_eq!(buffers.positions.len(), 36);
+        assert_eq!(buffers.normals.len(), 36);
+        // smooth shading points the normals of the corners to the outside
+        for (p, n) in buffers.positions.iter().zip(buffers.normals.iter()) {
+            assert!(n.distance(p.normalize()) < 1e-5);
+        }
+
+        mesh.mark_sharp_by_angle(std::f32::consts::FRAC_PI_4)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let buffers = mesh.to_buffers()?;
+        for triangle in buffers.normals.chunks(3) {
+            assert_eq!(triangle[0], triangle[1]);
+            assert_eq!(triangle[0], triangle[2]);
+            assert_eq!(triangle[0].abs().max_element(), 1.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn meshes_by_material() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        mesh.set_material_index(faces[0], 3)?;
+        mesh.set_material_index(faces[1], 3)?;
+        let meshes = mesh.to_meshes_by_material()?;
+        assert_eq!(meshes.iter().map(|(i, _)| *i).collect_vec(), vec![0, 3]);
+        assert_eq!(meshes[0].1.count_vertices(), 24);
+        assert_eq!(meshes[1].1.count_vertices(), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn export_stale_attributes() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        mesh.cube_project_uvs(faces.clone(), 1.0)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        // the new faces have no normals or uvs yet
+        mesh.extrude_faces_along_normals(faces[0], 0.5)?;
+        mesh.halfedge_normals = None;
+        let buffers = mesh.to_buffers()?;
+        assert_eq!(buffers.normals.len(), buffers.positions.len());
+        assert_eq!(buffers.uvs.len(), buffers.positions.len());
+        assert!(buffers.normals.iter().all(|n| n.is_normalized()));
+
+        let meshes = mesh.to_meshes_by_material()?;
+        let bevy_mesh = &meshes[0].1;
+        assert!(bevy_mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_some());
+        assert_eq!(
+            bevy_mesh.attribute(Mesh::ATTRIBUTE_NORMAL).unwrap().len(),
+            bevy_mesh.count_vertices()
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 //! # SMesh
 //! A polygon mesh manipulation library
 
+pub mod adapters;
 pub mod prelude;
 pub mod smesh;
 pub mod test_utils;
-pub mod adapters;
 mod tests;
@@ -1 +1,6 @@
-pub use crate::smesh::{error::*, iterators::*, mesh_query::*, topological_operations::*, *};
+pub use crate::smesh::{
+    bisect::*, boolean::*, builder::*, bvh::*, combine::*, curve::*, deform::*, dsmesh::*,
+    edit_operations::*, error::*, geometry::*, iterators::*, journal::*, mesh_query::*, normals::*,
+    observer::*, primitives::*, render::*, sdf::*, selection::*, shrinkwrap::*, transform::*,
+    uv_operations::*, *,
+};
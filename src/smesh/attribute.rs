@@ -1,7 +1,13 @@
 use core::f32;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
 
-use glam::{i32, Vec2, Vec3};
-use slotmap::SecondaryMap;
+use glam::{i32, Quat, Vec2, Vec3, Vec4};
+use itertools::Itertools;
+use slotmap::{KeyData, SecondaryMap};
+
+use sealed::AttributeMaps;
 
 use crate::{
     bail,
@@ -15,6 +21,30 @@ pub enum MeshAttribute {
     Vec2(Vec2),
     Vec3(Vec3),
     String(String),
+    Bool(bool),
+    U32(u32),
+    Vec4(Vec4),
+    Quat(Quat),
+    IntVec(Vec<i32>),
+}
+
+impl MeshAttribute {
+    /// Blend two values of the same variant. Floats, vectors and rotations get
+    /// interpolated, all other values are taken from the closer one.
+    /// `None` if the variants differ.
+    pub fn lerp(&self, other: &MeshAttribute, t: f32) -> Option<MeshAttribute> {
+        let closer = if t < 0.5 { self } else { other };
+        let value = match (self, other) {
+            (Self::Float(a), Self::Float(b)) => Self::Float(a + (b - a) * t),
+            (Self::Vec2(a), Self::Vec2(b)) => Self::Vec2(a.lerp(*b, t)),
+            (Self::Vec3(a), Self::Vec3(b)) => Self::Vec3(a.lerp(*b, t)),
+            (Self::Vec4(a), Self::Vec4(b)) => Self::Vec4(a.lerp(*b, t)),
+            (Self::Quat(a), Self::Quat(b)) => Self::Quat(a.slerp(*b, t)),
+            _ if std::mem::discriminant(self) == std::mem::discriminant(other) => closer.clone(),
+            _ => return None,
+        };
+        Some(value)
+    }
 }
 
 impl From<i32> for MeshAttribute {
@@ -46,6 +76,36 @@ impl From<String> for MeshAttribute {
     }
 }
 
+impl From<bool> for MeshAttribute {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<u32> for MeshAttribute {
+    fn from(value: u32) -> Self {
+        Self::U32(value)
+    }
+}
+
+impl From<Vec4> for MeshAttribute {
+    fn from(value: Vec4) -> Self {
+        Self::Vec4(value)
+    }
+}
+
+impl From<Quat> for MeshAttribute {
+    fn from(value: Quat) -> Self {
+        Self::Quat(value)
+    }
+}
+
+impl From<Vec<i32>> for MeshAttribute {
+    fn from(value: Vec<i32>) -> Self {
+        Self::IntVec(value)
+    }
+}
+
 impl TryFrom<MeshAttribute> for i32 {
     type Error = SMeshError;
 
@@ -101,9 +161,130 @@ impl TryFrom<MeshAttribute> for String {
     }
 }
 
+impl TryFrom<MeshAttribute> for bool {
+    type Error = SMeshError;
+
+    fn try_from(value: MeshAttribute) -> Result<Self, Self::Error> {
+        match value {
+            MeshAttribute::Bool(val) => Ok(val),
+            _ => bail!(DefaultError),
+        }
+    }
+}
+
+impl TryFrom<MeshAttribute> for u32 {
+    type Error = SMeshError;
+
+    fn try_from(value: MeshAttribute) -> Result<Self, Self::Error> {
+        match value {
+            MeshAttribute::U32(val) => Ok(val),
+            _ => bail!(DefaultError),
+        }
+    }
+}
+
+impl TryFrom<MeshAttribute> for Vec4 {
+    type Error = SMeshError;
+
+    fn try_from(value: MeshAttribute) -> Result<Self, Self::Error> {
+        match value {
+            MeshAttribute::Vec4(val) => Ok(val),
+            _ => bail!(DefaultError),
+        }
+    }
+}
+
+impl TryFrom<MeshAttribute> for Quat {
+    type Error = SMeshError;
+
+    fn try_from(value: MeshAttribute) -> Result<Self, Self::Error> {
+        match value {
+            MeshAttribute::Quat(val) => Ok(val),
+            _ => bail!(DefaultError),
+        }
+    }
+}
+
+impl TryFrom<MeshAttribute> for Vec<i32> {
+    type Error = SMeshError;
+
+    fn try_from(value: MeshAttribute) -> Result<Self, Self::Error> {
+        match value {
+            MeshAttribute::IntVec(val) => Ok(val),
+            _ => bail!(DefaultError),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CustomAttributeMap<T: slotmap::Key> {
     inner_map: SecondaryMap<T, MeshAttribute>,
+    /// Value returned for elements without an entry
+    default: Option<MeshAttribute>,
+}
+
+impl<K: slotmap::Key> CustomAttributeMap<K> {
+    /// Keys of all elements with a value
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.inner_map.keys()
+    }
+
+    /// Remove the value of an element, returning it if there was one.
+    /// Afterwards the element has the default value again.
+    pub fn remove(&mut self, key: K) -> Option<MeshAttribute> {
+        self.inner_map.remove(key)
+    }
+
+    /// Set the value returned by `get` for elements without an entry
+    pub fn set_default<V: Into<MeshAttribute>>(&mut self, value: V) {
+        self.default = Some(value.into());
+    }
+
+    pub fn clear_default(&mut self) {
+        self.default = None;
+    }
+
+    pub fn default_value(&self) -> Option<&MeshAttribute> {
+        self.default.as_ref()
+    }
+
+    /// The value of the element, or the default value if it has no entry
+    fn value(&self, key: K) -> Option<&MeshAttribute> {
+        self.inner_map.get(key).or(self.default.as_ref())
+    }
+
+    /// Set the value of `to` to the blend of the values of `a` and `b`,
+    /// see [`MeshAttribute::lerp`]
+    pub(crate) fn interpolate_value(&mut self, to: K, a: K, b: K, t: f32) {
+        if !self.inner_map.contains_key(a) && !self.inner_map.contains_key(b) {
+            return;
+        }
+        let (Some(a), Some(b)) = (self.value(a), self.value(b)) else {
+            return;
+        };
+        if let Some(value) = a.lerp(b, t) {
+            self.inner_map.insert(to, value);
+        }
+    }
+
+    /// Set the value of `to` to the value of `from`, if it has one
+    pub(crate) fn copy_value(&mut self, from: K, to: K) {
+        if let Some(value) = self.inner_map.get(from).cloned() {
+            self.inner_map.insert(to, value);
+        }
+    }
+
+    /// Insert the values of `other`, with their keys mapped by `remap`
+    pub(crate) fn extend_remapped(&mut self, other: &Self, remap: &SecondaryMap<K, K>) {
+        if self.default.is_none() {
+            self.default = other.default.clone();
+        }
+        for (key, value) in &other.inner_map {
+            if let Some(key) = remap.get(key) {
+                self.inner_map.insert(*key, value.clone());
+            }
+        }
+    }
 }
 
 pub trait CustomAttributeMapOps<K: slotmap::Key, V>
@@ -121,7 +302,7 @@ where
     MeshAttribute: From<V>,
 {
     fn get(&self, key: K) -> Option<V> {
-        self.inner_map.get(key)?.clone().try_into().ok()
+        self.value(key)?.clone().try_into().ok()
     }
 
     fn insert(&mut self, key: K, value: V) -> Option<V> {
@@ -133,57 +314,116 @@ where
     }
 }
 
-trait CustomAttributeOps<K: slotmap::Key> {
-    fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<K>>;
-    fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<K>>;
-    fn add_attribute_map_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<K>>;
-}
-
-impl CustomAttributeOps<VertexId> for SMesh {
-    fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<VertexId>> {
-        self.vertex_attributes.get(key)
-    }
-    fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<VertexId>> {
-        self.vertex_attributes.get_mut(key)
-    }
-    fn add_attribute_map_internal(
-        &mut self,
-        key: &str,
-    ) -> Option<&mut CustomAttributeMap<VertexId>> {
-        self.vertex_attributes
-            .insert(key.to_string(), CustomAttributeMap::default());
-        self.attribute_mut_internal(key)
+/// Elements of an [`SMesh`] that can have custom attribute maps: vertices, halfedges
+/// and faces. Sealed, the attribute maps are only accessible through the methods
+/// of the mesh.
+pub trait CustomAttributeOps<K: slotmap::Key>: sealed::AttributeMaps<K> {}
+
+impl CustomAttributeOps<VertexId> for SMesh {}
+impl CustomAttributeOps<HalfedgeId> for SMesh {}
+impl CustomAttributeOps<FaceId> for SMesh {}
+
+pub(crate) mod sealed {
+    use super::*;
+
+    pub trait AttributeMaps<K: slotmap::Key> {
+        fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<K>>;
+        fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<K>>;
+        fn add_attribute_map_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<K>>;
+        fn attribute_maps_internal(&self) -> &HashMap<String, CustomAttributeMap<K>>;
+        fn attribute_maps_mut_internal(&mut self) -> &mut HashMap<String, CustomAttributeMap<K>>;
+        /// Keys of all elements of the mesh the attributes belong to
+        fn element_keys_internal(&self) -> Vec<K>;
     }
-}
 
-impl CustomAttributeOps<HalfedgeId> for SMesh {
-    fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<HalfedgeId>> {
-        self.edge_attributes.get(key)
-    }
-    fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<HalfedgeId>> {
-        self.edge_attributes.get_mut(key)
-    }
-    fn add_attribute_map_internal(
-        &mut self,
-        key: &str,
-    ) -> Option<&mut CustomAttributeMap<HalfedgeId>> {
-        self.edge_attributes
-            .insert(key.to_string(), CustomAttributeMap::default());
-        self.attribute_mut_internal(key)
+    impl AttributeMaps<VertexId> for SMesh {
+        fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<VertexId>> {
+            self.vertex_attributes.get(key)
+        }
+        fn attribute_mut_internal(
+            &mut self,
+            key: &str,
+        ) -> Option<&mut CustomAttributeMap<VertexId>> {
+            self.vertex_attributes.get_mut(key)
+        }
+        fn add_attribute_map_internal(
+            &mut self,
+            key: &str,
+        ) -> Option<&mut CustomAttributeMap<VertexId>> {
+            self.vertex_attributes
+                .insert(key.to_string(), CustomAttributeMap::default());
+            self.attribute_mut_internal(key)
+        }
+        fn attribute_maps_internal(&self) -> &HashMap<String, CustomAttributeMap<VertexId>> {
+            &self.vertex_attributes
+        }
+        fn attribute_maps_mut_internal(
+            &mut self,
+        ) -> &mut HashMap<String, CustomAttributeMap<VertexId>> {
+            &mut self.vertex_attributes
+        }
+        fn element_keys_internal(&self) -> Vec<VertexId> {
+            self.vertices().keys().collect()
+        }
     }
-}
 
-impl CustomAttributeOps<FaceId> for SMesh {
-    fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<FaceId>> {
-        self.face_attributes.get(key)
-    }
-    fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<FaceId>> {
-        self.face_attributes.get_mut(key)
+    impl AttributeMaps<HalfedgeId> for SMesh {
+        fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<HalfedgeId>> {
+            self.edge_attributes.get(key)
+        }
+        fn attribute_mut_internal(
+            &mut self,
+            key: &str,
+        ) -> Option<&mut CustomAttributeMap<HalfedgeId>> {
+            self.edge_attributes.get_mut(key)
+        }
+        fn add_attribute_map_internal(
+            &mut self,
+            key: &str,
+        ) -> Option<&mut CustomAttributeMap<HalfedgeId>> {
+            self.edge_attributes
+                .insert(key.to_string(), CustomAttributeMap::default());
+            self.attribute_mut_internal(key)
+        }
+        fn attribute_maps_internal(&self) -> &HashMap<String, CustomAttributeMap<HalfedgeId>> {
+            &self.edge_attributes
+        }
+        fn attribute_maps_mut_internal(
+            &mut self,
+        ) -> &mut HashMap<String, CustomAttributeMap<HalfedgeId>> {
+            &mut self.edge_attributes
+        }
+        fn element_keys_internal(&self) -> Vec<HalfedgeId> {
+            self.halfedges().keys().collect()
+        }
     }
-    fn add_attribute_map_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<FaceId>> {
-        self.face_attributes
-            .insert(key.to_string(), CustomAttributeMap::default());
-        self.attribute_mut_internal(key)
+
+    impl AttributeMaps<FaceId> for SMesh {
+        fn attribute_internal(&self, key: &str) -> Option<&CustomAttributeMap<FaceId>> {
+            self.face_attributes.get(key)
+        }
+        fn attribute_mut_internal(&mut self, key: &str) -> Option<&mut CustomAttributeMap<FaceId>> {
+            self.face_attributes.get_mut(key)
+        }
+        fn add_attribute_map_internal(
+            &mut self,
+            key: &str,
+        ) -> Option<&mut CustomAttributeMap<FaceId>> {
+            self.face_attributes
+                .insert(key.to_string(), CustomAttributeMap::default());
+            self.attribute_mut_internal(key)
+        }
+        fn attribute_maps_internal(&self) -> &HashMap<String, CustomAttributeMap<FaceId>> {
+            &self.face_attributes
+        }
+        fn attribute_maps_mut_internal(
+            &mut self,
+        ) -> &mut HashMap<String, CustomAttributeMap<FaceId>> {
+            &mut self.face_attributes
+        }
+        fn element_keys_internal(&self) -> Vec<FaceId> {
+            self.faces().keys().collect()
+        }
     }
 }
 
@@ -214,13 +454,236 @@ impl SMesh {
     {
         self.add_attribute_map_internal(key)
     }
+
+    /// Names of all attribute maps of the elements `K`, sorted
+    pub fn attribute_names<K: slotmap::Key>(&self) -> Vec<String>
+    where
+        Self: CustomAttributeOps<K>,
+    {
+        self.attribute_maps_internal()
+            .keys()
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    /// Remove an attribute map, returning it if it existed
+    pub fn remove_attribute_map<K: slotmap::Key>(
+        &mut self,
+        key: &str,
+    ) -> Option<CustomAttributeMap<K>>
+    where
+        Self: CustomAttributeOps<K>,
+    {
+        self.attribute_maps_mut_internal().remove(key)
+    }
+
+    /// Rename an attribute map, replacing an existing map with the new name.
+    /// Returns `None` if there is no map named `from`.
+    pub fn rename_attribute_map<K: slotmap::Key>(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Option<&mut CustomAttributeMap<K>>
+    where
+        Self: CustomAttributeOps<K>,
+    {
+        let maps = self.attribute_maps_mut_internal();
+        let map = maps.remove(from)?;
+        maps.insert(to.to_string(), map);
+        maps.get_mut(to)
+    }
+}
+
+/// Type erased attribute map, so maps of arbitrary value types can be stored together
+trait AnyAttributeMap: Any + Debug + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AnyAttributeMap>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn copy_value(&mut self, from: KeyData, to: KeyData);
+    /// Insert the values of `other` if it is a map of the same type,
+    /// with their keys mapped by `remap`
+    fn extend_remapped(
+        &mut self,
+        other: &dyn AnyAttributeMap,
+        remap: &dyn Fn(KeyData) -> Option<KeyData>,
+    );
+    fn empty(&self) -> Box<dyn AnyAttributeMap>;
+}
+
+impl<K, T> AnyAttributeMap for SecondaryMap<K, T>
+where
+    K: slotmap::Key + Send + Sync + 'static,
+    T: Clone + Debug + Send + Sync + 'static,
+{
+    fn clone_box(&self) -> Box<dyn AnyAttributeMap> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn copy_value(&mut self, from: KeyData, to: KeyData) {
+        if let Some(value) = self.get(K::from(from)).cloned() {
+            self.insert(K::from(to), value);
+        }
+    }
+    fn extend_remapped(
+        &mut self,
+        other: &dyn AnyAttributeMap,
+        remap: &dyn Fn(KeyData) -> Option<KeyData>,
+    ) {
+        let Some(other) = other.as_any().downcast_ref::<Self>() else {
+            return;
+        };
+        for (key, value) in other {
+            if let Some(key) = remap(key.data()) {
+                self.insert(K::from(key), value.clone());
+            }
+        }
+    }
+    fn empty(&self) -> Box<dyn AnyAttributeMap> {
+        Box::new(Self::new())
+    }
+}
+
+/// Attribute maps with user defined value types, by element type and name
+#[derive(Debug, Default)]
+pub(crate) struct TypedAttributes(HashMap<(TypeId, String), Box<dyn AnyAttributeMap>>);
+
+impl Clone for TypedAttributes {
+    fn clone(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(key, map)| (key.clone(), map.clone_box()))
+                .collect(),
+        )
+    }
+}
+
+impl TypedAttributes {
+    /// Copy the values of `from` to `to` in all maps of the element type `K`.
+    /// Typed values can't be blended, so they are copied from the closest element.
+    pub(crate) fn copy_values<K: slotmap::Key + 'static>(&mut self, from: K, to: K) {
+        for ((element, _), map) in self.0.iter_mut() {
+            if *element == TypeId::of::<K>() {
+                map.copy_value(from.data(), to.data());
+            }
+        }
+    }
+
+    /// Insert the values of all maps of `other`, with their keys mapped by `remap`
+    /// for the element type. Maps that don't exist yet get created, maps with the same
+    /// name but a different value type keep their values.
+    pub(crate) fn extend_remapped(
+        &mut self,
+        other: &TypedAttributes,
+        remap: impl Fn(TypeId, KeyData) -> Option<KeyData>,
+    ) {
+        for (key, map) in &other.0 {
+            let target = self.0.entry(key.clone()).or_insert_with(|| map.empty());
+            target.extend_remapped(map.as_ref(), &|k| remap(key.0, k));
+        }
+    }
+}
+
+/// Typed custom attributes
+impl SMesh {
+    /// Add an attribute map storing values of any type `T` for the elements `K`,
+    /// replacing an existing map with the same element type and name.
+    /// Unlike [`SMesh::add_attribute_map`] the values are stored as they are,
+    /// without converting them to a [`MeshAttribute`].
+    /// ```
+    /// use glam::vec3;
+    /// use smesh::prelude::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Material {
+    ///     Wood,
+    ///     Metal,
+    /// }
+    ///
+    /// let mut mesh = SMesh::new();
+    /// let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));
+    /// let v1 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+    /// let v2 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+    /// let f = mesh.add_face(vec![v0, v1, v2]).unwrap();
+    /// mesh.add_typed_attribute::<FaceId, Material>("material")
+    ///     .insert(f, Material::Metal);
+    /// let materials = mesh.typed_attribute::<FaceId, Material>("material").unwrap();
+    /// assert_eq!(materials[f], Material::Metal);
+    /// ```
+    pub fn add_typed_attribute<K, T>(&mut self, name: &str) -> &mut SecondaryMap<K, T>
+    where
+        K: slotmap::Key + Send + Sync + 'static,
+        T: Clone + Debug + Send + Sync + 'static,
+    {
+        let key = (TypeId::of::<K>(), name.to_string());
+        let maps = &mut self.typed_attributes.0;
+        maps.insert(key.clone(), Box::new(SecondaryMap::<K, T>::new()));
+        maps.get_mut(&key)
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// The typed attribute map of the elements `K` with the given name,
+    /// `None` if it does not exist or stores a different value type
+    pub fn typed_attribute<K, T>(&self, name: &str) -> Option<&SecondaryMap<K, T>>
+    where
+        K: slotmap::Key + 'static,
+        T: 'static,
+    {
+        self.typed_attributes
+            .0
+            .get(&(TypeId::of::<K>(), name.to_string()))?
+            .as_any()
+            .downcast_ref()
+    }
+
+    /// Names of all typed attribute maps of the elements `K`, sorted
+    pub fn typed_attribute_names<K: slotmap::Key + 'static>(&self) -> Vec<String> {
+        self.typed_attributes
+            .0
+            .keys()
+            .filter(|(element, _)| *element == TypeId::of::<K>())
+            .map(|(_, name)| name.clone())
+            .sorted()
+            .collect()
+    }
+
+    /// Remove the typed attribute map of the elements `K` with the given name,
+    /// whatever its value type. Returns whether it existed.
+    pub fn remove_typed_attribute<K: slotmap::Key + 'static>(&mut self, name: &str) -> bool {
+        self.typed_attributes
+            .0
+            .remove(&(TypeId::of::<K>(), name.to_string()))
+            .is_some()
+    }
+
+    /// Mutable access to a typed attribute map, see [`SMesh::typed_attribute`]
+    pub fn typed_attribute_mut<K, T>(&mut self, name: &str) -> Option<&mut SecondaryMap<K, T>>
+    where
+        K: slotmap::Key + 'static,
+        T: 'static,
+    {
+        self.typed_attributes
+            .0
+            .get_mut(&(TypeId::of::<K>(), name.to_string()))?
+            .as_any_mut()
+            .downcast_mut()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use glam::vec3;
 
-    use crate::prelude::{attribute::CustomAttributeMapOps, SMesh, VertexId};
+    use crate::prelude::{attribute::CustomAttributeMapOps, FaceId, SMesh, VertexId};
 
     #[test]
     fn basic_integer() {
@@ -266,4 +729,140 @@ mod test {
         curvature.insert(v0, "hello".to_string());
         assert_eq!(curvature.get(v0), Some("hello".to_string()));
     }
+
+    #[test]
+    fn basic_flags_and_indices() {
+        let mut smesh = SMesh::new();
+        let v0 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let map = smesh.add_attribute_map::<VertexId>("data").unwrap();
+        map.insert(v0, true);
+        assert_eq!(map.get(v0), Some(true));
+        map.insert(v0, 7u32);
+        assert_eq!(map.get(v0), Some(7u32));
+        // no lossy conversion between the integer variants
+        assert_eq!(CustomAttributeMapOps::<VertexId, i32>::get(map, v0), None);
+        map.insert(v0, vec![1, -2, 3]);
+        assert_eq!(map.get(v0), Some(vec![1, -2, 3]));
+    }
+
+    #[test]
+    fn basic_vec4_and_quat() {
+        let mut smesh = SMesh::new();
+        let v0 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let tangents = smesh.add_attribute_map::<VertexId>("tangent").unwrap();
+        tangents.insert(v0, glam::vec4(1.0, 0.0, 0.0, -1.0));
+        assert_eq!(tangents.get(v0), Some(glam::vec4(1.0, 0.0, 0.0, -1.0)));
+        let q = glam::Quat::from_rotation_z(1.0);
+        tangents.insert(v0, q);
+        assert_eq!(tangents.get(v0), Some(q));
+    }
+
+    #[test]
+    fn default_value() {
+        let mut smesh = SMesh::new();
+        let v0 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let v1 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let weights = smesh.add_attribute_map::<VertexId>("weight").unwrap();
+        weights.insert(v0, 2.0);
+        assert_eq!(
+            CustomAttributeMapOps::<VertexId, f32>::get(weights, v1),
+            None
+        );
+        weights.set_default(0.0);
+        assert_eq!(weights.get(v0), Some(2.0));
+        assert_eq!(weights.get(v1), Some(0.0));
+        // only explicit entries are listed
+        assert_eq!(weights.keys().count(), 1);
+        weights.remove(v0);
+        assert_eq!(weights.get(v0), Some(0.0));
+        // the default only applies to values of its own type
+        assert_eq!(
+            CustomAttributeMapOps::<VertexId, i32>::get(weights, v1),
+            None
+        );
+        weights.clear_default();
+        assert_eq!(
+            CustomAttributeMapOps::<VertexId, f32>::get(weights, v1),
+            None
+        );
+    }
+
+    #[test]
+    fn manage_attribute_maps() {
+        let mut smesh = SMesh::new();
+        let v0 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        smesh
+            .add_attribute_map::<VertexId>("b")
+            .unwrap()
+            .insert(v0, 1);
+        smesh.add_attribute_map::<VertexId>("a");
+        smesh.add_attribute_map::<FaceId>("c");
+        assert_eq!(smesh.attribute_names::<VertexId>(), vec!["a", "b"]);
+        assert_eq!(smesh.attribute_names::<FaceId>(), vec!["c"]);
+
+        let renamed = smesh.rename_attribute_map::<VertexId>("b", "a").unwrap();
+        assert_eq!(renamed.get(v0), Some(1));
+        assert!(renamed.remove(v0).is_some());
+        assert_eq!(
+            CustomAttributeMapOps::<VertexId, i32>::get(renamed, v0),
+            None
+        );
+        assert_eq!(smesh.attribute_names::<VertexId>(), vec!["a"]);
+        assert!(smesh.rename_attribute_map::<VertexId>("b", "d").is_none());
+
+        assert!(smesh.remove_attribute_map::<VertexId>("a").is_some());
+        assert!(smesh.attribute::<VertexId>("a").is_none());
+        assert!(smesh.attribute_names::<VertexId>().is_empty());
+
+        smesh.add_typed_attribute::<VertexId, u8>("t");
+        assert_eq!(smesh.typed_attribute_names::<VertexId>(), vec!["t"]);
+        assert!(smesh.typed_attribute_names::<FaceId>().is_empty());
+        assert!(smesh.remove_typed_attribute::<VertexId>("t"));
+        assert!(!smesh.remove_typed_attribute::<VertexId>("t"));
+    }
+
+    #[test]
+    fn typed_attribute() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Frame {
+            tangent: glam::Vec3,
+            handedness: bool,
+        }
+
+        let mut smesh = SMesh::new();
+        let v0 = smesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let frame = Frame {
+            tangent: glam::Vec3::X,
+            handedness: true,
+        };
+        smesh
+            .add_typed_attribute::<VertexId, Frame>("frame")
+            .insert(v0, frame.clone());
+        assert_eq!(
+            smesh.typed_attribute::<VertexId, Frame>("frame").unwrap()[v0],
+            frame
+        );
+        // wrong value or element type
+        assert!(smesh.typed_attribute::<VertexId, f32>("frame").is_none());
+        assert!(smesh.typed_attribute::<FaceId, Frame>("frame").is_none());
+
+        let copy = smesh.clone();
+        smesh
+            .typed_attribute_mut::<VertexId, Frame>("frame")
+            .unwrap()
+            .remove(v0);
+        assert!(smesh
+            .typed_attribute::<VertexId, Frame>("frame")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            copy.typed_attribute::<VertexId, Frame>("frame").unwrap()[v0],
+            frame
+        );
+
+        // adding again replaces the map
+        smesh.add_typed_attribute::<VertexId, u8>("frame");
+        assert!(smesh.typed_attribute::<VertexId, Frame>("frame").is_none());
+        assert!(smesh.typed_attribute::<VertexId, u8>("frame").is_some());
+    }
 }
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+const PLANE_EPSILON: f32 = 1e-5;
+
+/// A plane defined by a point on the plane and its normal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub origin: Vec3,
+    pub normal: Vec3,
+}
+
+impl Plane {
+    /// Create a new plane, the normal gets normalized
+    pub fn new(origin: Vec3, normal: Vec3) -> Self {
+        Plane {
+            origin,
+            normal: normal.normalize(),
+        }
+    }
+
+    /// Distance of the point to the plane, positive on the side the normal points to
+    pub fn signed_distance(&self, p: Vec3) -> f32 {
+        self.normal.dot(p - self.origin)
+    }
+}
+
+/// What to do with the mesh after it was cut by the plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BisectMode {
+    /// Only insert the cut, keep both sides
+    #[default]
+    Cut,
+    /// Remove everything on the negative side of the plane
+    KeepPositive,
+    /// Remove everything on the positive side of the plane
+    KeepNegative,
+    /// Remove everything on the negative side of the plane and close
+    /// the resulting holes with faces
+    Cap,
+}
+
+impl SMesh {
+    /// Cut the mesh along the plane.
+    /// Edges crossing the plane are split and faces crossing the plane are split
+    /// along the cut, so the mesh gets a cut loop of edges lying on the plane.
+    /// Depending on the `mode` one side of the mesh is removed afterwards, and with
+    /// [`BisectMode::Cap`] holes that are bounded only by cut vertices are closed with
+    /// new faces.
+    /// Returns all vertices that lie on the plane after the operation.
+    pub fn bisect(&mut self, plane: Plane, mode: BisectMode) -> SMeshResult<Vec<VertexId>> {
+        let mut distances = self
+            .positions
+            .iter()
+            .map(|(v, p)| (v, plane.signed_distance(*p)))
+            .collect::<slotmap::SecondaryMap<_, _>>();
+
+        // split crossing edges
+        let crossing = self
+            .halfedges()
+            .keys()
+            .filter(|h| {
+                let (Ok(v0), Ok(v1)) = (h.src_vert().run(self), h.dst_vert().run(self)) else {
+                    return false;
+                };
+                let (d0, d1) = (distances[v0], distances[v1]);
+                v0 < v1
+                    && ((d0 < -PLANE_EPSILON && d1 > PLANE_EPSILON)
+                        || (d0 > PLANE_EPSILON && d1 < -PLANE_EPSILON))
+            })
+            .collect_vec();
+        for h in crossing {
+            let d0 = distances[h.src_vert().run(self)?];
+            let d1 = distances[h.dst_vert().run(self)?];
+            let v = self.split_edge_at(h, d0 / (d0 - d1))?;
+            distances.insert(v, 0.0);
+        }
+        let on_plane = |v: VertexId| distances[v].abs() <= PLANE_EPSILON;
+        let side = |v: VertexId| {
+            let d = distances[v];
+            if d > PLANE_EPSILON {
+                1
+            } else if d < -PLANE_EPSILON {
+                -1
+            } else {
+                0
+            }
+        };
+
+        // split faces crossing the plane
+        let faces = self.faces().keys().collect_vec();
+        for f in faces {
+            let mut f = f;
+            loop {
+                let halfedges = f.halfedges(self).collect_vec();
+                let sides = halfedges
+                    .iter()
+                    .map(|h| Ok(side(h.dst_vert().run(self)?)))
+                    .collect::<SMeshResult<Vec<_>>>()?;
+                if !(sides.contains(&1) && sides.contains(&-1)) {
+                    break;
+                }
+                // cut off the first arc between two cut vertices
+                let n = halfedges.len();
+                let Some(start) = (0..n).find(|i| sides[*i] == 0 && sides[(i + 1) % n] != 0) else {
+                    break;
+                };
+                let Some(end) = (1..n).map(|i| (start + i) % n).find(|i| sides[*i] == 0) else {
+                    break;
+                };
+                self.insert_edge(halfedges[start], halfedges[end])?;
+                f = halfedges[start].face().run(self)?;
+            }
+        }
+
+        // remove one side
+        let remove_side = match mode {
+            BisectMode::Cut => None,
+            BisectMode::KeepPositive | BisectMode::Cap => Some(-1),
+            BisectMode::KeepNegative => Some(1),
+        };
+        if let Some(remove_side) = remove_side {
+            let remove = self
+                .faces()
+                .keys()
+                .filter(|f| {
+                    let sides = f.vertices(self).map(side).collect_vec();
+                    sides.contains(&remove_side) && !sides.contains(&-remove_side)
+                })
+                .collect_vec();
+            for f in remove {
+                self.delete_face(f)?;
+            }
+        }
+
+        // close holes on the plane
+        if mode == BisectMode::Cap {
+            let mut visited = HashSet::new();
+            let boundary = self
+                .halfedges()
+                .keys()
+                .filter(|h| h.is_boundary(self))
+                .collect_vec();
+            for h in boundary {
+                if visited.contains(&h) {
+                    continue;
+                }
+                let mut hole = vec![];
+                let mut current = h;
+                loop {
+                    visited.insert(current);
+                    hole.push(current.src_vert().run(self)?);
+                    current = current.next().run(self)?;
+                    if current == h {
+                        break;
+                    }
+                }
+                if hole.len() >= 3 && hole.iter().all(|v| on_plane(*v)) {
+                    self.add_face(hole)?;
+                }
+            }
+        }
+
+        Ok(self
+            .vertices()
+            .keys()
+            .filter(|v| on_plane(*v))
+            .collect_vec())
+    }
+
+    /// Split the edge of `h` at parameter `t` from its source to target vertex,
+    /// the attributes get interpolated by [`SMesh::insert_vertex`]
+    fn split_edge_at(&mut self, h: HalfedgeId, t: f32) -> SMeshResult<VertexId> {
+        let p0 = self.positions[h.src_vert().run(self)?];
+        let p1 = self.positions[h.dst_vert().run(self)?];
+        let v = self.add_vertex(p0.lerp(p1, t));
+        self.insert_vertex(h, v)?;
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{check_connectivity, cube};
+    use glam::vec3;
+
+    fn half_plane() -> Plane {
+        Plane::new(vec3(0.0, 0.25, 0.0), Vec3::Y)
+    }
+
+    #[test]
+    fn bisect_cut() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let cut = mesh.bisect(half_plane(), BisectMode::Cut)?;
+        check_connectivity(&mesh)?;
+        assert_eq!(cut.len(), 4);
+        assert_eq!(mesh.vertices().len(), 12);
+        assert_eq!(mesh.faces().len(), 10);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        for v in cut {
+            assert!((mesh.positions[v].y - 0.25).abs() < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_keep_side() -> SMeshResult<()> {
+        let mut positive = cube(Vec3::ZERO, 1.0)?;
+        positive.bisect(half_plane(), BisectMode::KeepPositive)?;
+        check_connectivity(&positive)?;
+        assert_eq!(positive.vertices().len(), 8);
+        assert_eq!(positive.faces().len(), 5);
+        assert!(positive
+            .vertices()
+            .keys()
+            .all(|v| positive.positions[v].y >= 0.25 - 1e-5));
+
+        let mut negative = cube(Vec3::ZERO, 1.0)?;
+        negative.bisect(half_plane(), BisectMode::KeepNegative)?;
+        check_connectivity(&negative)?;
+        assert_eq!(negative.faces().len(), 5);
+        assert!(negative
+            .vertices()
+            .keys()
+            .all(|v| negative.positions[v].y <= 0.25 + 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_cap() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        mesh.bisect(half_plane(), BisectMode::Cap)?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 6);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        // cap faces away from the kept side
+        let cap = mesh
+            .faces()
+            .keys()
+            .find(|f| f.vertices(&mesh).all(|v| mesh.positions[v].y < 0.3))
+            .unwrap();
+        assert!(cap.normal(&mesh)?.dot(Vec3::NEG_Y) > 0.99);
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_interpolates_uvs() -> SMeshResult<()> {
+        let mut mesh = SMesh::new();
+        let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+        let f = mesh.add_quad(v0, v1, v2, v3)?;
+        let mut uvs = slotmap::SecondaryMap::new();
+        for h in f.halfedges(&mesh) {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            uvs.insert(h, p.truncate());
+        }
+        mesh.uvs = Some(uvs);
+        mesh.bisect(Plane::new(vec3(0.25, 0.0, 0.0), Vec3::X), BisectMode::Cut)?;
+        assert_eq!(mesh.faces().len(), 2);
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(&mesh) {
+                let p = mesh.positions[h.src_vert().run(&mesh)?];
+                assert!(uvs[h].distance(p.truncate()) < 1e-5);
+            }
+        }
+        Ok(())
+    }
+}
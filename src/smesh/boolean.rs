@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::prelude::*;
+use crate::smesh::bvh::{Bvh, Triangle};
+
+/// Boolean operation to combine two closed meshes with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Everything that is inside of either mesh
+    Union,
+    /// Everything of the first mesh that is not inside of the second mesh
+    Difference,
+    /// Everything that is inside of both meshes
+    Intersection,
+}
+
+impl SMesh {
+    /// Combine this mesh with `other` and return the result as a new mesh.
+    /// Faces of both meshes are split where they intersect the other mesh, and the
+    /// resulting pieces are kept or discarded depending on whether they lie inside
+    /// the other mesh.
+    /// Both meshes need to be closed and consist of convex faces. This is a simple
+    /// f32 implementation that is not robust against coplanar overlapping faces.
+    /// The pieces are welded by position into a closed mesh, faces that can't be
+    /// connected make it fail with a topology error.
+    pub fn boolean(&self, other: &SMesh, op: BooleanOp) -> SMeshResult<SMesh> {
+        let polygons_a = self.face_polygons();
+        let polygons_b = other.face_polygons();
+        let triangles_a = triangulate_polygons(&polygons_a);
+        let triangles_b = triangulate_polygons(&polygons_b);
+        let bvh_a = Bvh::new(self);
+        let bvh_b = Bvh::new(other);
+
+        let (min, max) = polygons_a
+            .iter()
+            .chain(polygons_b.iter())
+            .flatten()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), p| {
+                (min.min(*p), max.max(*p))
+            });
+        let eps = (max - min).length().max(1.0) * 1e-5;
+
+        let (keep_a_inside, keep_b_inside) = match op {
+            BooleanOp::Union => (false, false),
+            BooleanOp::Difference => (false, true),
+            BooleanOp::Intersection => (true, true),
+        };
+
+        let mut result = vec![];
+        for piece in split_polygons(&polygons_a, &triangles_b, eps) {
+            if bvh_b.contains(centroid(&piece)) == keep_a_inside {
+                result.push(piece);
+            }
+        }
+        for mut piece in split_polygons(&polygons_b, &triangles_a, eps) {
+            if bvh_a.contains(centroid(&piece)) == keep_b_inside {
+                if op == BooleanOp::Difference {
+                    piece.reverse();
+                }
+                result.push(piece);
+            }
+        }
+        weld_polygons(&result, eps)
+    }
+
+    pub(crate) fn face_polygons(&self) -> Vec<Vec<Vec3>> {
+        self.faces()
+            .keys()
+            .map(|f| f.vertices(self).map(|v| self.positions[v]).collect_vec())
+            .collect_vec()
+    }
+}
+
+fn triangulate_polygons(polygons: &[Vec<Vec3>]) -> Vec<Triangle> {
+    polygons
+        .iter()
+        .flat_map(|poly| {
+            poly[1..]
+                .iter()
+                .tuple_windows()
+                .map(|(p1, p2)| Triangle::new([poly[0], *p1, *p2]))
+        })
+        .collect_vec()
+}
+
+fn centroid(polygon: &[Vec3]) -> Vec3 {
+    polygon.iter().sum::<Vec3>() / polygon.len() as f32
+}
+
+fn polygon_bounds(polygon: &[Vec3]) -> (Vec3, Vec3) {
+    polygon
+        .iter()
+        .fold((Vec3::MAX, Vec3::MIN), |(min, max), p| {
+            (min.min(*p), max.max(*p))
+        })
+}
+
+/// Split every polygon along the planes of all triangles it intersects, so every
+/// resulting piece is either completely inside or outside of the triangles' mesh
+fn split_polygons(polygons: &[Vec<Vec3>], cutters: &[Triangle], eps: f32) -> Vec<Vec<Vec3>> {
+    let mut result = vec![];
+    for polygon in polygons {
+        let (min, max) = polygon_bounds(polygon);
+        let triangles = triangulate_polygons(std::slice::from_ref(polygon));
+        let mut pieces = vec![polygon.clone()];
+        for cutter in cutters {
+            if !cutter.bounds_overlap(min, max, eps)
+                || !triangles
+                    .iter()
+                    .any(|t| triangles_intersect(t, cutter, eps))
+            {
+                continue;
+            }
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| split_polygon(piece, cutter, eps))
+                .collect_vec();
+        }
+        result.extend(pieces);
+    }
+    result
+}
+
+/// Split a convex polygon by the plane of the triangle. Returns the polygon itself
+/// if it does not get split into two non-degenerate parts.
+fn split_polygon(polygon: Vec<Vec3>, plane: &Triangle, eps: f32) -> Vec<Vec<Vec3>> {
+    let d = polygon
+        .iter()
+        .map(|p| plane.signed_distance(*p))
+        .collect_vec();
+    if d.iter().all(|d| *d > -eps) || d.iter().all(|d| *d < eps) {
+        return vec![polygon];
+    }
+    let mut front = vec![];
+    let mut back = vec![];
+    for i in 0..polygon.len() {
+        let j = (i + 1) % polygon.len();
+        let (p0, d0) = (polygon[i], d[i]);
+        let (p1, d1) = (polygon[j], d[j]);
+        if d0.abs() < eps {
+            front.push(p0);
+            back.push(p0);
+        } else if d0 > 0.0 {
+            front.push(p0);
+        } else {
+            back.push(p0);
+        }
+        if (d0 > eps && d1 < -eps) || (d0 < -eps && d1 > eps) {
+            let p = p0 + (p1 - p0) * (d0 / (d0 - d1));
+            front.push(p);
+            back.push(p);
+        }
+    }
+    if front.len() < 3 || back.len() < 3 {
+        return vec![polygon];
+    }
+    vec![front, back]
+}
+
+/// Whether two triangles intersect, ignoring coplanar triangles
+fn triangles_intersect(a: &Triangle, b: &Triangle, eps: f32) -> bool {
+    if !a.bounds_overlap(b.min, b.max, eps) {
+        return false;
+    }
+    let da = a.p.map(|p| b.signed_distance(p));
+    let db = b.p.map(|p| a.signed_distance(p));
+    let separated = |d: &[f32; 3]| d.iter().all(|d| *d > eps) || d.iter().all(|d| *d < -eps);
+    if separated(&da) || separated(&db) {
+        return false;
+    }
+    let dir = a.n.cross(b.n);
+    if dir.length_squared() < 1e-12 {
+        return false;
+    }
+    let (a0, a1) = plane_interval(a, &da, dir, eps);
+    let (b0, b1) = plane_interval(b, &db, dir, eps);
+    a0 <= b1 + eps && b0 <= a1 + eps
+}
+
+/// Interval, projected on `dir`, in which the plane of the other triangle cuts the triangle
+fn plane_interval(t: &Triangle, d: &[f32; 3], dir: Vec3, eps: f32) -> (f32, f32) {
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let mut add = |p: Vec3| {
+            let x = p.dot(dir);
+            min = min.min(x);
+            max = max.max(x);
+        };
+        if d[i].abs() <= eps {
+            add(t.p[i]);
+        } else if d[i] * d[j] < 0.0 && d[j].abs() > eps {
+            add(t.p[i] + (t.p[j] - t.p[i]) * (d[i] / (d[i] - d[j])));
+        }
+    }
+    (min, max)
+}
+
+/// Build a mesh from polygons, merging vertices closer than `eps`.
+/// A polygon that was split next to an edge it shares with a polygon that wasn't gets
+/// the cut points inserted into that edge, so both sides are connected.
+fn weld_polygons(polygons: &[Vec<Vec3>], eps: f32) -> SMeshResult<SMesh> {
+    let mut mesh = SMesh::new();
+    let mut vertices: HashMap<(i64, i64, i64), VertexId> = HashMap::new();
+    let mut faces = vec![];
+    for polygon in polygons {
+        let mut ids = vec![];
+        for p in polygon {
+            let key = (
+                (p.x / eps).round() as i64,
+                (p.y / eps).round() as i64,
+                (p.z / eps).round() as i64,
+            );
+            let id = *vertices.entry(key).or_insert_with(|| mesh.add_vertex(*p));
+            if ids.last() != Some(&id) && ids.first() != Some(&id) {
+                ids.push(id);
+            }
+        }
+        if ids.len() >= 3 {
+            faces.push(ids);
+        }
+    }
+
+    let points = mesh.positions.iter().map(|(v, p)| (v, *p)).collect_vec();
+    for ids in faces {
+        let mut face = vec![];
+        for (a, b) in ids.iter().copied().circular_tuple_windows() {
+            face.push(a);
+            let (pa, pb) = (mesh.positions[a], mesh.positions[b]);
+            let edge = pb - pa;
+            let (min, max) = (pa.min(pb) - eps, pa.max(pb) + eps);
+            let mut on_edge = points
+                .iter()
+                .filter(|(v, p)| *v != a && *v != b && p.cmpge(min).all() && p.cmple(max).all())
+                .filter_map(|(v, p)| {
+                    let t = (*p - pa).dot(edge) / edge.length_squared();
+                    let on = t > 0.0 && t < 1.0 && (pa + edge * t).distance(*p) < eps;
+                    on.then_some((t, *v))
+                })
+                .collect_vec();
+            on_edge.sort_by(|x, y| x.0.total_cmp(&y.0));
+            face.extend(on_edge.into_iter().map(|(_, v)| v));
+        }
+        mesh.add_face(face)?;
+    }
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{check_connectivity, cube};
+
+    fn overlapping_cubes() -> SMeshResult<(SMesh, SMesh)> {
+        Ok((cube(Vec3::ZERO, 1.0)?, cube(Vec3::splat(0.5), 1.0)?))
+    }
+
+    #[test]
+    fn union() -> SMeshResult<()> {
+        let (a, b) = overlapping_cubes()?;
+        let result = a.boolean(&b, BooleanOp::Union)?;
+        check_connectivity(&result)?;
+        assert!((result.volume()? - 1.875).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn difference() -> SMeshResult<()> {
+        let (a, b) = overlapping_cubes()?;
+        let result = a.boolean(&b, BooleanOp::Difference)?;
+        check_connectivity(&result)?;
+        assert!((result.volume()? - 0.875).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn intersection() -> SMeshResult<()> {
+        let (a, b) = overlapping_cubes()?;
+        let result = a.boolean(&b, BooleanOp::Intersection)?;
+        check_connectivity(&result)?;
+        assert!((result.volume()? - 0.125).abs() < 1e-3);
+        for v in result.vertices().keys() {
+            let p = result.positions[v];
+            assert!(p.cmpge(Vec3::splat(-1e-4)).all() && p.cmple(Vec3::splat(0.5001)).all());
+        }
+        Ok(())
+    }
+
+    /// Cutting planes that aren't axis aligned give cut points that don't line up
+    /// with the neighbouring faces
+    #[test]
+    fn rotated_cubes() -> SMeshResult<()> {
+        let a = cube(Vec3::ZERO, 1.0)?;
+        let mut b = cube(Vec3::ZERO, 1.0)?;
+        let vertices = b.vertices().keys().collect_vec();
+        b.rotate(
+            vertices.clone(),
+            glam::Quat::from_euler(glam::EulerRot::XYZ, 0.3, 0.5, 0.7),
+            Pivot::Origin,
+        )?;
+        b.translate(vertices, Vec3::splat(0.4))?;
+        let mut volumes = vec![];
+        for op in [
+            BooleanOp::Union,
+            BooleanOp::Difference,
+            BooleanOp::Intersection,
+        ] {
+            let result = a.boolean(&b, op)?;
+            check_connectivity(&result)?;
+            volumes.push(result.volume()?);
+        }
+        let [union, difference, intersection] = volumes[..] else {
+            unreachable!()
+        };
+        assert!(intersection > 0.1);
+        assert!((union + intersection - 2.0).abs() < 1e-3);
+        assert!((difference + intersection - 1.0).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn disjoint() -> SMeshResult<()> {
+        let a = cube(Vec3::ZERO, 1.0)?;
+        let b = cube(Vec3::splat(3.0), 1.0)?;
+        assert_eq!(a.boolean(&b, BooleanOp::Union)?.faces().len(), 12);
+        assert_eq!(a.boolean(&b, BooleanOp::Intersection)?.faces().len(), 0);
+        assert_eq!(a.boolean(&b, BooleanOp::Difference)?.faces().len(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Collects vertex positions and faces as lists of vertex indices without
+/// maintaining any connectivity, which gets built in a single pass by
+/// [`MeshBuilder::build`]. Useful for generating or importing large meshes.
+/// ```
+/// use glam::vec3;
+/// use smesh::prelude::*;
+///
+/// let mut builder = MeshBuilder::new();
+/// let v0 = builder.add_vertex(vec3(0.0, 0.0, 0.0));
+/// let v1 = builder.add_vertex(vec3(1.0, 0.0, 0.0));
+/// let v2 = builder.add_vertex(vec3(0.0, 1.0, 0.0));
+/// builder.add_face(vec![v0, v1, v2]);
+/// let mesh = builder.build().unwrap();
+/// assert_eq!(mesh.faces().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MeshBuilder {
+    positions: Vec<Vec3>,
+    faces: Vec<Vec<usize>>,
+}
+
+impl MeshBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(vertices: usize, faces: usize) -> Self {
+        Self {
+            positions: Vec::with_capacity(vertices),
+            faces: Vec::with_capacity(faces),
+        }
+    }
+
+    /// Builder from a list of positions and faces indexing into it
+    pub fn from_polygons(positions: Vec<Vec3>, faces: Vec<Vec<usize>>) -> Self {
+        Self { positions, faces }
+    }
+
+    /// Add a vertex and return its index
+    pub fn add_vertex(&mut self, position: Vec3) -> usize {
+        self.positions.push(position);
+        self.positions.len() - 1
+    }
+
+    /// Add a face from the indices of its vertices and return its index
+    pub fn add_face(&mut self, vertices: Vec<usize>) -> usize {
+        self.faces.push(vertices);
+        self.faces.len() - 1
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Build the mesh. Vertex and face ids are created in the order they were added.
+    /// Fails if a face has less than 3 vertices or refers to a missing vertex,
+    /// or if the faces don't form a valid surface.
+    pub fn build(self) -> SMeshResult<SMesh> {
+        let vertex_count = self.positions.len();
+        if self.faces.iter().flatten().any(|i| *i >= vertex_count) {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let ids = self
+            .positions
+            .into_iter()
+            .map(|p| mesh.add_vertex(p))
+            .collect_vec();
+        let faces = self
+            .faces
+            .into_iter()
+            .map(|f| f.into_iter().map(|i| ids[i]).collect_vec())
+            .collect_vec();
+        mesh.add_faces(&faces)?;
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::vec3;
+
+    #[test]
+    fn build_cube() -> SMeshResult<()> {
+        let positions = (0..8)
+            .map(|i| vec3((i & 1) as f32, ((i >> 1) & 1) as f32, (i >> 2) as f32))
+            .collect_vec();
+        let faces = vec![
+            vec![0, 2, 3, 1],
+            vec![4, 5, 7, 6],
+            vec![0, 1, 5, 4],
+            vec![2, 6, 7, 3],
+            vec![0, 4, 6, 2],
+            vec![1, 3, 7, 5],
+        ];
+        let mesh = MeshBuilder::from_polygons(positions, faces).build()?;
+        assert_eq!(mesh.vertices().len(), 8);
+        assert_eq!(mesh.faces().len(), 6);
+        assert_eq!(mesh.halfedges().len(), 24);
+        check_connectivity(&mesh)?;
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!((mesh.volume()? - 1.0).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn build_invalid() {
+        let mut builder = MeshBuilder::new();
+        let v0 = builder.add_vertex(Vec3::ZERO);
+        let v1 = builder.add_vertex(Vec3::X);
+        builder.add_face(vec![v0, v1, 2]);
+        assert_eq!(builder.build().err(), Some(SMeshError::DefaultError));
+
+        // the same edge used twice in the same direction
+        let mut builder = MeshBuilder::new();
+        let v = (0..4)
+            .map(|i| builder.add_vertex(Vec3::splat(i as f32)))
+            .collect_vec();
+        builder.add_face(vec![v[0], v[1], v[2]]);
+        builder.add_face(vec![v[0], v[1], v[3]]);
+        assert_eq!(builder.build().err(), Some(SMeshError::TopologyError));
+    }
+}
@@ -0,0 +1,413 @@
+use glam::{vec3, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+const LEAF_SIZE: usize = 4;
+
+/// Triangle with its plane and bounds
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Triangle {
+    pub p: [Vec3; 3],
+    pub n: Vec3,
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Triangle {
+    pub fn new(p: [Vec3; 3]) -> Self {
+        Triangle {
+            p,
+            n: (p[1] - p[0]).cross(p[2] - p[0]).normalize_or_zero(),
+            min: p[0].min(p[1]).min(p[2]),
+            max: p[0].max(p[1]).max(p[2]),
+        }
+    }
+
+    pub fn signed_distance(&self, x: Vec3) -> f32 {
+        self.n.dot(x - self.p[0])
+    }
+
+    pub fn bounds_overlap(&self, min: Vec3, max: Vec3, eps: f32) -> bool {
+        self.min.cmple(max + eps).all() && self.max.cmpge(min - eps).all()
+    }
+
+    /// Distance along the ray to the triangle and the barycentric coordinates
+    /// of the hit (Möller–Trumbore)
+    pub fn intersect_ray(&self, origin: Vec3, dir: Vec3) -> Option<(f32, Vec3)> {
+        let e1 = self.p[1] - self.p[0];
+        let e2 = self.p[2] - self.p[0];
+        let pv = dir.cross(e2);
+        let det = e1.dot(pv);
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        let tv = origin - self.p[0];
+        let u = tv.dot(pv) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let qv = tv.cross(e1);
+        let v = dir.dot(qv) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = e2.dot(qv) * inv_det;
+        Some((t, vec3(1.0 - u - v, u, v)))
+    }
+
+    /// Closest point on the triangle to `x` (Ericson, Real-Time Collision Detection 5.1.5)
+    pub fn closest_point(&self, x: Vec3) -> Vec3 {
+        let [a, b, c] = self.p;
+        let ab = b - a;
+        let ac = c - a;
+        let ap = x - a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+        let bp = x - b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+        let cp = x - c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    /// Index of the first child node for inner nodes, first triangle for leaves
+    first: usize,
+    /// Number of triangles, 0 for inner nodes
+    count: usize,
+}
+
+/// Closest intersection of a ray with a mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub face: FaceId,
+    pub position: Vec3,
+    /// Barycentric coordinates of the hit in the triangle (v0, vi, vi+1) of the face's
+    /// triangle fan, which are the face's vertices for triangles
+    pub barycentrics: Vec3,
+    /// Distance along the ray, in multiples of the ray direction's length
+    pub distance: f32,
+}
+
+/// Bounding volume hierarchy over the (fan triangulated) faces of a mesh, to speed up
+/// repeated spatial queries. The hierarchy does not track changes of the mesh and
+/// needs to be rebuilt after the mesh was modified.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<(FaceId, Triangle)>,
+}
+
+impl Bvh {
+    /// Build the hierarchy for the current state of the mesh
+    pub fn new(mesh: &SMesh) -> Self {
+        let triangles = mesh
+            .faces()
+            .keys()
+            .flat_map(|f| {
+                let p = f.vertices(mesh).map(|v| mesh.positions[v]).collect_vec();
+                (1..p.len().saturating_sub(1))
+                    .map(|i| (f, Triangle::new([p[0], p[i], p[i + 1]])))
+                    .collect_vec()
+            })
+            .collect_vec();
+        let mut bvh = Bvh {
+            nodes: vec![],
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.nodes.push(BvhNode {
+                min: Vec3::ZERO,
+                max: Vec3::ZERO,
+                first: 0,
+                count: 0,
+            });
+            bvh.build(0, 0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    fn build(&mut self, node: usize, start: usize, end: usize) {
+        let triangles = &mut self.triangles[start..end];
+        let (min, max) = triangles
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), (_, t)| {
+                (min.min(t.min), max.max(t.max))
+            });
+        self.nodes[node].min = min;
+        self.nodes[node].max = max;
+        if triangles.len() <= LEAF_SIZE {
+            self.nodes[node].first = start;
+            self.nodes[node].count = triangles.len();
+            return;
+        }
+        // median split along the largest axis of the centroid bounds
+        let centroid = |t: &Triangle| t.min + t.max;
+        let (cmin, cmax) = triangles
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), (_, t)| {
+                (min.min(centroid(t)), max.max(centroid(t)))
+            });
+        let extent = cmax - cmin;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        let mid = triangles.len() / 2;
+        triangles.select_nth_unstable_by(mid, |(_, a), (_, b)| {
+            centroid(a)[axis].total_cmp(&centroid(b)[axis])
+        });
+        let left = self.nodes.len();
+        for _ in 0..2 {
+            self.nodes.push(BvhNode {
+                min: Vec3::ZERO,
+                max: Vec3::ZERO,
+                first: 0,
+                count: 0,
+            });
+        }
+        self.nodes[node].first = left;
+        self.build(left, start, start + mid);
+        self.build(left + 1, start + mid, end);
+    }
+
+    /// Closest point on the mesh surface to `p`, with the face it lies on
+    pub fn closest_point(&self, p: Vec3) -> Option<(FaceId, Vec3)> {
+        let mut best: Option<(FaceId, Vec3, f32)> = None;
+        let mut stack = vec![];
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let box_distance = (node.min - p).max(p - node.max).max(Vec3::ZERO);
+            if best.is_some_and(|(_, _, d)| box_distance.length_squared() > d) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+                continue;
+            }
+            for (f, t) in &self.triangles[node.first..node.first + node.count] {
+                let q = t.closest_point(p);
+                let d = q.distance_squared(p);
+                if best.is_none_or(|(_, _, best)| d < best) {
+                    best = Some((*f, q, d));
+                }
+            }
+        }
+        best.map(|(f, q, _)| (f, q))
+    }
+
+    /// Call `hit` for every triangle the ray intersects in front of its origin, with
+    /// the face, the distance along the ray and the barycentric coordinates of the hit
+    pub(crate) fn intersect_ray(
+        &self,
+        origin: Vec3,
+        dir: Vec3,
+        mut hit: impl FnMut(FaceId, f32, Vec3),
+    ) {
+        let inv_dir = dir.recip();
+        let mut stack = vec![];
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let t0 = (node.min - origin) * inv_dir;
+            let t1 = (node.max - origin) * inv_dir;
+            let t_min = t0.min(t1).max_element();
+            let t_max = t0.max(t1).min_element();
+            if t_max < 0.0 || t_min > t_max {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+                continue;
+            }
+            for (f, t) in &self.triangles[node.first..node.first + node.count] {
+                if let Some((d, bary)) = t.intersect_ray(origin, dir) {
+                    if d >= 0.0 {
+                        hit(*f, d, bary);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Closest intersection of the ray with the mesh in front of the ray origin
+    pub fn raycast(&self, origin: Vec3, dir: Vec3) -> Option<RayHit> {
+        let mut closest: Option<RayHit> = None;
+        self.intersect_ray(origin, dir, |face, distance, barycentrics| {
+            if closest.is_none_or(|c| distance < c.distance) {
+                closest = Some(RayHit {
+                    face,
+                    position: origin + dir * distance,
+                    barycentrics,
+                    distance,
+                });
+            }
+        });
+        closest
+    }
+
+    /// Whether the point lies inside of the mesh, which needs to be closed.
+    /// Uses a ray parity test, taking the majority vote of multiple rays
+    /// to be less sensitive to rays that graze edges.
+    pub fn contains(&self, p: Vec3) -> bool {
+        let dirs = [
+            vec3(0.5773, 0.5774, 0.5775),
+            vec3(-0.2672, 0.8018, -0.5345),
+            vec3(0.8729, -0.2182, 0.4364),
+        ];
+        let inside = dirs
+            .iter()
+            .filter(|dir| {
+                let mut hits = 0;
+                self.intersect_ray(p, **dir, |_, d, _| {
+                    if d > 0.0 {
+                        hits += 1;
+                    }
+                });
+                hits % 2 == 1
+            })
+            .count();
+        inside >= 2
+    }
+}
+
+impl SMesh {
+    /// Closest intersection of the ray with the mesh in front of the ray origin.
+    /// This builds a [`Bvh`] on every call, use [`Bvh::raycast`] directly to cast
+    /// multiple rays against an unchanged mesh.
+    pub fn raycast(&self, origin: Vec3, dir: Vec3) -> Option<RayHit> {
+        Bvh::new(self).raycast(origin, dir)
+    }
+
+    /// Whether the point lies inside of the mesh, see [`Bvh::contains`].
+    /// Fails with a topology error if the mesh is not closed.
+    /// This builds a [`Bvh`] on every call, use [`Bvh::contains`] directly to
+    /// test multiple points against an unchanged mesh.
+    pub fn contains_point(&self, p: Vec3) -> SMeshResult<bool> {
+        if self.halfedges().keys().any(|h| h.is_boundary(self)) {
+            bail!(TopologyError);
+        }
+        Ok(Bvh::new(self).contains(p))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+
+    #[test]
+    fn closest_point() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 1.0)?;
+        let bvh = Bvh::new(&mesh);
+        let (_, q) = bvh.closest_point(vec3(2.0, 0.1, 0.2)).unwrap();
+        assert!(q.distance(vec3(0.5, 0.1, 0.2)) < 1e-5);
+        let (_, q) = bvh.closest_point(vec3(2.0, 2.0, 2.0)).unwrap();
+        assert!(q.distance(Vec3::splat(0.5)) < 1e-5);
+        let (_, q) = bvh.closest_point(vec3(0.0, 0.4, 0.0)).unwrap();
+        assert!(q.distance(vec3(0.0, 0.5, 0.0)) < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn raycast() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 1.0)?;
+        let hit = mesh.raycast(vec3(0.1, 0.2, 3.0), Vec3::NEG_Z).unwrap();
+        assert!(hit.position.distance(vec3(0.1, 0.2, 0.5)) < 1e-5);
+        assert!((hit.distance - 2.5).abs() < 1e-5);
+        assert!(hit.face.normal(&mesh)?.distance(Vec3::Z) < 1e-5);
+        let face_points = hit
+            .face
+            .vertices(&mesh)
+            .map(|v| mesh.positions[v])
+            .collect_vec();
+        let b = hit.barycentrics;
+        assert!((b.x + b.y + b.z - 1.0).abs() < 1e-5);
+        let i = (1..face_points.len() - 1).find(|i| {
+            let p = face_points[0] * b.x + face_points[*i] * b.y + face_points[i + 1] * b.z;
+            p.distance(hit.position) < 1e-5
+        });
+        assert!(i.is_some());
+
+        // from the inside
+        let hit = mesh.raycast(Vec3::ZERO, vec3(0.0, 2.0, 0.0)).unwrap();
+        assert!((hit.distance - 0.25).abs() < 1e-5);
+        assert!(mesh.raycast(vec3(0.0, 0.0, 3.0), Vec3::Z).is_none());
+        assert!(mesh.raycast(vec3(2.0, 0.0, 3.0), Vec3::NEG_Z).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn contains() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 1.0)?;
+        let bvh = Bvh::new(&mesh);
+        assert!(bvh.contains(Vec3::ZERO));
+        assert!(bvh.contains(vec3(0.4, -0.4, 0.1)));
+        assert!(!bvh.contains(vec3(0.6, 0.0, 0.0)));
+        assert!(!bvh.contains(vec3(-3.0, 2.0, 1.0)));
+        // points close to the surface
+        assert!(bvh.contains(vec3(0.0, 0.0, 0.4999)));
+        assert!(!bvh.contains(vec3(0.0, 0.0, 0.5001)));
+        Ok(())
+    }
+
+    #[test]
+    fn contains_point() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let inner = cube(Vec3::ZERO, 0.5)?;
+        assert!(mesh.contains_point(vec3(0.3, 0.3, 0.3))?);
+        // hollow cube with an inner shell
+        mesh = mesh.boolean(&inner, BooleanOp::Difference)?;
+        assert!(mesh.contains_point(vec3(0.3, 0.3, 0.3))?);
+        assert!(!mesh.contains_point(Vec3::ZERO)?);
+
+        let face = mesh.faces().keys().next().unwrap();
+        mesh.delete_face(face)?;
+        assert_eq!(
+            mesh.contains_point(vec3(0.3, 0.3, 0.3)),
+            Err(SMeshError::TopologyError)
+        );
+        Ok(())
+    }
+}
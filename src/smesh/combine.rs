@@ -0,0 +1,230 @@
+use std::any::TypeId;
+
+use slotmap::{Key, KeyData, SecondaryMap};
+
+use crate::smesh::*;
+
+/// Ids of the elements copied by [`SMesh::combine_with`], by their id in the other mesh
+#[derive(Debug, Clone, Default)]
+pub struct CombineRemap {
+    pub vertices: SecondaryMap<VertexId, VertexId>,
+    pub halfedges: SecondaryMap<HalfedgeId, HalfedgeId>,
+    pub faces: SecondaryMap<FaceId, FaceId>,
+}
+
+/// Insert the entries of `other` into `target` with remapped keys,
+/// creating `target` if `other` exists
+fn extend_remapped<K: Key, T: Copy>(
+    target: &mut Option<SecondaryMap<K, T>>,
+    other: &Option<SecondaryMap<K, T>>,
+    remap: &SecondaryMap<K, K>,
+) {
+    let Some(other) = other else {
+        return;
+    };
+    let target = target.get_or_insert_with(SecondaryMap::new);
+    for (key, value) in other {
+        if let Some(key) = remap.get(key) {
+            target.insert(*key, *value);
+        }
+    }
+}
+
+impl SMesh {
+    /// Copy all elements of `other` into this mesh, together with the built-in and
+    /// custom attributes. The copy is not connected to the existing elements.
+    /// Returns the ids of the copied elements.
+    /// ```
+    /// use glam::vec3;
+    /// use smesh::prelude::*;
+    ///
+    /// let mut a = SMesh::new();
+    /// let v0 = a.add_vertex(vec3(0.0, 0.0, 0.0));
+    /// let v1 = a.add_vertex(vec3(1.0, 0.0, 0.0));
+    /// let v2 = a.add_vertex(vec3(0.0, 1.0, 0.0));
+    /// a.add_triangle(v0, v1, v2).unwrap();
+    /// let mut b = a.clone();
+    /// let remap = b.combine_with(&a);
+    /// assert_eq!(b.faces().len(), 2);
+    /// assert_eq!(b.positions[remap.vertices[v1]], vec3(1.0, 0.0, 0.0));
+    /// ```
+    pub fn combine_with(&mut self, other: &SMesh) -> CombineRemap {
+        let mut remap = CombineRemap::default();
+        for v in other.vertices().keys() {
+            remap
+                .vertices
+                .insert(v, self.vertices_mut().insert(Vertex::default()));
+        }
+        for h in other.halfedges().keys() {
+            remap
+                .halfedges
+                .insert(h, self.connectivity.halfedges.insert(Halfedge::default()));
+        }
+        for f in other.faces().keys() {
+            remap
+                .faces
+                .insert(f, self.faces_mut().insert(Face::default()));
+        }
+
+        // connectivity
+        let he = |h: Option<HalfedgeId>| h.and_then(|h| remap.halfedges.get(h).copied());
+        for (v, vertex) in other.vertices() {
+            self.connectivity.vertices[remap.vertices[v]].halfedge = he(vertex.halfedge);
+        }
+        for (h, halfedge) in other.halfedges() {
+            self.connectivity.halfedges[remap.halfedges[h]] = Halfedge {
+                vertex: remap.vertices[halfedge.vertex],
+                face: halfedge.face.and_then(|f| remap.faces.get(f).copied()),
+                opposite: he(halfedge.opposite),
+                prev: he(halfedge.prev),
+                next: he(halfedge.next),
+            };
+        }
+        for (f, face) in other.faces() {
+            self.connectivity.faces[remap.faces[f]].halfedge = he(face.halfedge);
+        }
+        if self.has_edge_index() {
+            self.build_edge_index();
+        }
+
+        // attributes
+        for (v, p) in &other.positions {
+            if let Some(v) = remap.vertices.get(v) {
+                self.positions.insert(*v, *p);
+            }
+        }
+        extend_remapped(
+            &mut self.vertex_normals,
+            &other.vertex_normals,
+            &remap.vertices,
+        );
+        extend_remapped(
+            &mut self.mean_curvature,
+            &other.mean_curvature,
+            &remap.vertices,
+        );
+        extend_remapped(
+            &mut self.gaussian_curvature,
+            &other.gaussian_curvature,
+            &remap.vertices,
+        );
+        extend_remapped(&mut self.uvs, &other.uvs, &remap.halfedges);
+        extend_remapped(
+            &mut self.halfedge_normals,
+            &other.halfedge_normals,
+            &remap.halfedges,
+        );
+        extend_remapped(
+            &mut self.edge_creases,
+            &other.edge_creases,
+            &remap.halfedges,
+        );
+        extend_remapped(&mut self.sharp_edges, &other.sharp_edges, &remap.halfedges);
+        extend_remapped(&mut self.seams, &other.seams, &remap.halfedges);
+        extend_remapped(&mut self.face_normals, &other.face_normals, &remap.faces);
+        extend_remapped(
+            &mut self.smoothing_groups,
+            &other.smoothing_groups,
+            &remap.faces,
+        );
+        extend_remapped(
+            &mut self.material_indices,
+            &other.material_indices,
+            &remap.faces,
+        );
+        for (name, map) in &other.vertex_attributes {
+            self.vertex_attributes
+                .entry(name.clone())
+                .or_default()
+                .extend_remapped(map, &remap.vertices);
+        }
+        for (name, map) in &other.edge_attributes {
+            self.edge_attributes
+                .entry(name.clone())
+                .or_default()
+                .extend_remapped(map, &remap.halfedges);
+        }
+        for (name, map) in &other.face_attributes {
+            self.face_attributes
+                .entry(name.clone())
+                .or_default()
+                .extend_remapped(map, &remap.faces);
+        }
+        self.typed_attributes
+            .extend_remapped(&other.typed_attributes, |element, key: KeyData| {
+                if element == TypeId::of::<VertexId>() {
+                    Some(remap.vertices.get(key.into())?.data())
+                } else if element == TypeId::of::<HalfedgeId>() {
+                    Some(remap.halfedges.get(key.into())?.data())
+                } else if element == TypeId::of::<FaceId>() {
+                    Some(remap.faces.get(key.into())?.data())
+                } else {
+                    None
+                }
+            });
+        self.dirty_vertices.extend(
+            other
+                .dirty_vertices
+                .iter()
+                .filter_map(|v| remap.vertices.get(*v)),
+        );
+
+        for v in remap.vertices.values() {
+            self.connectivity.notify(MeshChange::VertexAdded(*v));
+        }
+        for h in remap.halfedges.values() {
+            self.connectivity.notify(MeshChange::HalfedgeAdded(*h));
+        }
+        for f in remap.faces.values() {
+            self.connectivity.notify(MeshChange::FaceAdded(*f));
+        }
+        remap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::attribute::CustomAttributeMapOps;
+    use crate::test_utils::{check_connectivity, cube};
+
+    #[test]
+    fn combine_copies_attributes() -> SMeshResult<()> {
+        let mut a = cube(Vec3::ZERO, 1.0)?;
+        let mut b = cube(Vec3::X * 3.0, 1.0)?;
+        let v = b.vertices().keys().next().unwrap();
+        let f = b.faces().keys().next().unwrap();
+        let h = f.halfedge().run(&b)?;
+        b.add_attribute_map::<VertexId>("weight")
+            .unwrap()
+            .insert(v, 0.5);
+        b.add_typed_attribute::<FaceId, &'static str>("material")
+            .insert(f, "metal");
+        b.set_edge_crease(h, 1.0)?;
+        b.set_smoothing_group(f, 2)?;
+        b.uvs = Some(SecondaryMap::new());
+        b.uvs.as_mut().unwrap().insert(h, Vec2::ONE);
+
+        let remap = a.combine_with(&b);
+        check_connectivity(&a)?;
+        assert_eq!(a.vertices().len(), 16);
+        assert_eq!(a.faces().len(), 12);
+        assert_eq!(remap.halfedges.len(), b.halfedges().len());
+        assert_eq!(a.positions[remap.vertices[v]], b.positions[v]);
+        assert_eq!(remap.faces[f].halfedge().run(&a)?, remap.halfedges[h]);
+
+        let weight: Option<f32> = a
+            .attribute::<VertexId>("weight")
+            .unwrap()
+            .get(remap.vertices[v]);
+        assert_eq!(weight, Some(0.5));
+        let materials = a
+            .typed_attribute::<FaceId, &'static str>("material")
+            .unwrap();
+        assert_eq!(materials[remap.faces[f]], "metal");
+        assert_eq!(a.edge_crease(remap.halfedges[h]), 1.0);
+        assert_eq!(a.smoothing_group(remap.faces[f]), Some(2));
+        assert_eq!(a.uvs.as_ref().unwrap()[remap.halfedges[h]], Vec2::ONE);
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+use glam::{Quat, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Position and orientation at a point of a curve, with `binormal = tangent x normal`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub position: Vec3,
+    pub tangent: Vec3,
+    pub normal: Vec3,
+    pub binormal: Vec3,
+}
+
+/// Curve of straight segments between points, e.g. the path of a [`Tube`]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline {
+    pub points: Vec<Vec3>,
+    /// Whether the last point connects back to the first
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    pub fn new_closed(points: Vec<Vec3>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// Start and end of every segment, including the closing one
+    pub fn segments(&self) -> impl Iterator<Item = (Vec3, Vec3)> + '_ {
+        let closing = self
+            .closed
+            .then(|| (self.points.last().copied(), self.points.first().copied()));
+        self.points.iter().copied().tuple_windows().chain(
+            closing
+                .into_iter()
+                .filter_map(|(last, first)| Some((last?, first?))),
+        )
+    }
+
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(a, b)| a.distance(b)).sum()
+    }
+
+    /// `count` points evenly spaced by arc length. Open polylines keep their end points,
+    /// closed ones keep their first point.
+    /// Fails with less than two points or a count below two, or if the length is zero.
+    pub fn resample(&self, count: usize) -> SMeshResult<Polyline> {
+        let length = self.length();
+        if self.points.len() < 2 || count < 2 || length <= 0.0 {
+            bail!(DefaultError);
+        }
+        let spacing = if self.closed {
+            length / count as f32
+        } else {
+            length / (count - 1) as f32
+        };
+        let segments = self.segments().collect_vec();
+        let mut points = Vec::with_capacity(count);
+        let (mut segment, mut start) = (0, 0.0);
+        for i in 0..count {
+            let distance = i as f32 * spacing;
+            let (a, b) = loop {
+                let (a, b) = segments[segment];
+                if start + a.distance(b) >= distance || segment == segments.len() - 1 {
+                    break (a, b);
+                }
+                start += a.distance(b);
+                segment += 1;
+            };
+            let t = ((distance - start) / a.distance(b).max(f32::EPSILON)).clamp(0.0, 1.0);
+            points.push(a.lerp(b, t));
+        }
+        if !self.closed {
+            points[count - 1] = self.points[self.points.len() - 1];
+        }
+        Ok(Polyline {
+            points,
+            closed: self.closed,
+        })
+    }
+
+    /// Frame at every point, with the tangent along the neighbouring segments.
+    /// The normals are parallel transported, so they rotate as little as possible along
+    /// the curve. For closed polylines the remaining twist between the last and the first
+    /// frame is spread over all frames.
+    /// Fails with less than two points or repeated consecutive points.
+    pub fn frames(&self) -> SMeshResult<Vec<Frame>> {
+        let n = self.points.len();
+        if n < 2
+            || self
+                .segments()
+                .any(|(a, b)| a.distance_squared(b) <= f32::EPSILON)
+        {
+            bail!(DefaultError);
+        }
+        let point = |i: isize| {
+            if self.closed {
+                self.points[i.rem_euclid(n as isize) as usize]
+            } else {
+                self.points[i.clamp(0, n as isize - 1) as usize]
+            }
+        };
+        let tangents = (0..n as isize)
+            .map(|i| {
+                let tangent = point(i + 1) - point(i - 1);
+                // a closed curve doubling back on itself
+                tangent
+                    .try_normalize()
+                    .unwrap_or_else(|| (point(i + 1) - point(i)).normalize())
+            })
+            .collect_vec();
+
+        let mut normals = vec![tangents[0].any_orthonormal_vector()];
+        for i in 1..n {
+            let rotation = Quat::from_rotation_arc(tangents[i - 1], tangents[i]);
+            normals.push(rotation * normals[i - 1]);
+        }
+        if self.closed {
+            let rotation = Quat::from_rotation_arc(tangents[n - 1], tangents[0]);
+            let last = rotation * normals[n - 1];
+            let twist = normals[0]
+                .cross(last)
+                .dot(tangents[0])
+                .atan2(normals[0].dot(last));
+            for (i, normal) in normals.iter_mut().enumerate() {
+                let angle = -twist * i as f32 / n as f32;
+                *normal = Quat::from_axis_angle(tangents[i], angle) * *normal;
+            }
+        }
+
+        Ok((0..n)
+            .map(|i| Frame {
+                position: self.points[i],
+                tangent: tangents[i],
+                normal: normals[i],
+                binormal: tangents[i].cross(normals[i]),
+            })
+            .collect_vec())
+    }
+}
+
+/// Smooth curve through all of its control points, with the tangent at a point
+/// parallel to the line between its neighbours.
+/// Open curves mirror the second and second last point to get the tangents at their ends.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatmullRom {
+    pub points: Vec<Vec3>,
+    /// Whether the curve continues from the last point back to the first
+    pub closed: bool,
+}
+
+impl CatmullRom {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    pub fn new_closed(points: Vec<Vec3>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// Number of curve pieces between the control points
+    pub fn segment_count(&self) -> usize {
+        match (self.points.len(), self.closed) {
+            (0 | 1, _) => 0,
+            (n, true) => n,
+            (n, false) => n - 1,
+        }
+    }
+
+    /// Point on the piece of the curve starting at the control point `segment`,
+    /// with `t` going from 0 to 1. `None` if there is no such piece.
+    pub fn sample(&self, segment: usize, t: f32) -> Option<Vec3> {
+        if segment >= self.segment_count() {
+            return None;
+        }
+        let n = self.points.len() as isize;
+        let point = |i: isize| {
+            if self.closed {
+                self.points[i.rem_euclid(n) as usize]
+            } else if i < 0 {
+                2.0 * self.points[0] - self.points[1]
+            } else if i >= n {
+                2.0 * self.points[n as usize - 1] - self.points[n as usize - 2]
+            } else {
+                self.points[i as usize]
+            }
+        };
+        let i = segment as isize;
+        let [p0, p1, p2, p3] = [point(i - 1), point(i), point(i + 1), point(i + 2)];
+        let (t2, t3) = (t * t, t * t * t);
+        Some(
+            0.5 * (2.0 * p1
+                + (p2 - p0) * t
+                + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+        )
+    }
+
+    /// Sample every piece of the curve `steps` times.
+    /// Fails with less than two control points or without steps.
+    pub fn to_polyline(&self, steps: usize) -> SMeshResult<Polyline> {
+        if self.segment_count() == 0 || steps == 0 {
+            bail!(DefaultError);
+        }
+        let mut points = (0..self.segment_count())
+            .flat_map(|segment| {
+                (0..steps).filter_map(move |i| self.sample(segment, i as f32 / steps as f32))
+            })
+            .collect_vec();
+        if !self.closed {
+            points.push(self.points[self.points.len() - 1]);
+        }
+        Ok(Polyline {
+            points,
+            closed: self.closed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::vec3;
+    use std::f32::consts::TAU;
+
+    fn circle(count: usize) -> Vec<Vec3> {
+        (0..count)
+            .map(|i| {
+                let angle = i as f32 / count as f32 * TAU;
+                vec3(angle.cos(), 0.0, -angle.sin())
+            })
+            .collect_vec()
+    }
+
+    #[test]
+    fn resample_polyline() -> SMeshResult<()> {
+        let line = Polyline::new(vec![Vec3::ZERO, Vec3::X, vec3(1.0, 3.0, 0.0)]);
+        assert_eq!(line.length(), 4.0);
+        let resampled = line.resample(5)?;
+        let expected = [
+            Vec3::ZERO,
+            Vec3::X,
+            vec3(1.0, 1.0, 0.0),
+            vec3(1.0, 2.0, 0.0),
+            vec3(1.0, 3.0, 0.0),
+        ];
+        for (p, e) in resampled.points.iter().zip(expected) {
+            assert!(p.distance(e) < 1e-5);
+        }
+
+        let square = Polyline::new_closed(vec![Vec3::ZERO, Vec3::X, vec3(1.0, 1.0, 0.0), Vec3::Y]);
+        assert_eq!(square.length(), 4.0);
+        let resampled = square.resample(8)?;
+        assert_eq!(resampled.points.len(), 8);
+        assert!(resampled.points[7].distance(vec3(0.0, 0.5, 0.0)) < 1e-5);
+        assert!(Polyline::new(vec![Vec3::ZERO]).resample(4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn polyline_frames() -> SMeshResult<()> {
+        let frames = Polyline::new_closed(circle(32)).frames()?;
+        for frame in &frames {
+            assert!(frame.tangent.dot(frame.normal).abs() < 1e-5);
+            assert!((frame.binormal.length() - 1.0).abs() < 1e-5);
+        }
+        // a planar loop has no twist, the normals keep their angle to the plane
+        let first = frames[0].normal.dot(Vec3::Y);
+        assert!(frames
+            .iter()
+            .all(|frame| (frame.normal.dot(Vec3::Y) - first).abs() < 1e-3));
+
+        let repeated = Polyline::new(vec![Vec3::ZERO, Vec3::ZERO, Vec3::X]);
+        assert!(repeated.frames().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn catmull_rom() -> SMeshResult<()> {
+        let points = vec![
+            Vec3::ZERO,
+            Vec3::X,
+            vec3(2.0, 1.0, 0.0),
+            vec3(3.0, 1.0, 1.0),
+        ];
+        let curve = CatmullRom::new(points.clone());
+        // the curve passes through the control points
+        for (i, p) in points.iter().enumerate().take(3) {
+            assert!(curve.sample(i, 0.0).unwrap().distance(*p) < 1e-5);
+        }
+        assert!(curve.sample(2, 1.0).unwrap().distance(points[3]) < 1e-5);
+        assert_eq!(curve.sample(3, 0.0), None);
+
+        // collinear points stay on the line
+        let line = CatmullRom::new(vec![Vec3::ZERO, Vec3::X, Vec3::X * 2.0]);
+        assert!(line.sample(0, 0.5).unwrap().distance(Vec3::X * 0.5) < 1e-5);
+
+        let polyline = CatmullRom::new_closed(circle(8)).to_polyline(4)?;
+        assert_eq!(polyline.points.len(), 32);
+        assert!(polyline
+            .points
+            .iter()
+            .all(|p| (p.length() - 1.0).abs() < 0.01));
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::ops::Add;
+
+use glam::{DMat3, DMat4, DVec3, Vec3};
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Weight of the constraint planes that keep boundary edges in place
+const BOUNDARY_WEIGHT: f64 = 100.0;
+
+/// Error quadric of a vertex, the sum of the squared distances to a set of planes
+#[derive(Debug, Clone, Copy)]
+struct Quadric(DMat4);
+
+impl Default for Quadric {
+    fn default() -> Self {
+        Quadric(DMat4::ZERO)
+    }
+}
+
+impl Add for Quadric {
+    type Output = Quadric;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Quadric(self.0 + rhs.0)
+    }
+}
+
+impl Quadric {
+    /// Quadric of the plane with unit normal `n` through `point`
+    fn from_plane(n: DVec3, point: DVec3, weight: f64) -> Self {
+        let p = n.extend(-n.dot(point));
+        Quadric(DMat4::from_cols(p * p.x, p * p.y, p * p.z, p * p.w) * weight)
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        let v = p.extend(1.0);
+        v.dot(self.0 * v)
+    }
+
+    /// Position with the minimal error, if the quadric is not singular
+    fn optimum(&self) -> Option<DVec3> {
+        let m = self.0;
+        let a = DMat3::from_cols(
+            m.x_axis.truncate(),
+            m.y_axis.truncate(),
+            m.z_axis.truncate(),
+        );
+        if a.determinant().abs() < 1e-12 {
+            return None;
+        }
+        Some(a.inverse() * -m.w_axis.truncate())
+    }
+}
+
+/// Possible collapse of the edge of `h` to `position`
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    cost: f64,
+    position: Vec3,
+    h: HalfedgeId,
+    v0: VertexId,
+    v1: VertexId,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    // reversed, so the binary heap pops the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl SMesh {
+    /// Reduce the amount of faces to (at most) `target_face_count` by collapsing edges
+    /// in the order of their quadric error (Garland & Heckbert). The remaining vertex of
+    /// each collapse is placed at the position minimizing the error, and the halfedge
+    /// uvs around it are interpolated accordingly. Boundary edges are constrained to
+    /// stay in place, and collapses that would flip faces are skipped.
+    /// This function is only valid for triangle meshes.
+    pub fn simplify(&mut self, target_face_count: usize) -> SMeshResult<()> {
+        if !self.is_triangle_mesh() {
+            bail!(UnsupportedOperation);
+        }
+        let mut quadrics = self.vertex_quadrics()?;
+        let mut versions: SecondaryMap<VertexId, u32> = SecondaryMap::new();
+        for v in self.vertices().keys() {
+            versions.insert(v, 0);
+        }
+
+        let mut heap = BinaryHeap::new();
+        for h in self.halfedges().keys() {
+            if h < h.opposite().run(self)? {
+                heap.push(self.collapse_candidate(h, &quadrics, &versions)?);
+            }
+        }
+
+        while self.faces().len() > target_face_count {
+            let Some(c) = heap.pop() else {
+                break;
+            };
+            let is_current = self.halfedges().contains_key(c.h)
+                && versions.get(c.v0) == Some(&c.versions.0)
+                && versions.get(c.v1) == Some(&c.versions.1)
+                && c.h.src_vert().run(self)? == c.v0
+                && c.h.dst_vert().run(self)? == c.v1;
+            if !is_current {
+                continue;
+            }
+            let o = c.h.opposite().run(self)?;
+            let Some(h) = [c.h, o].into_iter().find(|h| {
+                self.is_collapse_ok(*h).is_ok()
+                    && !self.collapse_flips_faces(*h, c.position).unwrap_or(true)
+            }) else {
+                continue;
+            };
+
+            let survivor = h.dst_vert().run(self)?;
+            self.collapse_to_position(h, c.position)?;
+            quadrics.insert(survivor, quadrics[c.v0] + quadrics[c.v1]);
+            versions[survivor] += 1;
+            for h in survivor.halfedges(self).collect_vec() {
+                heap.push(self.collapse_candidate(h, &quadrics, &versions)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn vertex_quadrics(&self) -> SMeshResult<SecondaryMap<VertexId, Quadric>> {
+        let mut quadrics: SecondaryMap<VertexId, Quadric> = SecondaryMap::new();
+        for v in self.vertices().keys() {
+            quadrics.insert(v, Quadric::default());
+        }
+        for f in self.faces().keys() {
+            let vertices = f.vertices(self).collect_vec();
+            let p = vertices
+                .iter()
+                .map(|v| self.positions[*v].as_dvec3())
+                .collect_vec();
+            let n = (p[1] - p[0]).cross(p[2] - p[0]);
+            let area = n.length() * 0.5;
+            if area <= f64::EPSILON {
+                continue;
+            }
+            let q = Quadric::from_plane(n.normalize(), p[0], area);
+            for v in vertices {
+                quadrics[v] = quadrics[v] + q;
+            }
+        }
+        // planes perpendicular to the boundary faces keep the boundary in place
+        for h in self.halfedges().keys() {
+            if !h.is_boundary(self) {
+                continue;
+            }
+            let v0 = h.src_vert().run(self)?;
+            let v1 = h.dst_vert().run(self)?;
+            let f = h.opposite().face().run(self)?;
+            let p0 = self.positions[v0].as_dvec3();
+            let edge = self.positions[v1].as_dvec3() - p0;
+            let n = edge.cross(f.normal(self)?.as_dvec3()).normalize_or_zero();
+            let q = Quadric::from_plane(n, p0, BOUNDARY_WEIGHT * edge.length_squared());
+            quadrics[v0] = quadrics[v0] + q;
+            quadrics[v1] = quadrics[v1] + q;
+        }
+        Ok(quadrics)
+    }
+
+    fn collapse_candidate(
+        &self,
+        h: HalfedgeId,
+        quadrics: &SecondaryMap<VertexId, Quadric>,
+        versions: &SecondaryMap<VertexId, u32>,
+    ) -> SMeshResult<Candidate> {
+        let v0 = h.src_vert().run(self)?;
+        let v1 = h.dst_vert().run(self)?;
+        let p0 = self.positions[v0].as_dvec3();
+        let p1 = self.positions[v1].as_dvec3();
+        let q = quadrics[v0] + quadrics[v1];
+
+        let mid = (p0 + p1) * 0.5;
+        let mut positions = vec![p0, p1, mid];
+        // a nearly singular quadric can place the optimum far away from the edge
+        if let Some(p) = q.optimum().filter(|p| p.distance(mid) <= p0.distance(p1)) {
+            positions.push(p);
+        }
+        let (cost, position) = positions
+            .into_iter()
+            .map(|p| (q.error(p), p))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap();
+        Ok(Candidate {
+            cost,
+            position: position.as_vec3(),
+            h,
+            v0,
+            v1,
+            versions: (versions[v0], versions[v1]),
+        })
+    }
+
+    /// Whether moving both vertices of the edge to `position` would flip one of the
+    /// remaining faces around them
+    fn collapse_flips_faces(&self, h: HalfedgeId, position: Vec3) -> SMeshResult<bool> {
+        let v0 = h.src_vert().run(self)?;
+        let v1 = h.dst_vert().run(self)?;
+        let removed = [h.face().run(self).ok(), h.opposite().face().run(self).ok()];
+        let faces: HashSet<FaceId> = v0.faces(self).chain(v1.faces(self)).collect();
+        for f in faces {
+            if removed.contains(&Some(f)) {
+                continue;
+            }
+            let vertices = f.vertices(self).collect_vec();
+            let before = vertices.iter().map(|v| self.positions[*v]).collect_vec();
+            let after = vertices
+                .iter()
+                .map(|v| {
+                    if *v == v0 || *v == v1 {
+                        position
+                    } else {
+                        self.positions[*v]
+                    }
+                })
+                .collect_vec();
+            let n_before = (before[1] - before[0]).cross(before[2] - before[0]);
+            let n_after = (after[1] - after[0]).cross(after[2] - after[0]);
+            if n_before.dot(n_after) <= 0.0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Collapse `h` and move the remaining vertex to `position`, interpolating the
+    /// attributes of the vertex and the uvs of the halfedges around it along the collapsed edge.
+    fn collapse_to_position(&mut self, h: HalfedgeId, position: Vec3) -> SMeshResult<()> {
+        let v0 = h.src_vert().run(self)?;
+        let v1 = h.dst_vert().run(self)?;
+        let p0 = self.positions[v0];
+        let p1 = self.positions[v1];
+        // parameter of the new position on the edge, 0 at v1 and 1 at v0
+        let edge = p0 - p1;
+        let t = if edge.length_squared() > 0.0 {
+            ((position - p1).dot(edge) / edge.length_squared()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let wedges = self.edge_uv_wedges(h)?;
+        self.interpolate_vertex_attributes(v1, v1, v0, t);
+
+        // the collapse moves the uvs at v0 to the ones at v1
+        self.collapse(h)?;
+        self.positions[v1] = position;
+
+        if let Some(uvs) = self.uvs.as_mut() {
+            for wedge in wedges {
+                let uv = wedge.uv1.lerp(wedge.uv0, t);
+                for corner in wedge.corners0.iter().chain(&wedge.corners1) {
+                    if let Some(corner_uv) = uvs.get_mut(*corner) {
+                        *corner_uv = uv;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{check_connectivity, triangulated_grid};
+    use glam::vec2;
+
+    #[test]
+    fn simplify_grid() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(8)?;
+        assert_eq!(mesh.faces().len(), 128);
+        mesh.simplify(32)?;
+        assert!(mesh.faces().len() <= 32);
+        assert!(mesh.is_triangle_mesh());
+        check_connectivity(&mesh)?;
+        for v in mesh.vertices().keys() {
+            let p = mesh.positions[v];
+            assert!(p.z.abs() < 1e-4);
+            assert!(p.x > -1e-4 && p.x < 8.0001 && p.y > -1e-4 && p.y < 8.0001);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn simplify_interpolates_uvs() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(6)?;
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            uvs.insert(h, vec2(p.x, p.y) / 6.0);
+        }
+        mesh.uvs = Some(uvs);
+        mesh.simplify(20)?;
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for h in mesh.halfedges().keys() {
+            if h.is_boundary(&mesh) {
+                continue;
+            }
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            assert!(uvs[h].distance(vec2(p.x, p.y) / 6.0) < 1e-3);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn simplify_requires_triangles() {
+        let mut mesh = SMesh::new();
+        let v0 = mesh.add_vertex(Vec3::ZERO);
+        let v1 = mesh.add_vertex(Vec3::X);
+        let v2 = mesh.add_vertex(Vec3::ONE);
+        let v3 = mesh.add_vertex(Vec3::Y);
+        mesh.add_quad(v0, v1, v2, v3).unwrap();
+        assert_eq!(mesh.simplify(0), Err(SMeshError::UnsupportedOperation));
+    }
+}
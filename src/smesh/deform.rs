@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+
+use glam::{UVec3, Vec2, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+use crate::smesh::random::SplitMix64;
+
+/// Grid of height values, sampled with bilinear filtering over the uv square
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heightmap {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row, the first row at v = 0 and the first column at u = 0
+    pub values: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Interpolated value at `uv`, clamped to the edges of the map.
+    /// Zero for an empty map.
+    pub fn sample(&self, uv: Vec2) -> f32 {
+        if self.width == 0 || self.height == 0 || self.values.len() < self.width * self.height {
+            return 0.0;
+        }
+        let max = Vec2::new((self.width - 1) as f32, (self.height - 1) as f32);
+        let p = uv.clamp(Vec2::ZERO, Vec2::ONE) * max;
+        let (x0, y0) = (p.x.floor() as usize, p.y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let t = p - p.floor();
+        let value = |x: usize, y: usize| self.values[y * self.width + x];
+        let bottom = value(x0, y0) + (value(x1, y0) - value(x0, y0)) * t.x;
+        let top = value(x0, y1) + (value(x1, y1) - value(x0, y1)) * t.x;
+        bottom + (top - bottom) * t.y
+    }
+}
+
+/// How the points of a [`Lattice`] deform the space inside of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LatticeInterpolation {
+    /// Trilinear interpolation of the eight points around a vertex, which only
+    /// affects the cells next to a moved point
+    Linear,
+    /// The points are the control points of a Bezier volume, every point affects
+    /// the whole lattice and the deformation is smooth
+    #[default]
+    Bezier,
+}
+
+/// Grid of control points spanning a box, deforming the vertices inside of the box
+/// when the points are moved away from their initial positions
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lattice {
+    pub bounds: (Vec3, Vec3),
+    /// Number of points along each axis
+    pub resolution: UVec3,
+    /// The control points, x varying fastest, then y, then z
+    pub points: Vec<Vec3>,
+    pub interpolation: LatticeInterpolation,
+}
+
+impl Lattice {
+    /// Lattice with its points evenly spaced over the `bounds` (min, max),
+    /// which leaves the vertices where they are.
+    /// Fails with less than two points along an axis or empty bounds.
+    pub fn new(bounds: (Vec3, Vec3), resolution: UVec3) -> SMeshResult<Self> {
+        let (min, max) = bounds;
+        if resolution.cmplt(UVec3::splat(2)).any() || max.cmple(min).any() {
+            bail!(DefaultError);
+        }
+        let step = (max - min) / (resolution - UVec3::ONE).as_vec3();
+        let mut points = vec![];
+        for z in 0..resolution.z {
+            for y in 0..resolution.y {
+                for x in 0..resolution.x {
+                    points.push(min + UVec3::new(x, y, z).as_vec3() * step);
+                }
+            }
+        }
+        Ok(Lattice {
+            bounds,
+            resolution,
+            points,
+            interpolation: LatticeInterpolation::default(),
+        })
+    }
+
+    pub fn with_interpolation(mut self, interpolation: LatticeInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Index into [`Lattice::points`] of the point at the grid coordinates
+    pub fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.resolution.x * (y + self.resolution.y * z)) as usize
+    }
+
+    /// Deformed position of `p`, `None` outside of the bounds
+    pub fn deform(&self, p: Vec3) -> Option<Vec3> {
+        let (min, max) = self.bounds;
+        if p.cmplt(min).any() || p.cmpgt(max).any() {
+            return None;
+        }
+        let s = (p - min) / (max - min);
+        let n = self.resolution;
+        match self.interpolation {
+            LatticeInterpolation::Linear => {
+                let g = s * (n - UVec3::ONE).as_vec3();
+                let cell = g.floor().as_uvec3().min(n - UVec3::splat(2));
+                let t = g - cell.as_vec3();
+                let mut result = Vec3::ZERO;
+                for corner in 0..8u32 {
+                    let offset = UVec3::new(corner & 1, corner >> 1 & 1, corner >> 2 & 1);
+                    let w = Vec3::select(offset.cmpeq(UVec3::ONE), t, Vec3::ONE - t);
+                    let c = cell + offset;
+                    result += self.points[self.index(c.x, c.y, c.z)] * w.x * w.y * w.z;
+                }
+                Some(result)
+            }
+            LatticeInterpolation::Bezier => {
+                let basis = |count: u32, t: f32| {
+                    (0..count).map(|i| bernstein(count - 1, i, t)).collect_vec()
+                };
+                let (bx, by, bz) = (basis(n.x, s.x), basis(n.y, s.y), basis(n.z, s.z));
+                let mut result = Vec3::ZERO;
+                for z in 0..n.z {
+                    for y in 0..n.y {
+                        for x in 0..n.x {
+                            let w = bx[x as usize] * by[y as usize] * bz[z as usize];
+                            result += self.points[self.index(x, y, z)] * w;
+                        }
+                    }
+                }
+                Some(result)
+            }
+        }
+    }
+}
+
+/// Bernstein polynomial `i` of the given degree
+fn bernstein(degree: u32, i: u32, t: f32) -> f32 {
+    let binomial = (0..i).fold(1.0, |b, k| b * (degree - k) as f32 / (k + 1) as f32);
+    binomial * t.powi(i as i32) * (1.0 - t).powi((degree - i) as i32)
+}
+
+impl SMesh {
+    /// Move the selected vertices along their normals by `amount`.
+    /// The normals and amounts are all computed before any vertex is moved,
+    /// so the closure sees the undeformed mesh.
+    pub fn displace<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        amount: impl Fn(VertexId, &SMesh) -> f32,
+    ) -> SMeshResult<()> {
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        let offsets = vertices
+            .iter()
+            .map(|v| {
+                if !self.vertices().contains_key(*v) {
+                    bail!(VertexNotFound, *v);
+                }
+                let normal = self.vertex_normal(*v, NormalWeighting::Angle)?;
+                Ok((*v, normal * amount(*v, self)))
+            })
+            .collect::<SMeshResult<Vec<_>>>()?;
+        for (v, offset) in offsets {
+            self.positions[v] += offset;
+            self.dirty_vertices.insert(v);
+        }
+        Ok(())
+    }
+
+    /// Move every selected vertex by a random offset of up to `amplitude` in any direction.
+    /// The same seed gives the same offsets for the same vertices.
+    pub fn jitter<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        amplitude: f32,
+        seed: u64,
+    ) -> SMeshResult<()> {
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        let mut rng = SplitMix64::new(seed);
+        for v in vertices.into_iter().sorted() {
+            let Some(p) = self.positions.get_mut(v) else {
+                bail!(VertexNotFound, v);
+            };
+            // uniform in the unit ball
+            let offset = loop {
+                let d = Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32()) * 2.0 - 1.0;
+                if d.length_squared() <= 1.0 {
+                    break d;
+                }
+            };
+            *p += offset * amplitude;
+            self.dirty_vertices.insert(v);
+        }
+        Ok(())
+    }
+
+    /// Move every selected vertex along its normal by a random distance
+    /// between `-amplitude` and `amplitude`, see [`SMesh::jitter`]
+    pub fn jitter_along_normals<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        amplitude: f32,
+        seed: u64,
+    ) -> SMeshResult<()> {
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        let mut rng = SplitMix64::new(seed);
+        let distances = vertices
+            .iter()
+            .copied()
+            .sorted()
+            .map(|v| (v, (rng.next_f32() * 2.0 - 1.0) * amplitude))
+            .collect::<HashMap<_, _>>();
+        self.displace(vertices, |v, _| distances[&v])
+    }
+
+    /// Move the selected vertices inside of the lattice bounds by the deformation of the
+    /// lattice, see [`Lattice::deform`]. Vertices outside of the bounds keep their position.
+    /// Fails if the number of points does not match the resolution of the lattice.
+    pub fn lattice_deform<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        lattice: &Lattice,
+    ) -> SMeshResult<()> {
+        let r = lattice.resolution;
+        if r.cmplt(UVec3::splat(2)).any() || lattice.points.len() != (r.x * r.y * r.z) as usize {
+            bail!(DefaultError);
+        }
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        for v in vertices {
+            let Some(p) = self.positions.get(v) else {
+                bail!(VertexNotFound, v);
+            };
+            if let Some(p) = lattice.deform(*p) {
+                self.positions[v] = p;
+                self.dirty_vertices.insert(v);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the selected vertices along their normals by the value of the `heightmap`
+    /// at their uv, times `scale`. Vertices with several uvs use the average.
+    /// Fails if the mesh has no uvs.
+    pub fn displace_with_heightmap<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        heightmap: &Heightmap,
+        scale: f32,
+    ) -> SMeshResult<()> {
+        if self.uvs.is_none() {
+            bail!(UnsupportedOperation);
+        }
+        self.displace(selection, |v, mesh| {
+            let uvs = mesh.uvs.as_ref().unwrap();
+            let corners = v
+                .halfedges(mesh)
+                .filter_map(|h| uvs.get(h).copied())
+                .collect_vec();
+            if corners.is_empty() {
+                return 0.0;
+            }
+            let uv = corners.iter().sum::<Vec2>() / corners.len() as f32;
+            heightmap.sample(uv) * scale
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, quad_grid};
+    use glam::vec3;
+
+    #[test]
+    fn displace() -> SMeshResult<()> {
+        let mut mesh = quad_grid(4)?;
+        let original = mesh.positions.clone();
+        let all = mesh.vertices().keys().collect_vec();
+        mesh.displace(all.clone(), |v, mesh| mesh.positions[v].x * 0.5)?;
+        for v in all {
+            let expected = original[v] + Vec3::Z * original[v].x * 0.5;
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+
+        // a cube grows evenly along its corner normals
+        let mut mesh = cube(Vec3::ZERO, 2.0)?;
+        let all = mesh.vertices().keys().collect_vec();
+        mesh.displace(all, |_, _| 3f32.sqrt())?;
+        assert!(mesh
+            .positions
+            .values()
+            .all(|p| (p.abs() - Vec3::splat(2.0)).length() < 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn displace_with_heightmap() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        let all = mesh.vertices().keys().collect_vec();
+        let heightmap = Heightmap {
+            width: 2,
+            height: 2,
+            values: vec![0.0, 1.0, 2.0, 3.0],
+        };
+        assert_eq!(
+            mesh.displace_with_heightmap(all.clone(), &heightmap, 1.0),
+            Err(SMeshError::UnsupportedOperation)
+        );
+
+        let faces = mesh.faces().keys().collect_vec();
+        mesh.planar_project_uvs(faces.clone(), Vec3::NEG_Z, Vec3::Y)?;
+        mesh.normalize_uvs(faces)?;
+        let original = mesh.positions.clone();
+        mesh.displace_with_heightmap(all.clone(), &heightmap, 2.0)?;
+        for v in all {
+            let p = original[v];
+            let expected = heightmap.sample(p.truncate() / 2.0) * 2.0;
+            assert!((mesh.positions[v].z - expected).abs() < 1e-5);
+        }
+        assert_eq!(heightmap.sample(Vec2::splat(0.5)), 1.5);
+        Ok(())
+    }
+
+    #[test]
+    fn lattice_deform() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 2.0)?;
+        let outside = mesh.add_vertex(vec3(5.0, 0.0, 0.0));
+        let all = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let bounds = (Vec3::splat(-1.0), Vec3::splat(1.0));
+
+        // an undeformed lattice keeps the positions
+        for interpolation in [LatticeInterpolation::Linear, LatticeInterpolation::Bezier] {
+            let lattice =
+                Lattice::new(bounds, UVec3::new(3, 2, 4))?.with_interpolation(interpolation);
+            let p = vec3(0.3, -0.2, 0.7);
+            assert!(lattice.deform(p).unwrap().distance(p) < 1e-5);
+        }
+
+        // lift the top layer
+        let mut lattice = Lattice::new(bounds, UVec3::splat(3))?;
+        for z in 0..3 {
+            for x in 0..3 {
+                let i = lattice.index(x, 2, z);
+                lattice.points[i].y += 1.0;
+            }
+        }
+        let middle = lattice.deform(Vec3::ZERO).unwrap();
+        // the middle layer follows with the weight of the top control points
+        assert!(middle.distance(vec3(0.0, 0.25, 0.0)) < 1e-5);
+        mesh.lattice_deform(all, &lattice)?;
+        for (v, p) in original {
+            let expected = if v == outside {
+                p
+            } else if p.y > 0.0 {
+                p + Vec3::Y
+            } else {
+                p
+            };
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+
+        let linear = lattice
+            .clone()
+            .with_interpolation(LatticeInterpolation::Linear);
+        assert!(linear.deform(Vec3::ZERO).unwrap().distance(Vec3::ZERO) < 1e-5);
+        assert!(linear.deform(Vec3::Y * 0.5).unwrap().distance(Vec3::Y) < 1e-5);
+
+        lattice.points.pop();
+        assert!(mesh.lattice_deform(outside, &lattice).is_err());
+        assert!(Lattice::new(bounds, UVec3::new(1, 2, 2)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn jitter() -> SMeshResult<()> {
+        let mut mesh = quad_grid(4)?;
+        let original = mesh.positions.clone();
+        let all = mesh.vertices().keys().collect_vec();
+        mesh.jitter(all.clone(), 0.25, 7)?;
+        let mut moved = 0;
+        for v in &all {
+            let d = mesh.positions[*v].distance(original[*v]);
+            assert!(d <= 0.25 + 1e-6);
+            moved += (d > 0.0) as usize;
+        }
+        assert!(moved > 20);
+
+        // same seed, same result
+        let mut again = quad_grid(4)?;
+        again.jitter(all.clone(), 0.25, 7)?;
+        assert_eq!(again.positions, mesh.positions);
+
+        let mut mesh = quad_grid(4)?;
+        mesh.jitter_along_normals(all.clone(), 0.25, 3)?;
+        for v in all {
+            let d = mesh.positions[v] - original[v];
+            assert!(d.truncate() == glam::Vec2::ZERO && d.z.abs() <= 0.25);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use glam::DVec3;
+use slotmap::SecondaryMap;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Mesh with double precision positions, for workflows that move vertices many times
+/// and can't afford to round them to f32 after every step.
+/// The connectivity and all other attributes live in an [`SMesh`], whose f32 positions
+/// are a rounded copy of the f64 positions. Topological operations run on that mesh
+/// through [`DSMesh::edit`].
+#[derive(Debug, Clone, Default)]
+pub struct DSMesh {
+    mesh: SMesh,
+    positions: SecondaryMap<VertexId, DVec3>,
+}
+
+impl DSMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mesh with positions rounded to f32, for queries and rendering
+    pub fn mesh(&self) -> &SMesh {
+        &self.mesh
+    }
+
+    /// Convert into an [`SMesh`], rounding the positions to f32
+    pub fn into_mesh(self) -> SMesh {
+        self.mesh
+    }
+
+    pub fn positions(&self) -> &SecondaryMap<VertexId, DVec3> {
+        &self.positions
+    }
+
+    pub fn position(&self, v: VertexId) -> Option<DVec3> {
+        self.positions.get(v).copied()
+    }
+
+    pub fn add_vertex(&mut self, position: DVec3) -> VertexId {
+        let v = self.mesh.add_vertex(position.as_vec3());
+        self.positions.insert(v, position);
+        v
+    }
+
+    pub fn set_position(&mut self, v: VertexId, position: DVec3) -> SMeshResult<()> {
+        if !self.mesh.vertices().contains_key(v) {
+            bail!(VertexNotFound, v);
+        }
+        self.positions.insert(v, position);
+        self.mesh.positions[v] = position.as_vec3();
+        self.mesh.dirty_vertices.insert(v);
+        Ok(())
+    }
+
+    /// Run an operation on the f32 mesh, e.g. a topological edit.
+    /// Vertices keep their f64 position unless the operation moved them,
+    /// vertices that were moved or created take their new f32 position.
+    pub fn edit<R>(&mut self, f: impl FnOnce(&mut SMesh) -> R) -> R {
+        let before = self.mesh.positions.clone();
+        let result = f(&mut self.mesh);
+        let mut positions = SecondaryMap::new();
+        for v in self.mesh.vertices().keys() {
+            let p = self.mesh.positions[v];
+            let position = match (before.get(v), self.positions.get(v)) {
+                (Some(old), Some(precise)) if *old == p => *precise,
+                _ => p.as_dvec3(),
+            };
+            positions.insert(v, position);
+        }
+        self.positions = positions;
+        result
+    }
+}
+
+impl From<SMesh> for DSMesh {
+    fn from(mesh: SMesh) -> Self {
+        Self {
+            positions: mesh.positions_f64(),
+            mesh,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+    use glam::Vec3;
+    use itertools::Itertools;
+
+    #[test]
+    fn dsmesh() -> SMeshResult<()> {
+        let original = cube(Vec3::ZERO, 1.0)?;
+        let mut mesh = DSMesh::from(original.clone());
+        let vertices = mesh.mesh().vertices().keys().collect_vec();
+        // far from the origin, steps this small get lost in f32
+        let offset = DVec3::splat(1e6);
+        for v in &vertices {
+            mesh.set_position(*v, mesh.position(*v).unwrap() + offset)?;
+            mesh.set_position(*v, mesh.position(*v).unwrap() + DVec3::splat(1e-3))?;
+        }
+        let face = mesh.mesh().faces().keys().next().unwrap();
+        mesh.edit(|m| m.extrude_faces(face))?;
+        assert_eq!(mesh.mesh().vertices().len(), 12);
+        assert_eq!(mesh.positions().len(), 12);
+        for v in &vertices {
+            let expected = original.positions[*v].as_dvec3() + DVec3::splat(1e-3);
+            assert!((mesh.position(*v).unwrap() - offset).distance(expected) < 1e-9);
+        }
+        assert!(mesh.set_position(VertexId::default(), DVec3::ZERO).is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,351 @@
-use crate::prelude::SMesh;
+use std::collections::{HashMap, HashSet};
+
+use crate::bail;
+use crate::prelude::*;
+use glam::Vec3;
+use itertools::Itertools;
+
+/// How a multi-face selection gets extruded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExtrudeMode {
+    /// Extrude all faces as one connected region, only creating side walls
+    /// along the outer boundary of the region
+    #[default]
+    Region,
+    /// Extrude every face on its own with its own side walls
+    Individual,
+}
 
 impl SMesh {
+    /// Connect a sequence of closed vertex loops with quad strips.
+    /// All profiles need to have the same amount of vertices (at least 3).
+    /// Profiles that are ordered counter-clockwise when looking along the
+    /// loft direction produce outward facing quads.
+    /// Returns the created face ids.
+    pub fn loft(&mut self, profiles: &[Vec<VertexId>]) -> SMeshResult<Vec<FaceId>> {
+        self.loft_internal(profiles, false)
+    }
+
+    /// Same as [`SMesh::loft`] but additionally closes the first and last profile
+    /// with an n-gon face each.
+    pub fn loft_with_caps(&mut self, profiles: &[Vec<VertexId>]) -> SMeshResult<Vec<FaceId>> {
+        self.loft_internal(profiles, true)
+    }
+
+    fn loft_internal(
+        &mut self,
+        profiles: &[Vec<VertexId>],
+        caps: bool,
+    ) -> SMeshResult<Vec<FaceId>> {
+        if profiles.len() < 2 {
+            bail!(DefaultError);
+        }
+        let n = profiles[0].len();
+        if n < 3 || profiles.iter().any(|p| p.len() != n) {
+            bail!(DefaultError);
+        }
+
+        let mut faces = vec![];
+        for (p0, p1) in profiles.iter().tuple_windows() {
+            for i in 0..n {
+                let ii = (i + 1) % n;
+                faces.push(self.add_quad(p0[i], p0[ii], p1[ii], p1[i])?);
+            }
+        }
+        if caps {
+            let first = profiles
+                .first()
+                .unwrap()
+                .iter()
+                .rev()
+                .copied()
+                .collect_vec();
+            faces.push(self.add_face(first)?);
+            faces.push(self.add_face(profiles.last().unwrap().clone())?);
+        }
+        Ok(faces)
+    }
+
+    /// Extrude the selected faces as one connected region.
+    /// The faces get moved onto newly created vertices which are connected to the
+    /// boundary of the region with side quads. The new vertices are placed at the
+    /// positions of the original ones, so the region typically gets translated afterwards.
+    /// Returns a selection containing the extruded faces.
+    pub fn extrude_faces<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+    ) -> SMeshResult<MeshSelection> {
+        self.extrude_faces_with_mode(selection, ExtrudeMode::Region)
+    }
+
+    /// Extrude the selected faces, either as one region or each face on its own
+    /// depending on the [`ExtrudeMode`].
+    /// Returns a selection containing the extruded faces.
+    pub fn extrude_faces_with_mode<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        mode: ExtrudeMode,
+    ) -> SMeshResult<MeshSelection> {
+        let faces = selection.into().resolve_to_faces(self)?;
+        self.check_journal();
+        let extruded = self.extrude_face_set(&faces, mode)?;
+        self.record_topology(|| Operation::ExtrudeFaces {
+            faces: faces.into_iter().sorted().collect(),
+            mode,
+        });
+        Ok(extruded)
+    }
+
+    /// Extrude the selected faces as one region and move every new vertex by `distance`
+    /// along the averaged normal of its adjacent extruded faces.
+    /// Returns a selection containing the extruded faces.
+    pub fn extrude_faces_along_normals<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        distance: f32,
+    ) -> SMeshResult<MeshSelection> {
+        self.extrude_faces_along_normals_with_mode(selection, distance, ExtrudeMode::Region)
+    }
+
+    /// Same as [`SMesh::extrude_faces_along_normals`], with the [`ExtrudeMode`] to use.
+    /// In [`ExtrudeMode::Individual`] every face is moved along its own normal.
+    pub fn extrude_faces_along_normals_with_mode<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        distance: f32,
+        mode: ExtrudeMode,
+    ) -> SMeshResult<MeshSelection> {
+        let faces = selection.into().resolve_to_faces(self)?;
+        self.check_journal();
+        let extruded = self.extrude_face_set(&faces, mode)?;
+        let mut normals: HashMap<VertexId, Vec3> = HashMap::new();
+        for f in extruded.resolve_to_faces(self)? {
+            let normal = f.normal(self)?;
+            for v in f.vertices(self) {
+                *normals.entry(v).or_default() += normal;
+            }
+        }
+        for (v, normal) in normals {
+            self.positions[v] += normal.normalize_or_zero() * distance;
+        }
+        self.record_topology(|| Operation::ExtrudeFacesAlongNormals {
+            faces: faces.into_iter().sorted().collect(),
+            distance,
+            mode,
+        });
+        Ok(extruded)
+    }
+
+    fn extrude_face_set(
+        &mut self,
+        faces: &HashSet<FaceId>,
+        mode: ExtrudeMode,
+    ) -> SMeshResult<MeshSelection> {
+        self.transaction(|tx| {
+            let mut extruded = MeshSelection::new();
+            match mode {
+                ExtrudeMode::Region => {
+                    for f in tx.extrude_region(faces)? {
+                        extruded.insert(f);
+                    }
+                }
+                ExtrudeMode::Individual => {
+                    for f in faces.iter().sorted() {
+                        for f in tx.extrude_region(&HashSet::from([*f]))? {
+                            extruded.insert(f);
+                        }
+                    }
+                }
+            }
+            Ok(extruded)
+        })
+    }
+
+    fn extrude_region(&mut self, faces: &HashSet<FaceId>) -> SMeshResult<Vec<FaceId>> {
+        let face_ids = faces.iter().copied().sorted().collect_vec();
+        let face_loops = face_ids
+            .iter()
+            .map(|f| f.vertices(self).collect_vec())
+            .collect_vec();
+
+        // halfedges on the region boundary as (src, dst) pairs
+        let mut boundary = vec![];
+        for f in &face_ids {
+            for he in f.halfedges(self) {
+                let neighbour = he.opposite().face().run(self).ok();
+                if neighbour.is_none_or(|n| !faces.contains(&n)) {
+                    boundary.push((he.src_vert().run(self)?, he.dst_vert().run(self)?));
+                }
+            }
+        }
+        let boundary_vertices: HashSet<VertexId> =
+            boundary.iter().flat_map(|(v0, v1)| [*v0, *v1]).collect();
+
+        let mut vertex_map = HashMap::new();
+        for v in face_loops.iter().flatten() {
+            if !vertex_map.contains_key(v) {
+                let position = *self
+                    .positions
+                    .get(*v)
+                    .ok_or(SMeshError::VertexNotFound(*v))?;
+                vertex_map.insert(*v, self.add_vertex(position));
+            }
+        }
+
+        for f in face_ids {
+            self.delete_face_keep_vertices(f)?;
+        }
+        // Vertices inside the region are not needed anymore
+        for v in vertex_map.keys().sorted() {
+            if !boundary_vertices.contains(v) && v.is_isolated(self) {
+                self.delete_vertex(*v)?;
+            }
+        }
+
+        self.dirty_vertices.extend(boundary_vertices);
+        self.dirty_vertices.extend(vertex_map.values());
+        let mut extruded = vec![];
+        for face_loop in face_loops {
+            extruded.push(self.add_face(face_loop.iter().map(|v| vertex_map[v]).collect())?);
+        }
+        for (v0, v1) in boundary {
+            self.add_quad(v0, v1, vertex_map[&v1], vertex_map[&v0])?;
+        }
+        Ok(extruded)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::vec3;
+
+    fn square_profile(mesh: &mut SMesh, y: f32) -> Vec<VertexId> {
+        vec![
+            mesh.add_vertex(vec3(1.0, y, 1.0)),
+            mesh.add_vertex(vec3(1.0, y, -1.0)),
+            mesh.add_vertex(vec3(-1.0, y, -1.0)),
+            mesh.add_vertex(vec3(-1.0, y, 1.0)),
+        ]
+    }
+
+    #[test]
+    fn loft() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let profiles = (0..3).map(|i| square_profile(mesh, i as f32)).collect_vec();
+        let faces = mesh.loft(&profiles)?;
+        assert_eq!(faces.len(), 8);
+        assert!(mesh.is_quad_mesh());
+        assert!(profiles[0][0].is_boundary(mesh));
+        assert!(!profiles[1][0].is_boundary(mesh));
+        Ok(())
+    }
+
+    #[test]
+    fn loft_with_caps() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let profiles = (0..2).map(|i| square_profile(mesh, i as f32)).collect_vec();
+        let faces = mesh.loft_with_caps(&profiles)?;
+        assert_eq!(faces.len(), 6);
+        for v in mesh.vertices().keys() {
+            assert!(!v.is_boundary(mesh));
+        }
+        Ok(())
+    }
+
+    fn cube(mesh: &mut SMesh) -> SMeshResult<Vec<FaceId>> {
+        let profiles = (0..2).map(|i| square_profile(mesh, i as f32)).collect_vec();
+        mesh.loft_with_caps(&profiles)
+    }
+
+    #[test]
+    fn extrude_single_face() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+        let f = mesh.add_face(vec![v0, v1, v2, v3])?;
+        let extruded = mesh.extrude_faces(f)?;
+        assert_eq!(mesh.faces().len(), 5);
+        assert_eq!(mesh.vertices().len(), 8);
+        assert_eq!(extruded.resolve_to_faces(mesh)?.len(), 1);
+        assert!(v0.is_boundary(mesh));
+        Ok(())
+    }
+
+    #[test]
+    fn extrude_along_normals() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let faces = cube(mesh)?;
+        let top = *faces.last().unwrap();
+        let extruded = mesh.extrude_faces_along_normals(top, 0.5)?;
+        assert_eq!(mesh.faces().len(), 10);
+        assert_eq!(mesh.vertices().len(), 12);
+        for v in extruded.resolve_to_vertices(mesh)? {
+            assert!((mesh.positions[v].y - 1.5).abs() < 1e-5);
+        }
+        for v in mesh.vertices().keys() {
+            assert!(!v.is_boundary(mesh));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extrude_region() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let faces = cube(mesh)?;
+        // two adjacent side faces
+        let region = vec![faces[0], faces[1]];
+        mesh.extrude_faces(region)?;
+        // 2 moved faces + 6 side quads around the region boundary
+        assert_eq!(mesh.faces().len(), 6 + 6);
+        for v in mesh.vertices().keys() {
+            assert!(!v.is_boundary(mesh));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extrude_individual() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let faces = cube(mesh)?;
+        let region = vec![faces[0], faces[1]];
+        let extruded =
+            mesh.extrude_faces_along_normals_with_mode(region, 1.0, ExtrudeMode::Individual)?;
+        // 2 moved faces + 4 side quads each
+        assert_eq!(mesh.faces().len(), 6 + 8);
+        assert_eq!(mesh.vertices().len(), 8 + 8);
+        for f in extruded.resolve_to_faces(mesh)? {
+            let n = f.normal(mesh)?;
+            let center = f.vertices(mesh).map(|v| mesh.positions[v]).sum::<Vec3>() / 4.0;
+            // every face got moved along its own normal away from the cube
+            assert!((center.dot(n) - 2.0).abs() < 1e-4);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extrude_rollback() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let faces = cube(mesh)?;
+        mesh.delete_face(faces[1])?;
+        let (vertices, halfedges) = (mesh.vertices().len(), mesh.halfedges().len());
+        // extruding the deleted face fails after the first face got extruded
+        let result = mesh.extrude_faces_with_mode(faces[..2].to_vec(), ExtrudeMode::Individual);
+        assert!(result.is_err());
+        assert_eq!(mesh.vertices().len(), vertices);
+        assert_eq!(mesh.halfedges().len(), halfedges);
+        assert_eq!(mesh.faces().len(), 5);
+        Ok(())
+    }
 
+    #[test]
+    fn loft_mismatched_profiles() {
+        let mesh = &mut SMesh::new();
+        let mut profiles = (0..2).map(|i| square_profile(mesh, i as f32)).collect_vec();
+        profiles[1].pop();
+        assert!(mesh.loft(&profiles).is_err());
+    }
 }
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use glam::{UVec3, Vec3};
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Corner offsets of a grid cell
+const CELL_CORNERS: [[u32; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [1, 1, 0],
+    [0, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [1, 1, 1],
+    [0, 1, 1],
+];
+
+/// Split of a cell into six tetrahedra around the diagonal from corner 0 to 6.
+/// The split is the same for every cell, so neighbouring cells share their face diagonals.
+const CELL_TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 5, 1, 6],
+    [0, 1, 2, 6],
+    [0, 2, 3, 6],
+    [0, 3, 7, 6],
+    [0, 7, 4, 6],
+    [0, 4, 5, 6],
+];
+
+/// Create a triangle mesh of the zero level set of the signed distance function `f`.
+/// `f` should be negative inside and positive outside of the shape, the face normals of
+/// the result point to the outside.
+/// The function is sampled on a regular grid spanning `bounds` (min, max) with
+/// `resolution` cells on each axis. Every cell gets polygonized as six tetrahedra
+/// (marching tetrahedra) instead of with the marching cubes case table, which never
+/// produces ambiguous cells. The price is about six triangles per cell the surface
+/// passes through, roughly three times as many as marching cubes, and more of them
+/// are thin slivers, see [`SMesh::simplify`] to reduce them.
+/// Shapes that are cut by the bounds produce open meshes.
+pub fn sdf_to_mesh(
+    f: impl Fn(Vec3) -> f32,
+    bounds: (Vec3, Vec3),
+    resolution: UVec3,
+) -> SMeshResult<SMesh> {
+    let (min, max) = bounds;
+    if resolution.cmpeq(UVec3::ZERO).any() || max.cmple(min).any() {
+        bail!(DefaultError);
+    }
+    let cell_size = (max - min) / resolution.as_vec3();
+    let samples = resolution + UVec3::ONE;
+    let index = |p: UVec3| (p.x + samples.x * (p.y + samples.y * p.z)) as usize;
+    let grid_position = |p: UVec3| min + p.as_vec3() * cell_size;
+
+    let mut values = vec![0.0; (samples.x * samples.y * samples.z) as usize];
+    for z in 0..samples.z {
+        for y in 0..samples.y {
+            for x in 0..samples.x {
+                let p = UVec3::new(x, y, z);
+                values[index(p)] = f(grid_position(p));
+            }
+        }
+    }
+
+    let mut mesh = SMesh::new();
+    let mut triangles = vec![];
+    // vertices on the grid edges, keyed by the sorted indices of the edge's grid points
+    let mut edge_vertices: HashMap<(usize, usize), VertexId> = HashMap::new();
+    let mut edge_vertex = |mesh: &mut SMesh, a: (usize, Vec3, f32), b: (usize, Vec3, f32)| {
+        let key = (a.0.min(b.0), a.0.max(b.0));
+        *edge_vertices.entry(key).or_insert_with(|| {
+            let t = a.2 / (a.2 - b.2);
+            mesh.add_vertex(a.1.lerp(b.1, t))
+        })
+    };
+
+    for z in 0..resolution.z {
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                let cell = UVec3::new(x, y, z);
+                let corners = CELL_CORNERS.map(|c| {
+                    let p = cell + UVec3::from(c);
+                    (index(p), grid_position(p), values[index(p)])
+                });
+                for tet in CELL_TETRAHEDRA {
+                    let tet = tet.map(|i| corners[i]);
+                    let (inside, outside): (Vec<_>, Vec<_>) =
+                        tet.iter().copied().partition(|c| c.2 < 0.0);
+                    let outside_center =
+                        outside.iter().map(|c| c.1).sum::<Vec3>() / outside.len().max(1) as f32;
+                    let inside_center =
+                        inside.iter().map(|c| c.1).sum::<Vec3>() / inside.len().max(1) as f32;
+                    let polygon = match (inside.len(), outside.len()) {
+                        (1, 3) => outside
+                            .iter()
+                            .map(|o| edge_vertex(&mut mesh, inside[0], *o))
+                            .collect::<Vec<_>>(),
+                        (3, 1) => inside
+                            .iter()
+                            .map(|i| edge_vertex(&mut mesh, *i, outside[0]))
+                            .collect::<Vec<_>>(),
+                        (2, 2) => vec![
+                            edge_vertex(&mut mesh, inside[0], outside[0]),
+                            edge_vertex(&mut mesh, inside[0], outside[1]),
+                            edge_vertex(&mut mesh, inside[1], outside[1]),
+                            edge_vertex(&mut mesh, inside[1], outside[0]),
+                        ],
+                        _ => continue,
+                    };
+                    add_oriented_fan(
+                        &mesh,
+                        &mut triangles,
+                        &polygon,
+                        outside_center - inside_center,
+                    );
+                }
+            }
+        }
+    }
+    mesh.add_faces(&triangles)?;
+    Ok(mesh)
+}
+
+/// Wrap a graph of nodes with a position and radius each in quad tubes along the `edges`,
+/// with welded joints. See [`Skin`] for the details and the ids of the generated rings.
+pub fn skin(nodes: &[(Vec3, f32)], edges: &[(usize, usize)]) -> SMeshResult<SMesh> {
+    let (mesh, _) = Skin {
+        nodes: nodes.to_vec(),
+        edges: edges.to_vec(),
+    }
+    .generate()?;
+    Ok(mesh)
+}
+
+/// Triangulate the convex polygon with the normals pointing along `direction`
+fn add_oriented_fan(
+    mesh: &SMesh,
+    triangles: &mut Vec<Vec<VertexId>>,
+    polygon: &[VertexId],
+    direction: Vec3,
+) {
+    let p = |i: usize| mesh.positions[polygon[i]];
+    let mut normal = Vec3::ZERO;
+    for i in 1..polygon.len() - 1 {
+        normal += (p(i) - p(0)).cross(p(i + 1) - p(0));
+    }
+    let mut polygon = polygon.to_vec();
+    if normal.dot(direction) < 0.0 {
+        polygon.reverse();
+    }
+    for i in 1..polygon.len() - 1 {
+        triangles.push(vec![polygon[0], polygon[i], polygon[i + 1]]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use itertools::Itertools;
+
+    fn sphere(p: Vec3) -> f32 {
+        p.length() - 1.0
+    }
+
+    #[test]
+    fn sdf_sphere() -> SMeshResult<()> {
+        let mesh = sdf_to_mesh(
+            sphere,
+            (Vec3::splat(-1.5), Vec3::splat(1.5)),
+            UVec3::splat(16),
+        )?;
+        check_connectivity(&mesh)?;
+        assert!(mesh.is_triangle_mesh());
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        for v in mesh.vertices().keys() {
+            assert!((mesh.positions[v].length() - 1.0).abs() < 0.1);
+        }
+        let volume = mesh
+            .faces()
+            .keys()
+            .map(|f| {
+                let p = f.vertices(&mesh).map(|v| mesh.positions[v]).collect_vec();
+                p[0].dot(p[1].cross(p[2])) / 6.0
+            })
+            .sum::<f32>();
+        let expected = 4.0 / 3.0 * std::f32::consts::PI;
+        assert!((volume - expected).abs() < expected * 0.05);
+        Ok(())
+    }
+
+    #[test]
+    fn sdf_clipped_by_bounds() -> SMeshResult<()> {
+        let mesh = sdf_to_mesh(sphere, (Vec3::ZERO, Vec3::splat(1.5)), UVec3::splat(8))?;
+        check_connectivity(&mesh)?;
+        assert!(mesh.halfedges().keys().any(|h| h.is_boundary(&mesh)));
+        Ok(())
+    }
+
+    #[test]
+    fn skin_chain() -> SMeshResult<()> {
+        let nodes = [
+            (Vec3::ZERO, 0.5),
+            (Vec3::Y, 0.5),
+            (Vec3::new(1.0, 2.0, 0.0), 0.25),
+        ];
+        let mesh = skin(&nodes, &[(0, 1), (1, 2)])?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 2 * 4 + 2);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        Ok(())
+    }
+
+    #[test]
+    fn sdf_invalid_resolution() {
+        let result = sdf_to_mesh(sphere, (Vec3::splat(-1.0), Vec3::ONE), UVec3::new(4, 0, 4));
+        assert_eq!(result.err(), Some(SMeshError::DefaultError));
+    }
+}
@@ -0,0 +1,456 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32::consts::{PI, TAU};
+
+use glam::{DVec3, Vec3};
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// How [`SMesh::centroid`] weights the geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CentroidWeighting {
+    /// Average of the vertex positions, biased towards densely tessellated regions
+    Uniform,
+    /// Center of the surface of the faces, independent of the tessellation
+    #[default]
+    Area,
+}
+
+/// Geometric measures of the mesh
+impl SMesh {
+    /// Summed area of the selected faces
+    pub fn surface_area<S: Into<MeshSelection>>(&self, selection: S) -> SMeshResult<f32> {
+        let mut area = 0.0;
+        for f in selection.into().resolve_to_faces(self)? {
+            area += f.area(self)?;
+        }
+        Ok(area)
+    }
+
+    /// Enclosed volume of the mesh, computed with the divergence theorem.
+    /// The mesh needs to be closed, the volume is negative if the faces
+    /// point inwards.
+    pub fn volume(&self) -> SMeshResult<f32> {
+        if self.halfedges().keys().any(|h| h.is_boundary(self)) {
+            bail!(TopologyError);
+        }
+        // accumulated in double precision, the terms get large far away from the origin
+        let mut volume = 0.0;
+        for f in self.faces().keys() {
+            let p = f
+                .vertices(self)
+                .map(|v| self.positions[v].as_dvec3())
+                .collect_vec();
+            for i in 1..p.len() - 1 {
+                volume += p[0].dot(p[i].cross(p[i + 1]));
+            }
+        }
+        Ok((volume / 6.0) as f32)
+    }
+
+    /// Center of the selection, either of its vertices or of the surface of its faces.
+    /// Fails if the selection has no vertices, or no area for [`CentroidWeighting::Area`].
+    pub fn centroid<S: Into<MeshSelection>>(
+        &self,
+        selection: S,
+        weighting: CentroidWeighting,
+    ) -> SMeshResult<Vec3> {
+        let selection = selection.into();
+        match weighting {
+            CentroidWeighting::Uniform => {
+                let vertices = selection.resolve_to_vertices(self)?;
+                if vertices.is_empty() {
+                    bail!(DefaultError);
+                }
+                let sum = vertices
+                    .iter()
+                    .map(|v| self.positions.get(*v).copied().unwrap_or_default())
+                    .sum::<Vec3>();
+                Ok(sum / vertices.len() as f32)
+            }
+            CentroidWeighting::Area => {
+                // triangle fans, each triangle weighted by its area
+                let (mut sum, mut area) = (Vec3::ZERO, 0.0);
+                for f in selection.resolve_to_faces(self)? {
+                    let p = f.vertices(self).map(|v| self.positions[v]).collect_vec();
+                    for i in 1..p.len() - 1 {
+                        let a = (p[i] - p[0]).cross(p[i + 1] - p[0]).length() * 0.5;
+                        sum += (p[0] + p[i] + p[i + 1]) / 3.0 * a;
+                        area += a;
+                    }
+                }
+                if area <= 0.0 {
+                    bail!(DefaultError);
+                }
+                Ok(sum / area)
+            }
+        }
+    }
+
+    /// Vertex positions in double precision, for algorithms that need more
+    /// precision internally than the stored positions
+    pub fn positions_f64(&self) -> SecondaryMap<VertexId, DVec3> {
+        self.vertices()
+            .keys()
+            .map(|v| (v, self.positions[v].as_dvec3()))
+            .collect()
+    }
+
+    /// Write back double precision positions, see [`SMesh::positions_f64`].
+    /// Entries of vertices that are not part of the mesh are ignored.
+    pub fn set_positions_f64(&mut self, positions: &SecondaryMap<VertexId, DVec3>) {
+        for (v, p) in positions {
+            if self.vertices().contains_key(v) {
+                self.positions[v] = p.as_vec3();
+                self.dirty_vertices.insert(v);
+            }
+        }
+    }
+
+    /// Compute the mean and gaussian curvature of every vertex and store them in
+    /// [`SMesh::mean_curvature`] and [`SMesh::gaussian_curvature`].
+    /// The mean curvature is derived from the cotangent laplacian and is positive
+    /// for convex regions, the gaussian curvature from the angle defect. Both are
+    /// normalized by the mixed voronoi area of the vertex (Meyer et al. 2003).
+    /// Only supported for triangle meshes.
+    pub fn compute_curvature(&mut self) -> SMeshResult<()> {
+        if !self.is_triangle_mesh() {
+            bail!(UnsupportedOperation);
+        }
+        let mut mean = SecondaryMap::new();
+        let mut gaussian = SecondaryMap::new();
+        for v in self.vertices().keys() {
+            if v.is_isolated(self) {
+                continue;
+            }
+            let p = self.positions[v];
+            let mut area = 0.0;
+            let mut angle_sum = 0.0;
+            let mut normal = Vec3::ZERO;
+            let mut laplacian = Vec3::ZERO;
+            for h in v.halfedges(self) {
+                let q = self.positions[h.dst_vert().run(self)?];
+                // cotangents of the angles opposite to the edge in both adjacent faces
+                let mut weight = 0.0;
+                for he in [h, h.opposite().run(self)?] {
+                    if he.is_boundary(self) {
+                        continue;
+                    }
+                    let r = self.positions[he.next().dst_vert().run(self)?];
+                    weight += cotangent(p - r, q - r);
+                }
+                laplacian += weight * (q - p);
+            }
+            for f in v.faces(self) {
+                let corner = f
+                    .halfedges(self)
+                    .find(|h| h.src_vert().run(self) == Ok(v))
+                    .ok_or(SMeshError::TopologyError)?;
+                let q0 = self.positions[corner.dst_vert().run(self)?];
+                let q1 = self.positions[corner.prev().src_vert().run(self)?];
+                angle_sum += (q0 - p).angle_between(q1 - p);
+                area += mixed_area(p, q0, q1);
+                normal += f.normal(self)?;
+            }
+            if area <= f32::EPSILON {
+                continue;
+            }
+            laplacian /= 2.0 * area;
+            let h = 0.5 * laplacian.length();
+            mean.insert(v, if laplacian.dot(normal) > 0.0 { -h } else { h });
+            let full_angle = if v.is_boundary(self) { PI } else { TAU };
+            gaussian.insert(v, (full_angle - angle_sum) / area);
+        }
+        self.mean_curvature = Some(mean);
+        self.gaussian_curvature = Some(gaussian);
+        Ok(())
+    }
+}
+
+/// Paths along the edges of the mesh
+impl SMesh {
+    /// Distance from the selected vertices to every other vertex, measured along
+    /// the edges of the mesh (Dijkstra). The edge distance overestimates the true
+    /// geodesic distance on coarse or irregular meshes.
+    /// Vertices that can not be reached are missing in the result.
+    pub fn geodesic_distance<S: Into<MeshSelection>>(
+        &self,
+        from: S,
+    ) -> SMeshResult<SecondaryMap<VertexId, f32>> {
+        let sources = from.into().resolve_to_vertices(self)?;
+        let (distances, _) = self.dijkstra(sources, None, None)?;
+        Ok(distances)
+    }
+
+    /// Shortest chain of halfedges leading from `v0` to `v1`, or `None` if `v1`
+    /// can not be reached from `v0`
+    pub fn shortest_edge_path(
+        &self,
+        v0: VertexId,
+        v1: VertexId,
+    ) -> SMeshResult<Option<Vec<HalfedgeId>>> {
+        if !self.vertices().contains_key(v1) {
+            bail!(VertexNotFound, v1);
+        }
+        let (_, predecessors) = self.dijkstra([v0], Some(v1), None)?;
+        let mut path = vec![];
+        let mut current = v1;
+        while current != v0 {
+            let Some(h) = predecessors.get(current) else {
+                return Ok(None);
+            };
+            path.push(*h);
+            current = h.src_vert().run(self)?;
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    /// Edge distances from the sources and the halfedge each vertex was reached with.
+    /// Stops early once `target` was reached, and skips vertices at `radius` or further.
+    pub(crate) fn dijkstra(
+        &self,
+        sources: impl IntoIterator<Item = VertexId>,
+        target: Option<VertexId>,
+        radius: Option<f32>,
+    ) -> SMeshResult<(
+        SecondaryMap<VertexId, f32>,
+        SecondaryMap<VertexId, HalfedgeId>,
+    )> {
+        let mut distances = SecondaryMap::new();
+        let mut predecessors = SecondaryMap::new();
+        let mut heap = BinaryHeap::new();
+        for v in sources {
+            if !self.vertices().contains_key(v) {
+                bail!(VertexNotFound, v);
+            }
+            distances.insert(v, 0.0);
+            heap.push(PathCandidate {
+                distance: 0.0,
+                vertex: v,
+            });
+        }
+        while let Some(PathCandidate { distance, vertex }) = heap.pop() {
+            if distances.get(vertex).is_some_and(|d| distance > *d) {
+                continue;
+            }
+            if Some(vertex) == target {
+                break;
+            }
+            if vertex.is_isolated(self) {
+                continue;
+            }
+            let p = self.positions[vertex];
+            for h in vertex.halfedges(self) {
+                let next = h.dst_vert().run(self)?;
+                let d = distance + p.distance(self.positions[next]);
+                if radius.is_some_and(|r| d >= r) {
+                    continue;
+                }
+                if distances.get(next).is_none_or(|current| d < *current) {
+                    distances.insert(next, d);
+                    predecessors.insert(next, h);
+                    heap.push(PathCandidate {
+                        distance: d,
+                        vertex: next,
+                    });
+                }
+            }
+        }
+        Ok((distances, predecessors))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PathCandidate {
+    distance: f32,
+    vertex: VertexId,
+}
+
+impl PartialEq for PathCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+impl Eq for PathCandidate {}
+impl PartialOrd for PathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PathCandidate {
+    // reversed, so the binary heap pops the closest vertex first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+/// Voronoi area of the triangle corner at `p`, falling back to fractions of the
+/// triangle area for obtuse triangles
+fn mixed_area(p: Vec3, q0: Vec3, q1: Vec3) -> f32 {
+    let area = 0.5 * (q0 - p).cross(q1 - p).length();
+    if (q0 - p).dot(q1 - p) < 0.0 {
+        area / 2.0
+    } else if (p - q0).dot(q1 - q0) < 0.0 || (p - q1).dot(q0 - q1) < 0.0 {
+        area / 4.0
+    } else {
+        ((q0 - p).length_squared() * cotangent(p - q1, q0 - q1)
+            + (q1 - p).length_squared() * cotangent(p - q0, q1 - q0))
+            / 8.0
+    }
+}
+
+/// Cotangent of the angle between the two vectors
+fn cotangent(a: Vec3, b: Vec3) -> f32 {
+    let sin = a.cross(b).length();
+    if sin <= f32::EPSILON {
+        return 0.0;
+    }
+    a.dot(b) / sin
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, triangulated_grid, uv_sphere};
+    use glam::vec3;
+
+    #[test]
+    fn surface_area() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 2.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        assert!((mesh.surface_area(faces.clone())? - 24.0).abs() < 1e-5);
+        assert!((mesh.surface_area(faces[0])? - 4.0).abs() < 1e-5);
+        assert_eq!(mesh.surface_area(MeshSelection::new())?, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn centroid() -> SMeshResult<()> {
+        // a unit square tessellated finely on the left side only
+        let mut mesh = triangulated_grid(4)?;
+        for p in mesh.positions.values_mut() {
+            p.x = (p.x / 4.0).powi(2);
+            p.y /= 4.0;
+        }
+        let faces = mesh.faces().keys().collect_vec();
+        let center = mesh.centroid(faces.clone(), CentroidWeighting::Area)?;
+        assert!(center.distance(vec3(0.5, 0.5, 0.0)) < 1e-5);
+        let average = mesh.centroid(faces, CentroidWeighting::Uniform)?;
+        assert!(average.x < 0.4);
+        assert!(mesh
+            .centroid(MeshSelection::new(), CentroidWeighting::Uniform)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn volume() -> SMeshResult<()> {
+        let mesh = cube(Vec3::splat(3.0), 2.0)?;
+        assert!((mesh.volume()? - 8.0).abs() < 1e-4);
+        let open = triangulated_grid(2)?;
+        assert_eq!(open.volume().err(), Some(SMeshError::TopologyError));
+        // far away from the origin
+        let mesh = cube(Vec3::splat(1000.0), 1.0)?;
+        assert!((mesh.volume()? - 1.0).abs() < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn positions_f64() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let mut positions = mesh.positions_f64();
+        assert_eq!(positions.len(), 8);
+        for p in positions.values_mut() {
+            *p *= 2.0;
+        }
+        mesh.set_positions_f64(&positions);
+        assert!((mesh.volume()? - 8.0).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn curvature_sphere() -> SMeshResult<()> {
+        let r = 2.0;
+        let mut mesh = uv_sphere(r, 32, 64)?;
+        mesh.compute_curvature()?;
+        let median = |values: &SecondaryMap<VertexId, f32>| {
+            let sorted = values
+                .values()
+                .copied()
+                .sorted_by(f32::total_cmp)
+                .collect_vec();
+            sorted[sorted.len() / 2]
+        };
+        assert!((median(mesh.mean_curvature.as_ref().unwrap()) - 1.0 / r).abs() < 0.01);
+        assert!((median(mesh.gaussian_curvature.as_ref().unwrap()) - 1.0 / (r * r)).abs() < 0.01);
+        Ok(())
+    }
+
+    #[test]
+    fn curvature_flat() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(2)?;
+        mesh.compute_curvature()?;
+        let center = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == vec3(1.0, 1.0, 0.0))
+            .unwrap();
+        assert!(mesh.mean_curvature.as_ref().unwrap()[center].abs() < 1e-5);
+        assert!(mesh.gaussian_curvature.as_ref().unwrap()[center].abs() < 1e-5);
+        assert_eq!(
+            cube(Vec3::ZERO, 1.0)?.compute_curvature().err(),
+            Some(SMeshError::UnsupportedOperation)
+        );
+        Ok(())
+    }
+
+    fn grid_vertex(mesh: &SMesh, x: f32, y: f32) -> VertexId {
+        mesh.vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == vec3(x, y, 0.0))
+            .unwrap()
+    }
+
+    #[test]
+    fn geodesic_distance() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(3)?;
+        let isolated = mesh.add_vertex(vec3(10.0, 0.0, 0.0));
+        let origin = grid_vertex(&mesh, 0.0, 0.0);
+        let distances = mesh.geodesic_distance(origin)?;
+        assert_eq!(distances[origin], 0.0);
+        assert!((distances[grid_vertex(&mesh, 3.0, 0.0)] - 3.0).abs() < 1e-5);
+        // along the diagonals of the grid triangles
+        assert!((distances[grid_vertex(&mesh, 2.0, 2.0)] - 2.0 * 2f32.sqrt()).abs() < 1e-5);
+        assert!(!distances.contains_key(isolated));
+
+        let row = (0..=3)
+            .map(|x| grid_vertex(&mesh, x as f32, 0.0))
+            .collect_vec();
+        let distances = mesh.geodesic_distance(row)?;
+        assert!((distances[grid_vertex(&mesh, 1.0, 3.0)] - 3.0).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn shortest_edge_path() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(3)?;
+        let isolated = mesh.add_vertex(vec3(10.0, 0.0, 0.0));
+        let v0 = grid_vertex(&mesh, 0.0, 0.0);
+        let v1 = grid_vertex(&mesh, 3.0, 2.0);
+        let path = mesh.shortest_edge_path(v0, v1)?.unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0].src_vert().run(&mesh)?, v0);
+        assert_eq!(path[2].dst_vert().run(&mesh)?, v1);
+        for (h0, h1) in path.iter().tuple_windows() {
+            assert_eq!(h0.dst_vert().run(&mesh)?, h1.src_vert().run(&mesh)?);
+        }
+        assert_eq!(mesh.shortest_edge_path(v0, v0)?, Some(vec![]));
+        assert_eq!(mesh.shortest_edge_path(v0, isolated)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use glam::Vec3;
+use slotmap::SecondaryMap;
+
+use crate::smesh::*;
+
+/// Blend the values of `a` and `b` into `to`, if both have one
+fn lerp_entry<K: slotmap::Key, T: Copy>(
+    map: &mut SecondaryMap<K, T>,
+    to: K,
+    a: K,
+    b: K,
+    t: f32,
+    lerp: impl Fn(T, T, f32) -> T,
+) {
+    if let (Some(a), Some(b)) = (map.get(a).copied(), map.get(b).copied()) {
+        map.insert(to, lerp(a, b, t));
+    }
+}
+
+fn copy_entry<K: slotmap::Key, T: Copy>(map: &mut SecondaryMap<K, T>, from: K, to: K) {
+    if let Some(value) = map.get(from).copied() {
+        map.insert(to, value);
+    }
+}
+
+fn lerp_f32(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn lerp_normal(a: Vec3, b: Vec3, t: f32) -> Vec3 {
+    a.lerp(b, t).normalize_or_zero()
+}
+
+/// Attribute interpolation for topological operations, so that elements created
+/// by splitting or merging keep the built-in and custom attributes of their neighbours
+impl SMesh {
+    /// Set the vertex attributes of `v` to the blend of `a` and `b`,
+    /// `t` going from 0 at `a` to 1 at `b`. Positions are left untouched.
+    pub(crate) fn interpolate_vertex_attributes(
+        &mut self,
+        v: VertexId,
+        a: VertexId,
+        b: VertexId,
+        t: f32,
+    ) {
+        if let Some(normals) = self.vertex_normals.as_mut() {
+            lerp_entry(normals, v, a, b, t, lerp_normal);
+        }
+        for curvature in [
+            self.mean_curvature.as_mut(),
+            self.gaussian_curvature.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            lerp_entry(curvature, v, a, b, t, lerp_f32);
+        }
+        for map in self.vertex_attributes.values_mut() {
+            map.interpolate_value(v, a, b, t);
+        }
+        self.typed_attributes
+            .copy_values(if t < 0.5 { a } else { b }, v);
+    }
+
+    /// Set the face corner attributes (uvs and corner normals) of `h`
+    /// to the blend of the corners `a` and `b`
+    pub(crate) fn interpolate_corner_attributes(
+        &mut self,
+        h: HalfedgeId,
+        a: HalfedgeId,
+        b: HalfedgeId,
+        t: f32,
+    ) {
+        if let Some(uvs) = self.uvs.as_mut() {
+            lerp_entry(uvs, h, a, b, t, |a, b, t| a.lerp(b, t));
+        }
+        if let Some(normals) = self.halfedge_normals.as_mut() {
+            lerp_entry(normals, h, a, b, t, lerp_normal);
+        }
+    }
+
+    /// Copy the edge attributes (creases, sharpness, seams and custom halfedge attributes)
+    /// of `from` to `to`, used when an edge gets split into two
+    pub(crate) fn copy_edge_attributes(&mut self, from: HalfedgeId, to: HalfedgeId) {
+        if let Some(creases) = self.edge_creases.as_mut() {
+            copy_entry(creases, from, to);
+        }
+        if let Some(sharp) = self.sharp_edges.as_mut() {
+            copy_entry(sharp, from, to);
+        }
+        if let Some(seams) = self.seams.as_mut() {
+            copy_entry(seams, from, to);
+        }
+        for map in self.edge_attributes.values_mut() {
+            map.copy_value(from, to);
+        }
+        self.typed_attributes.copy_values(from, to);
+    }
+
+    /// Copy the face attributes of `from` to `to`, used when a face gets split into two
+    pub(crate) fn copy_face_attributes(&mut self, from: FaceId, to: FaceId) {
+        if let Some(normals) = self.face_normals.as_mut() {
+            copy_entry(normals, from, to);
+        }
+        if let Some(groups) = self.smoothing_groups.as_mut() {
+            copy_entry(groups, from, to);
+        }
+        if let Some(materials) = self.material_indices.as_mut() {
+            copy_entry(materials, from, to);
+        }
+        for map in self.face_attributes.values_mut() {
+            map.copy_value(from, to);
+        }
+        self.typed_attributes.copy_values(from, to);
+    }
+}
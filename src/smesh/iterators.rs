@@ -1,19 +1,29 @@
+use std::collections::{HashSet, VecDeque};
+
 use crate::smesh::mesh_query::*;
 use crate::smesh::*;
 
+/// Next outgoing halfedge around the source vertex of `h`, in the given direction
+fn rotate(conn: &Connectivity, h: HalfedgeId, clockwise: bool) -> Option<HalfedgeId> {
+    if clockwise {
+        h.cw_rotated_neighbour().run(conn).ok()
+    } else {
+        h.ccw_rotated_neighbour().run(conn).ok()
+    }
+}
+
 pub struct HalfedgeAroundVertexIter<'a> {
     conn: &'a Connectivity,
     start: HalfedgeId,
     current: Option<HalfedgeId>,
+    clockwise: bool,
 }
 impl<'a> Iterator for HalfedgeAroundVertexIter<'a> {
     type Item = HalfedgeId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(current) = self.current else {
-            return None;
-        };
-        let next = current.ccw_rotated_neighbour().run(self.conn).ok();
+        let current = self.current?;
+        let next = rotate(self.conn, current, self.clockwise);
         self.current = if next == Some(self.start) { None } else { next };
         Some(current)
     }
@@ -23,21 +33,39 @@ pub struct VertexAroundVertexIter<'a> {
     conn: &'a Connectivity,
     start: HalfedgeId,
     current: Option<HalfedgeId>,
+    clockwise: bool,
 }
 impl<'a> Iterator for VertexAroundVertexIter<'a> {
     type Item = VertexId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(current) = self.current else {
-            return None;
-        };
+        let current = self.current?;
         let dst_vert = current.dst_vert().run(self.conn);
-        let next = current.ccw_rotated_neighbour().run(self.conn).ok();
+        let next = rotate(self.conn, current, self.clockwise);
         self.current = if next == Some(self.start) { None } else { next };
         dst_vert.ok()
     }
 }
 
+/// Yields every edge around a vertex once, as its canonical halfedge:
+/// the one of the halfedge pair with the smaller id
+pub struct EdgeAroundVertexIter<'a> {
+    conn: &'a Connectivity,
+    start: HalfedgeId,
+    current: Option<HalfedgeId>,
+}
+impl<'a> Iterator for EdgeAroundVertexIter<'a> {
+    type Item = HalfedgeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        let opposite = current.opposite().run(self.conn);
+        let next = current.ccw_rotated_neighbour().run(self.conn).ok();
+        self.current = if next == Some(self.start) { None } else { next };
+        Some(opposite.map_or(current, |o| current.min(o)))
+    }
+}
+
 pub struct FaceAroundVertexIter<'a> {
     conn: &'a Connectivity,
     start: HalfedgeId,
@@ -48,9 +76,7 @@ impl<'a> Iterator for FaceAroundVertexIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let Some(current) = self.current else {
-                return None;
-            };
+            let current = self.current?;
 
             let face = current.face().run(self.conn);
             let next = current.ccw_rotated_neighbour().run(self.conn).ok();
@@ -72,9 +98,7 @@ impl<'a> Iterator for VertexAroundFaceIter<'a> {
     type Item = VertexId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(current) = self.current else {
-            return None;
-        };
+        let current = self.current?;
         let dst_vert = current.dst_vert().run(self.conn);
         let next = current.next().run(self.conn).ok();
         self.current = if next == Some(self.start) { None } else { next };
@@ -92,46 +116,278 @@ impl<'a> Iterator for HalfedgeAroundFaceIter<'a> {
     type Item = HalfedgeId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(current) = self.current else {
-            return None;
-        };
+        let current = self.current?;
         let next = current.next().run(self.conn).ok();
         self.current = if next == Some(self.start) { None } else { next };
         Some(current)
     }
 }
 
+pub struct FaceAroundFaceIter<'a> {
+    conn: &'a Connectivity,
+    start: HalfedgeId,
+    current: Option<HalfedgeId>,
+    share_vertex: bool,
+    pending: Vec<FaceId>,
+    seen: Vec<FaceId>,
+}
+
+impl<'a> FaceAroundFaceIter<'a> {
+    fn new(conn: &'a Connectivity, start: HalfedgeId, share_vertex: bool) -> Self {
+        Self {
+            conn,
+            start,
+            current: Some(start),
+            share_vertex,
+            pending: vec![],
+            seen: start.face().run(conn).into_iter().collect(),
+        }
+    }
+
+    /// Faces adjacent to the edge of `h`, or to its destination vertex
+    fn candidates(&self, h: HalfedgeId) -> Vec<FaceId> {
+        if !self.share_vertex {
+            return h.opposite().face().run(self.conn).into_iter().collect();
+        }
+        let Ok(start) = h.next().run(self.conn) else {
+            return vec![];
+        };
+        let mut faces = vec![];
+        let mut current = start;
+        loop {
+            if let Ok(face) = current.face().run(self.conn) {
+                faces.push(face);
+            }
+            match current.ccw_rotated_neighbour().run(self.conn) {
+                Ok(next) if next != start => current = next,
+                _ => break,
+            }
+        }
+        faces
+    }
+}
+
+impl<'a> Iterator for FaceAroundFaceIter<'a> {
+    type Item = FaceId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(face) = self.pending.pop() {
+                return Some(face);
+            }
+            let current = self.current?;
+            let next = current.next().run(self.conn).ok();
+            self.current = if next == Some(self.start) { None } else { next };
+            for face in self.candidates(current) {
+                if !self.seen.contains(&face) {
+                    self.seen.push(face);
+                    self.pending.insert(0, face);
+                }
+            }
+        }
+    }
+}
+
+/// Breadth first traversal over the vertices connected to a seed vertex,
+/// yielding each vertex with its depth (edge hops from the seed)
+pub struct BfsVertexIter<'a> {
+    mesh: &'a SMesh,
+    queue: VecDeque<(VertexId, usize)>,
+    visited: HashSet<VertexId>,
+    max_depth: Option<usize>,
+}
+
+impl<'a> BfsVertexIter<'a> {
+    /// Stop the traversal after `max_depth` hops
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+}
+
+impl<'a> Iterator for BfsVertexIter<'a> {
+    type Item = (VertexId, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (v, depth) = self.queue.pop_front()?;
+        if self.max_depth.is_none_or(|max| depth < max) && !v.is_isolated(self.mesh) {
+            for n in v.vertices(self.mesh) {
+                if self.visited.insert(n) {
+                    self.queue.push_back((n, depth + 1));
+                }
+            }
+        }
+        Some((v, depth))
+    }
+}
+
+/// Breadth first traversal over the faces connected to a seed face across edges,
+/// yielding each face with its depth (edge crossings from the seed)
+pub struct BfsFaceIter<'a> {
+    mesh: &'a SMesh,
+    queue: VecDeque<(FaceId, usize)>,
+    visited: HashSet<FaceId>,
+    max_depth: Option<usize>,
+}
+
+impl<'a> BfsFaceIter<'a> {
+    /// Stop the traversal after `max_depth` edge crossings
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+}
+
+impl<'a> Iterator for BfsFaceIter<'a> {
+    type Item = (FaceId, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (f, depth) = self.queue.pop_front()?;
+        if self.max_depth.is_none_or(|max| depth < max) {
+            for n in f.neighbors(self.mesh) {
+                if self.visited.insert(n) {
+                    self.queue.push_back((n, depth + 1));
+                }
+            }
+        }
+        Some((f, depth))
+    }
+}
+
+/// Traversals
+impl SMesh {
+    /// Breadth first traversal from `seed`, empty if the vertex does not exist
+    pub fn bfs_vertices(&self, seed: VertexId) -> BfsVertexIter<'_> {
+        let queue = if self.vertices().contains_key(seed) {
+            VecDeque::from([(seed, 0)])
+        } else {
+            VecDeque::new()
+        };
+        BfsVertexIter {
+            mesh: self,
+            queue,
+            visited: HashSet::from([seed]),
+            max_depth: None,
+        }
+    }
+
+    /// Breadth first traversal from `seed`, empty if the face does not exist
+    pub fn bfs_faces(&self, seed: FaceId) -> BfsFaceIter<'_> {
+        let queue = if self.faces().contains_key(seed) {
+            VecDeque::from([(seed, 0)])
+        } else {
+            VecDeque::new()
+        };
+        BfsFaceIter {
+            mesh: self,
+            queue,
+            visited: HashSet::from([seed]),
+            max_depth: None,
+        }
+    }
+}
+
+/// Upper bound for the length of a single `next` cycle. Walks that take longer
+/// are stuck in a cycle that does not contain their start, which is a topology error.
+pub const MAX_LOOP_ITERATIONS: usize = 1_000_000;
+
+/// Loops
+impl SMesh {
+    /// The cycle of halfedges reached from `h` by following `next`, starting with `h`.
+    /// Fails instead of looping forever if the cycle never returns to `h`.
+    pub fn halfedge_loop(&self, h: HalfedgeId) -> SMeshResult<Vec<HalfedgeId>> {
+        let max = self.halfedges().len().min(MAX_LOOP_ITERATIONS);
+        let mut halfedges = vec![h];
+        let mut current = h.next().run(self)?;
+        while current != h {
+            if halfedges.len() >= max {
+                bail!(TopologyError);
+            }
+            halfedges.push(current);
+            current = current.next().run(self)?;
+        }
+        Ok(halfedges)
+    }
+
+    /// The halfedges bounding face `f`, checking that every one of them belongs to `f`
+    pub fn face_loop(&self, f: FaceId) -> SMeshResult<Vec<HalfedgeId>> {
+        let halfedges = self.halfedge_loop(f.halfedge().run(self)?)?;
+        for h in &halfedges {
+            if h.face().run(self) != Ok(f) {
+                bail!(TopologyError);
+            }
+        }
+        Ok(halfedges)
+    }
+}
+
 pub trait VertexIterators {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter;
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter;
-    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter;
+    /// Neighbouring vertices in counter clockwise order
+    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter<'_>;
+    /// Neighbouring vertices in clockwise order
+    fn vertices_cw(self, mesh: &SMesh) -> VertexAroundVertexIter<'_>;
+    /// Outgoing halfedges in counter clockwise order
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_>;
+    /// Outgoing halfedges in clockwise order
+    fn halfedges_cw(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_>;
+    fn edges(self, mesh: &SMesh) -> EdgeAroundVertexIter<'_>;
+    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter<'_>;
 }
 
 pub trait FaceIterators {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter;
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter;
+    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter<'_>;
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter<'_>;
+    /// Faces sharing an edge with the face
+    fn neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_>;
+    /// Faces sharing at least a vertex with the face
+    fn vertex_neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_>;
 }
 
 impl VertexIterators for MeshQueryBuilder<VertexId> {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter {
+    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter<'_> {
         let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
         VertexAroundVertexIter {
             conn: &mesh.connectivity,
             start,
             current: Some(start),
+            clockwise: false,
+        }
+    }
+
+    fn vertices_cw(self, mesh: &SMesh) -> VertexAroundVertexIter<'_> {
+        VertexAroundVertexIter {
+            clockwise: true,
+            ..self.vertices(mesh)
         }
     }
 
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter {
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_> {
         let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
         HalfedgeAroundVertexIter {
             conn: &mesh.connectivity,
             start,
             current: Some(start),
+            clockwise: false,
         }
     }
 
-    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter {
+    fn halfedges_cw(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_> {
+        HalfedgeAroundVertexIter {
+            clockwise: true,
+            ..self.halfedges(mesh)
+        }
+    }
+
+    fn edges(self, mesh: &SMesh) -> EdgeAroundVertexIter<'_> {
+        let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
+        EdgeAroundVertexIter {
+            conn: &mesh.connectivity,
+            start,
+            current: Some(start),
+        }
+    }
+
+    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter<'_> {
         let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
         FaceAroundVertexIter {
             conn: &mesh.connectivity,
@@ -142,21 +398,33 @@ impl VertexIterators for MeshQueryBuilder<VertexId> {
 }
 
 impl VertexIterators for VertexId {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter {
+    fn vertices(self, mesh: &SMesh) -> VertexAroundVertexIter<'_> {
         self.q().vertices(mesh)
     }
 
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter {
+    fn vertices_cw(self, mesh: &SMesh) -> VertexAroundVertexIter<'_> {
+        self.q().vertices_cw(mesh)
+    }
+
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_> {
         self.q().halfedges(mesh)
     }
 
-    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter {
+    fn halfedges_cw(self, mesh: &SMesh) -> HalfedgeAroundVertexIter<'_> {
+        self.q().halfedges_cw(mesh)
+    }
+
+    fn edges(self, mesh: &SMesh) -> EdgeAroundVertexIter<'_> {
+        self.q().edges(mesh)
+    }
+
+    fn faces(self, mesh: &SMesh) -> FaceAroundVertexIter<'_> {
         self.q().faces(mesh)
     }
 }
 
 impl FaceIterators for MeshQueryBuilder<FaceId> {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter {
+    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter<'_> {
         let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
         VertexAroundFaceIter {
             conn: &mesh.connectivity,
@@ -165,7 +433,7 @@ impl FaceIterators for MeshQueryBuilder<FaceId> {
         }
     }
 
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter {
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter<'_> {
         let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
         HalfedgeAroundFaceIter {
             conn: &mesh.connectivity,
@@ -173,18 +441,37 @@ impl FaceIterators for MeshQueryBuilder<FaceId> {
             current: Some(start),
         }
     }
+
+    fn neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_> {
+        let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
+        FaceAroundFaceIter::new(&mesh.connectivity, start, false)
+    }
+
+    fn vertex_neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_> {
+        let start = self.halfedge().run(mesh).unwrap_or(HalfedgeId::default());
+        FaceAroundFaceIter::new(&mesh.connectivity, start, true)
+    }
 }
 
 impl FaceIterators for FaceId {
-    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter {
+    fn vertices(self, mesh: &SMesh) -> VertexAroundFaceIter<'_> {
         self.q().vertices(mesh)
     }
 
-    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter {
+    fn halfedges(self, mesh: &SMesh) -> HalfedgeAroundFaceIter<'_> {
         self.q().halfedges(mesh)
     }
+
+    fn neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_> {
+        self.q().neighbors(mesh)
+    }
+
+    fn vertex_neighbors(self, mesh: &SMesh) -> FaceAroundFaceIter<'_> {
+        self.q().vertex_neighbors(mesh)
+    }
 }
 
+#[cfg(test)]
 mod test {
     use super::*;
     use glam::vec3;
@@ -216,6 +503,12 @@ mod test {
             ids.push(v_id);
         }
         assert_eq!(ids, vec![v3, v4, v1]);
+
+        assert_eq!(v0.vertices_cw(mesh).collect_vec(), vec![v3, v1, v4]);
+        let ccw = v0.halfedges(mesh).collect_vec();
+        let cw = v0.halfedges_cw(mesh).collect_vec();
+        assert_eq!(cw[0], ccw[0]);
+        assert_eq!(cw[1..], ccw[1..].iter().rev().copied().collect_vec());
     }
 
     #[test]
@@ -236,4 +529,103 @@ mod test {
         ids = f1.vertices(mesh).collect_vec();
         assert_eq!(ids, vec![v0, v4, v1,]);
     }
+
+    #[test]
+    fn edge_around_vertex() -> SMeshResult<()> {
+        let mesh = crate::test_utils::quad_grid(2)?;
+        for v in mesh.vertices().keys() {
+            let edges = v.edges(&mesh).collect_vec();
+            assert_eq!(edges.len(), v.halfedges(&mesh).count());
+            assert_eq!(edges.iter().unique().count(), edges.len());
+            for (h, e) in v.halfedges(&mesh).zip(edges) {
+                let o = h.opposite().run(&mesh)?;
+                assert_eq!(e, o.min(h));
+                // both endpoints agree on the canonical halfedge
+                assert!(h.dst_vert().run(&mesh)?.edges(&mesh).contains(&e));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn face_around_face() -> SMeshResult<()> {
+        let mesh = crate::test_utils::quad_grid(3)?;
+        let face_at = |x: f32, y: f32| {
+            mesh.faces()
+                .keys()
+                .find(|f| {
+                    let c = f
+                        .vertices(&mesh)
+                        .map(|v| mesh.positions[v])
+                        .sum::<glam::Vec3>()
+                        / 4.0;
+                    c.distance(vec3(x, y, 0.0)) < 1e-5
+                })
+                .unwrap()
+        };
+        let center = face_at(1.5, 1.5);
+        let neighbors = center.neighbors(&mesh).collect_vec();
+        assert_eq!(neighbors.len(), 4);
+        assert!(neighbors.contains(&face_at(1.5, 0.5)));
+        assert!(!neighbors.contains(&center));
+        assert_eq!(center.vertex_neighbors(&mesh).count(), 8);
+
+        let corner = face_at(0.5, 0.5);
+        assert_eq!(corner.neighbors(&mesh).count(), 2);
+        let vertex_neighbors = corner.vertex_neighbors(&mesh).collect_vec();
+        assert_eq!(vertex_neighbors.len(), 3);
+        assert!(vertex_neighbors.contains(&face_at(1.5, 1.5)));
+        Ok(())
+    }
+
+    #[test]
+    fn bfs() -> SMeshResult<()> {
+        let mesh = crate::test_utils::quad_grid(3)?;
+        let corner = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == glam::Vec3::ZERO)
+            .unwrap();
+        let visited = mesh.bfs_vertices(corner).collect_vec();
+        assert_eq!(visited.len(), 16);
+        assert_eq!(visited[0], (corner, 0));
+        assert!(visited
+            .iter()
+            .map(|(_, d)| d)
+            .tuple_windows()
+            .all(|(a, b)| a <= b));
+        for (v, depth) in &visited {
+            // manhattan distance on the grid
+            let p = mesh.positions[*v];
+            assert_eq!(*depth, (p.x + p.y) as usize);
+        }
+        assert_eq!(mesh.bfs_vertices(corner).with_max_depth(1).count(), 3);
+
+        let face = corner.faces(&mesh).next().unwrap();
+        assert_eq!(mesh.bfs_faces(face).count(), 9);
+        let near = mesh.bfs_faces(face).with_max_depth(1).collect_vec();
+        assert_eq!(near.len(), 3);
+        assert!(near.iter().skip(1).all(|(_, d)| *d == 1));
+        Ok(())
+    }
+
+    #[test]
+    fn loops() -> SMeshResult<()> {
+        let mut mesh = crate::test_utils::quad_grid(1)?;
+        let f = mesh.faces().keys().next().unwrap();
+        let halfedges = mesh.face_loop(f)?;
+        assert_eq!(halfedges, f.halfedges(&mesh).collect_vec());
+
+        let boundary = halfedges[0].opposite().run(&mesh)?;
+        assert_eq!(mesh.halfedge_loop(boundary)?.len(), 4);
+        assert_eq!(mesh.face_loop(f)?.len(), 4);
+
+        // a cycle that never returns to its start
+        mesh.get_mut(halfedges[3]).set_next(Some(halfedges[1]))?;
+        assert_eq!(
+            mesh.halfedge_loop(halfedges[0]),
+            Err(SMeshError::TopologyError)
+        );
+        Ok(())
+    }
 }
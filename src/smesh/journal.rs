@@ -0,0 +1,263 @@
+use glam::{Mat4, Quat, Vec3};
+
+use crate::prelude::*;
+
+/// A high level edit recorded in the operation journal, see [`SMesh::start_journal`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
+    ExtrudeFaces {
+        faces: Vec<FaceId>,
+        mode: ExtrudeMode,
+    },
+    ExtrudeFacesAlongNormals {
+        faces: Vec<FaceId>,
+        distance: f32,
+        mode: ExtrudeMode,
+    },
+    Translate {
+        vertices: Vec<VertexId>,
+        offset: Vec3,
+        falloff: Option<Falloff>,
+    },
+    Scale {
+        vertices: Vec<VertexId>,
+        factor: Vec3,
+        pivot: Pivot,
+        falloff: Option<Falloff>,
+    },
+    Rotate {
+        vertices: Vec<VertexId>,
+        rotation: Quat,
+        pivot: Pivot,
+        falloff: Option<Falloff>,
+    },
+    Shear {
+        vertices: Vec<VertexId>,
+        direction: Vec3,
+        axis: Vec3,
+        factor: f32,
+        pivot: Pivot,
+    },
+    Transform {
+        vertices: Vec<VertexId>,
+        matrix: Mat4,
+    },
+    Twist {
+        vertices: Vec<VertexId>,
+        axis: Vec3,
+        angle_per_unit: f32,
+        pivot: Pivot,
+    },
+    Bend {
+        vertices: Vec<VertexId>,
+        axis: Vec3,
+        direction: Vec3,
+        angle_per_unit: f32,
+        pivot: Pivot,
+    },
+    Taper {
+        vertices: Vec<VertexId>,
+        axis: Vec3,
+        factor_per_unit: f32,
+        pivot: Pivot,
+    },
+}
+
+/// Operations recorded since [`SMesh::start_journal`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Journal {
+    operations: Vec<Operation>,
+    /// Topology changes of the mesh when the last operation was recorded
+    changes: u64,
+    /// Whether the topology was changed by an operation that is not recorded
+    incomplete: bool,
+}
+
+impl SMesh {
+    /// Start recording high level edits with their parameters. Replaying the journal
+    /// on a copy of the mesh at the time the recording started, or on a mesh built the
+    /// same way, repeats the edits, since element ids are created deterministically.
+    ///
+    /// Only extrusions and transformations are recorded. Other topological edits, like
+    /// inserting vertices, collapsing edges, bisecting or simplifying, make
+    /// [`SMesh::stop_journal`] fail, since replaying would diverge from the mesh.
+    /// Positions changed in any other way, e.g. by smoothing or by writing to
+    /// [`SMesh::positions`], are not detected and are missing from the journal.
+    pub fn start_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Journal {
+                changes: self.connectivity.changes,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Stop recording and return the recorded operations.
+    /// Fails with [`SMeshError::UnsupportedOperation`] if the topology was changed by an
+    /// operation that can't be recorded.
+    pub fn stop_journal(&mut self) -> SMeshResult<Vec<Operation>> {
+        self.check_journal();
+        match self.journal.take() {
+            Some(journal) if journal.incomplete => Err(SMeshError::UnsupportedOperation),
+            journal => Ok(journal.map(|j| j.operations).unwrap_or_default()),
+        }
+    }
+
+    /// The operations recorded so far, if recording
+    pub fn journal(&self) -> Option<&[Operation]> {
+        self.journal.as_ref().map(|j| j.operations.as_slice())
+    }
+
+    /// Apply a single recorded operation
+    pub fn apply(&mut self, operation: &Operation) -> SMeshResult<()> {
+        match operation {
+            Operation::ExtrudeFaces { faces, mode } => {
+                self.extrude_faces_with_mode(faces.clone(), *mode)?;
+            }
+            Operation::ExtrudeFacesAlongNormals {
+                faces,
+                distance,
+                mode,
+            } => {
+                self.extrude_faces_along_normals_with_mode(faces.clone(), *distance, *mode)?;
+            }
+            Operation::Translate {
+                vertices,
+                offset,
+                falloff,
+            } => self.translate_impl(vertices.clone(), *offset, *falloff)?,
+            Operation::Scale {
+                vertices,
+                factor,
+                pivot,
+                falloff,
+            } => self.scale_impl(vertices.clone(), *factor, *pivot, *falloff)?,
+            Operation::Rotate {
+                vertices,
+                rotation,
+                pivot,
+                falloff,
+            } => self.rotate_impl(vertices.clone(), *rotation, *pivot, *falloff)?,
+            Operation::Shear {
+                vertices,
+                direction,
+                axis,
+                factor,
+                pivot,
+            } => self.shear(vertices.clone(), *direction, *axis, *factor, *pivot)?,
+            Operation::Transform { vertices, matrix } => {
+                self.transform(vertices.clone(), *matrix)?
+            }
+            Operation::Twist {
+                vertices,
+                axis,
+                angle_per_unit,
+                pivot,
+            } => self.twist(vertices.clone(), *axis, *angle_per_unit, *pivot)?,
+            Operation::Bend {
+                vertices,
+                axis,
+                direction,
+                angle_per_unit,
+                pivot,
+            } => self.bend(vertices.clone(), *axis, *direction, *angle_per_unit, *pivot)?,
+            Operation::Taper {
+                vertices,
+                axis,
+                factor_per_unit,
+                pivot,
+            } => self.taper(vertices.clone(), *axis, *factor_per_unit, *pivot)?,
+        }
+        Ok(())
+    }
+
+    /// Apply the recorded operations in order. Nothing is changed if one of them fails.
+    pub fn replay(&mut self, operations: &[Operation]) -> SMeshResult<()> {
+        self.transaction(|tx| {
+            for operation in operations {
+                tx.apply(operation)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Append an operation that doesn't change the topology to the journal, if recording
+    pub(crate) fn record(&mut self, operation: impl FnOnce() -> Operation) {
+        self.check_journal();
+        self.record_topology(operation);
+    }
+
+    /// Append an operation that changed the topology to the journal, if recording.
+    /// The operation needs to call [`SMesh::check_journal`] before its first change.
+    pub(crate) fn record_topology(&mut self, operation: impl FnOnce() -> Operation) {
+        let changes = self.connectivity.changes;
+        if let Some(journal) = self.journal.as_mut() {
+            journal.operations.push(operation());
+            journal.changes = changes;
+        }
+    }
+
+    /// Mark the journal as incomplete if the topology changed since the last
+    /// recorded operation
+    pub(crate) fn check_journal(&mut self) {
+        let changes = self.connectivity.changes;
+        if let Some(journal) = self.journal.as_mut() {
+            journal.incomplete |= journal.changes != changes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+    use glam::vec3;
+    use itertools::Itertools;
+
+    #[test]
+    fn replay() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let base = mesh.clone();
+        mesh.start_journal();
+        let face = mesh.faces().keys().next().unwrap();
+        let extruded = mesh.extrude_faces_along_normals(face, 0.5)?;
+        mesh.scale(extruded.clone(), Vec3::splat(0.5), Pivot::SelectionCenter)?;
+        mesh.translate(extruded, vec3(0.0, 0.0, 1.0))?;
+        let journal = mesh.stop_journal()?;
+        assert_eq!(journal.len(), 3);
+        assert!(mesh.journal().is_none());
+
+        let mut replayed = base.clone();
+        replayed.replay(&journal)?;
+        assert_eq!(replayed.faces().len(), mesh.faces().len());
+        for v in mesh.vertices().keys() {
+            assert_eq!(replayed.positions[v], mesh.positions[v]);
+        }
+
+        // undo the last operation
+        let mut undone = base.clone();
+        undone.replay(&journal[..2])?;
+        let moved = mesh
+            .vertices()
+            .keys()
+            .filter(|v| undone.positions[*v] != mesh.positions[*v]);
+        assert_eq!(moved.collect_vec().len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn unrecorded_topology_change() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        mesh.start_journal();
+        let face = mesh.faces().keys().next().unwrap();
+        mesh.extrude_faces(face)?;
+        let v = mesh.vertices().keys().next().unwrap();
+        let h = v.halfedge().run(&mesh)?;
+        let inserted = mesh.add_vertex(Vec3::ZERO);
+        mesh.insert_vertex(h, inserted)?;
+        mesh.translate(v, Vec3::X)?;
+        assert_eq!(mesh.stop_journal(), Err(SMeshError::UnsupportedOperation));
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use crate::bail;
 use crate::prelude::{FaceIterators, SMesh, SMeshError, SMeshResult, VertexIterators};
 use crate::smesh::{Connectivity, FaceId, HalfedgeId, VertexId};
+use glam::Vec3;
+use itertools::Itertools;
+use smallvec::SmallVec;
 use std::marker::PhantomData;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,10 +26,13 @@ enum QueryOp {
     HalfedgeTo(VertexId),
 }
 
+/// Number of operations a query can hold without allocating
+const INLINE_QUERY_OPS: usize = 6;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MeshQueryBuilder<T> {
     initial: QueryParam,
-    history: Vec<QueryOp>,
+    history: SmallVec<[QueryOp; INLINE_QUERY_OPS]>,
     phantom_data: PhantomData<T>,
 }
 
@@ -42,15 +48,62 @@ impl<T> MeshQueryBuilder<T> {
     }
 
     fn evaluate_operations(&self, c: &Connectivity) -> SMeshResult<QueryParam> {
-        let mut value = self.initial;
-        for op in &self.history {
-            value = match value {
-                QueryParam::Vertex(id) => eval_vertex_op(c, id, *op)?,
-                QueryParam::Halfedge(id) => eval_halfedge_op(c, id, *op)?,
-                QueryParam::Face(id) => eval_face_op(c, id, *op)?,
-            };
+        evaluate(self.initial, &self.history, c)
+    }
+
+    /// Compile the operations of this query, so they can be run from many
+    /// starting elements of the same type without building a new query each time.
+    /// The starting element of this query only determines the type.
+    /// ```
+    /// use glam::vec3;
+    /// use smesh::prelude::*;
+    ///
+    /// let mut mesh = SMesh::new();
+    /// let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));
+    /// let v1 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+    /// let v2 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+    /// mesh.add_triangle(v0, v1, v2).unwrap();
+    ///
+    /// let next_dst = HalfedgeId::default().next().dst_vert().compile();
+    /// let h = v0.halfedge_to(v1).run(&mesh).unwrap();
+    /// assert_eq!(next_dst.run_from(h, &mesh), Ok(v2));
+    /// ```
+    pub fn compile(&self) -> CompiledQuery<T> {
+        CompiledQuery {
+            initial: std::mem::discriminant(&self.initial),
+            history: self.history.clone(),
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+fn evaluate(initial: QueryParam, history: &[QueryOp], c: &Connectivity) -> SMeshResult<QueryParam> {
+    let mut value = initial;
+    for op in history {
+        value = match value {
+            QueryParam::Vertex(id) => eval_vertex_op(c, id, *op)?,
+            QueryParam::Halfedge(id) => eval_halfedge_op(c, id, *op)?,
+            QueryParam::Face(id) => eval_face_op(c, id, *op)?,
+        };
+    }
+    Ok(value)
+}
+
+/// Query operations that can be run from different starting elements,
+/// see [`MeshQueryBuilder::compile`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledQuery<T> {
+    initial: std::mem::Discriminant<QueryParam>,
+    history: SmallVec<[QueryOp; INLINE_QUERY_OPS]>,
+    phantom_data: PhantomData<T>,
+}
+
+impl<T> CompiledQuery<T> {
+    fn evaluate_from(&self, initial: QueryParam, c: &Connectivity) -> SMeshResult<QueryParam> {
+        if std::mem::discriminant(&initial) != self.initial {
+            bail!(DefaultError);
         }
-        Ok(value)
+        evaluate(initial, &self.history, c)
     }
 }
 
@@ -68,7 +121,7 @@ macro_rules! impl_mesh_query_for {
             fn q(&self) -> MeshQueryBuilder<$type> {
                 MeshQueryBuilder {
                     initial: QueryParam::$enum_variant(self.clone()),
-                    history: vec![],
+                    history: SmallVec::new(),
                     phantom_data: PhantomData,
                 }
             }
@@ -76,14 +129,39 @@ macro_rules! impl_mesh_query_for {
 
         impl RunQuery<$type, SMesh> for MeshQueryBuilder<$type> {
             fn run(self, mesh: &SMesh) -> SMeshResult<$type> {
-                match self.evaluate_operations(&mesh.connectivity)? {
+                (&self).run(&mesh.connectivity)
+            }
+        }
+
+        impl RunQuery<$type, Connectivity> for MeshQueryBuilder<$type> {
+            fn run(self, connectivity: &Connectivity) -> SMeshResult<$type> {
+                (&self).run(connectivity)
+            }
+        }
+
+        impl CompiledQuery<$type> {
+            /// Run the query from `id`, which needs to have the same type as the
+            /// starting element of the compiled query
+            pub fn run_from<S: ToMeshQueryBuilder<S>>(
+                &self,
+                id: S,
+                mesh: &SMesh,
+            ) -> SMeshResult<$type> {
+                match self.evaluate_from(id.q().initial, &mesh.connectivity)? {
                     QueryParam::$enum_variant(id) => Ok(id),
                     _ => Err(SMeshError::DefaultError),
                 }
             }
         }
 
-        impl RunQuery<$type, Connectivity> for MeshQueryBuilder<$type> {
+        /// Run a query without consuming it
+        impl RunQuery<$type, SMesh> for &MeshQueryBuilder<$type> {
+            fn run(self, mesh: &SMesh) -> SMeshResult<$type> {
+                self.run(&mesh.connectivity)
+            }
+        }
+
+        impl RunQuery<$type, Connectivity> for &MeshQueryBuilder<$type> {
             fn run(self, connectivity: &Connectivity) -> SMeshResult<$type> {
                 match self.evaluate_operations(connectivity)? {
                     QueryParam::$enum_variant(id) => Ok(id),
@@ -286,6 +364,11 @@ impl HalfedgeOps for HalfedgeId {
 pub trait FaceOps {
     fn halfedge(&self) -> MeshQueryBuilder<HalfedgeId>;
     fn valence(self, mesh: &SMesh) -> usize;
+    /// Unit face normal, computed with Newell's method so that
+    /// non-planar polygons yield a sensible average
+    fn normal(self, mesh: &SMesh) -> SMeshResult<Vec3>;
+    /// Area of the face, exact for planar polygons
+    fn area(self, mesh: &SMesh) -> SMeshResult<f32>;
 }
 impl FaceOps for MeshQueryBuilder<FaceId> {
     fn halfedge(&self) -> MeshQueryBuilder<HalfedgeId> {
@@ -295,6 +378,26 @@ impl FaceOps for MeshQueryBuilder<FaceId> {
     fn valence(self, mesh: &SMesh) -> usize {
         self.vertices(mesh).count()
     }
+
+    fn normal(self, mesh: &SMesh) -> SMeshResult<Vec3> {
+        Ok(newell_normal(self, mesh)?.normalize_or_zero())
+    }
+
+    fn area(self, mesh: &SMesh) -> SMeshResult<f32> {
+        Ok(newell_normal(self, mesh)?.length() * 0.5)
+    }
+}
+
+/// Un-normalized face normal, with a length of twice the face area
+fn newell_normal(face: MeshQueryBuilder<FaceId>, mesh: &SMesh) -> SMeshResult<Vec3> {
+    let mut positions = vec![];
+    for v in face.vertices(mesh) {
+        positions.push(*mesh.positions.get(v).ok_or(SMeshError::VertexNotFound(v))?);
+    }
+    Ok(positions
+        .iter()
+        .circular_tuple_windows()
+        .fold(Vec3::ZERO, |n, (p0, p1)| n + p0.cross(*p1)))
 }
 
 impl FaceOps for FaceId {
@@ -305,6 +408,14 @@ impl FaceOps for FaceId {
     fn valence(self, mesh: &SMesh) -> usize {
         self.q().valence(mesh)
     }
+
+    fn normal(self, mesh: &SMesh) -> SMeshResult<Vec3> {
+        self.q().normal(mesh)
+    }
+
+    fn area(self, mesh: &SMesh) -> SMeshResult<f32> {
+        self.q().area(mesh)
+    }
 }
 
 fn eval_vertex_op(c: &Connectivity, id: VertexId, op: QueryOp) -> SMeshResult<QueryParam> {
@@ -317,6 +428,9 @@ fn eval_vertex_op(c: &Connectivity, id: VertexId, op: QueryOp) -> SMeshResult<Qu
         }
         QueryOp::HalfedgeTo(dst_vertex) => {
             let initial_he = id.halfedge().run(c)?;
+            if let Some(h) = c.indexed_halfedge(id, dst_vertex) {
+                return Ok(QueryParam::Halfedge(h));
+            }
             let mut he = initial_he;
 
             let r = loop {
@@ -422,6 +536,48 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn long_query() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+
+        let v0 = mesh.add_vertex(vec3(-1.0, -1.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, -1.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(-1.0, 1.0, 0.0));
+        mesh.add_face(vec![v0, v1, v2, v3])?;
+
+        let h = v0.halfedge_to(v1).run(mesh)?;
+        // longer than the inline capacity of the query
+        let query = h.next().next().next().next().next().next().next().next();
+        assert_eq!((&query).run(mesh)?, h);
+        assert_eq!(query.dst_vert().run(mesh)?, v1);
+        Ok(())
+    }
+
+    #[test]
+    fn compiled_query() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+
+        let v0 = mesh.add_vertex(vec3(-1.0, -1.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, -1.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(-1.0, 1.0, 0.0));
+        let face = mesh.add_face(vec![v0, v1, v2, v3])?;
+
+        let query = HalfedgeId::default()
+            .opposite()
+            .next()
+            .opposite()
+            .face()
+            .compile();
+        for h in face.halfedges(mesh) {
+            let expected = h.opposite().next().opposite().face().run(mesh)?;
+            assert_eq!(query.run_from(h, mesh)?, expected);
+        }
+        assert_eq!(query.run_from(v0, mesh), Err(SMeshError::DefaultError));
+        Ok(())
+    }
+
     #[test]
     fn valence() -> SMeshResult<()> {
         let mesh = &mut SMesh::new();
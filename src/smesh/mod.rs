@@ -1,20 +1,50 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use glam::{Vec2, Vec3};
 use itertools::Itertools;
 use slotmap::{new_key_type, SecondaryMap, SlotMap};
 
-use crate::prelude::{attribute::CustomAttributeMap, SMeshError::FaceNotFound};
+use crate::bail;
+use crate::prelude::{
+    attribute::{CustomAttributeMap, TypedAttributes},
+    SMeshError::FaceNotFound,
+};
 use crate::smesh::error::*;
 use crate::smesh::mesh_query::*;
-use crate::{bail, prelude::attribute::MeshAttribute};
+use crate::smesh::observer::{MeshChange, Observers};
 
 pub mod attribute;
+pub mod bisect;
+pub mod boolean;
+pub mod builder;
+pub mod bvh;
+pub mod combine;
+pub mod curve;
+pub mod decimation;
+pub mod deform;
+pub mod dsmesh;
 pub mod edit_operations;
 pub mod error;
+pub mod generate;
+pub mod geometry;
+mod interpolate;
 pub mod iterators;
+pub mod journal;
 pub mod mesh_query;
+pub mod normals;
+pub mod observer;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod primitives;
+mod random;
+pub mod render;
+pub mod sdf;
+pub mod selection;
+pub mod shrinkwrap;
+pub mod smoothing;
 pub mod topological_operations;
+pub mod transform;
+pub mod uv_operations;
 
 new_key_type! { pub struct VertexId; }
 new_key_type! { pub struct HalfedgeId; }
@@ -25,6 +55,8 @@ pub struct Vertex {
     pub halfedge: Option<HalfedgeId>,
 }
 
+/// Connectivity of a halfedge. Optional ids are as large as plain ids,
+/// since `None` is stored in the unused zero version of the key.
 #[derive(Debug, Default, Clone)]
 pub struct Halfedge {
     pub vertex: VertexId,
@@ -49,6 +81,12 @@ pub struct Connectivity {
     pub vertices: SlotMap<VertexId, Vertex>,
     pub halfedges: SlotMap<HalfedgeId, Halfedge>,
     pub faces: SlotMap<FaceId, Face>,
+    /// Halfedges by their source and target vertex, see [`SMesh::build_edge_index`]
+    edge_index: Option<HashMap<(VertexId, VertexId), HalfedgeId>>,
+    /// Callbacks for topology changes, see [`SMesh::on_change`]
+    observers: Observers,
+    /// Number of topology changes so far, see [`SMesh::stop_journal`]
+    changes: u64,
 }
 
 impl Connectivity {
@@ -65,6 +103,41 @@ impl Connectivity {
     pub fn face_mut(&mut self, id: FaceId) -> Result<&mut Face, SMeshError> {
         self.faces.get_mut(id).ok_or(FaceNotFound(id))
     }
+
+    /// Look up the halfedge from v0 to v1 in the edge index.
+    /// Returns `None` if there is no index or the entry is missing or outdated,
+    /// in which case the caller falls back to walking the vertex ring.
+    pub(crate) fn indexed_halfedge(&self, v0: VertexId, v1: VertexId) -> Option<HalfedgeId> {
+        let index = self.edge_index.as_ref()?;
+        let h = index.get(&(v0, v1)).copied()?;
+        // entries of removed or modified halfedges are not cleaned up
+        let he = self.halfedges.get(h)?;
+        let o = self.halfedges.get(he.opposite?)?;
+        (he.vertex == v1 && o.vertex == v0).then_some(h)
+    }
+
+    pub(crate) fn notify(&mut self, change: MeshChange) {
+        self.changes += 1;
+        self.observers.notify(change);
+    }
+
+    /// Update the edge index entries of the halfedge and its opposite
+    fn index_edge(&mut self, h: HalfedgeId) {
+        let Some(index) = self.edge_index.as_mut() else {
+            return;
+        };
+        let Some(he) = self.halfedges.get(h) else {
+            return;
+        };
+        let Some(o) = he.opposite else {
+            return;
+        };
+        let Some(oe) = self.halfedges.get(o) else {
+            return;
+        };
+        index.insert((oe.vertex, he.vertex), h);
+        index.insert((he.vertex, oe.vertex), o);
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -76,9 +149,34 @@ pub struct SMesh {
     pub face_normals: Option<SecondaryMap<FaceId, Vec3>>,
     pub vertex_normals: Option<SecondaryMap<VertexId, Vec3>>,
     pub uvs: Option<SecondaryMap<HalfedgeId, Vec2>>,
+    /// Normal of the face corner at the source vertex of the halfedge,
+    /// see [`SMesh::recalculate_normals`]
+    pub halfedge_normals: Option<SecondaryMap<HalfedgeId, Vec3>>,
+    /// Crease weight in [0, 1] per edge, stored for both halfedges of an edge
+    pub edge_creases: Option<SecondaryMap<HalfedgeId, f32>>,
+    /// Edges marked as sharp, stored for both halfedges of an edge
+    pub sharp_edges: Option<SecondaryMap<HalfedgeId, bool>>,
+    /// Edges marked as uv seams, stored for both halfedges of an edge,
+    /// see [`SMesh::unwrap_with_seams`]
+    pub seams: Option<SecondaryMap<HalfedgeId, bool>>,
+    /// Smoothing group per face. Corner normals are only smoothed between faces of
+    /// the same group, faces in group 0 are flat shaded.
+    pub smoothing_groups: Option<SecondaryMap<FaceId, u32>>,
+    /// Material index per face, see [`SMesh::faces_by_material`]
+    pub material_indices: Option<SecondaryMap<FaceId, u32>>,
+    /// Mean curvature per vertex, see [`SMesh::compute_curvature`]
+    pub mean_curvature: Option<SecondaryMap<VertexId, f32>>,
+    /// Gaussian curvature per vertex, see [`SMesh::compute_curvature`]
+    pub gaussian_curvature: Option<SecondaryMap<VertexId, f32>>,
     vertex_attributes: HashMap<String, CustomAttributeMap<VertexId>>,
     edge_attributes: HashMap<String, CustomAttributeMap<HalfedgeId>>,
     face_attributes: HashMap<String, CustomAttributeMap<FaceId>>,
+    /// See [`SMesh::add_typed_attribute`]
+    typed_attributes: TypedAttributes,
+    /// Vertices whose normals are outdated, see [`SMesh::recalculate_dirty_normals`]
+    dirty_vertices: HashSet<VertexId>,
+    /// Recorded operations, see [`SMesh::start_journal`]
+    journal: Option<journal::Journal>,
 }
 
 /// Init, Getters
@@ -101,7 +199,10 @@ impl SMesh {
     pub fn vertices_mut(&mut self) -> &mut SlotMap<VertexId, Vertex> {
         &mut self.connectivity.vertices
     }
+    /// Direct access to the halfedges. This drops the edge index, since changes
+    /// can't be tracked, see [`SMesh::build_edge_index`]
     pub fn halfedges_mut(&mut self) -> &mut SlotMap<HalfedgeId, Halfedge> {
+        self.connectivity.edge_index = None;
         &mut self.connectivity.halfedges
     }
     pub fn faces_mut(&mut self) -> &mut SlotMap<FaceId, Face> {
@@ -110,18 +211,173 @@ impl SMesh {
     pub fn vert_mut(&mut self, id: VertexId) -> &mut Vertex {
         self.vertices_mut().get_mut(id).unwrap()
     }
+    /// Direct access to a halfedge. Like [`SMesh::halfedges_mut`] this drops the edge index
     pub fn he_mut(&mut self, id: HalfedgeId) -> &mut Halfedge {
-        self.halfedges_mut().get_mut(id).unwrap()
+        self.connectivity.edge_index = None;
+        self.connectivity.halfedges.get_mut(id).unwrap()
     }
     pub fn face_mut(&mut self, id: FaceId) -> &mut Face {
         self.faces_mut().get_mut(id).unwrap()
     }
-    pub fn get_mut<T>(&mut self, id: T) -> MeshMutator<T> {
+    pub fn get_mut<T>(&mut self, id: T) -> MeshMutator<'_, T> {
         MeshMutator {
             conn: &mut self.connectivity,
             value: id,
         }
     }
+
+    /// Build an index of all halfedges by their source and target vertex, so that
+    /// `halfedge_to` queries (which are also used by [`SMesh::add_face`]) don't need to
+    /// rotate around the source vertex anymore.
+    /// The index is kept up to date by all mesh operations, but gets dropped when
+    /// the halfedges are accessed directly with [`SMesh::halfedges_mut`].
+    pub fn build_edge_index(&mut self) {
+        let index = self
+            .halfedges()
+            .iter()
+            .filter_map(|(h, he)| {
+                let o = self.halfedges().get(he.opposite?)?;
+                Some(((o.vertex, he.vertex), h))
+            })
+            .collect();
+        self.connectivity.edge_index = Some(index);
+    }
+
+    /// Remove the edge index
+    pub fn clear_edge_index(&mut self) {
+        self.connectivity.edge_index = None;
+    }
+
+    pub fn has_edge_index(&self) -> bool {
+        self.connectivity.edge_index.is_some()
+    }
+}
+
+/// Built-in attributes
+impl SMesh {
+    /// Crease weight of the edge the halfedge belongs to, 0.0 if none is set
+    pub fn edge_crease(&self, h: HalfedgeId) -> f32 {
+        self.edge_creases
+            .as_ref()
+            .and_then(|creases| creases.get(h).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Set the crease weight for the edge the halfedge belongs to.
+    /// The weight gets clamped to [0, 1] and is stored for both halfedges.
+    pub fn set_edge_crease(&mut self, h: HalfedgeId, crease: f32) -> SMeshResult<()> {
+        let o = h.opposite().run(self)?;
+        let crease = crease.clamp(0.0, 1.0);
+        let creases = self.edge_creases.get_or_insert_with(SecondaryMap::new);
+        creases.insert(h, crease);
+        creases.insert(o, crease);
+        Ok(())
+    }
+
+    /// Remove the crease weight from the edge the halfedge belongs to
+    pub fn clear_edge_crease(&mut self, h: HalfedgeId) -> SMeshResult<()> {
+        let o = h.opposite().run(self)?;
+        if let Some(creases) = self.edge_creases.as_mut() {
+            creases.remove(h);
+            creases.remove(o);
+        }
+        Ok(())
+    }
+
+    /// Whether the edge the halfedge belongs to is marked as sharp
+    pub fn is_sharp(&self, h: HalfedgeId) -> bool {
+        self.sharp_edges
+            .as_ref()
+            .and_then(|sharp| sharp.get(h).copied())
+            .unwrap_or(false)
+    }
+
+    /// Mark or unmark the edge the halfedge belongs to as sharp
+    pub fn set_sharp(&mut self, h: HalfedgeId, sharp: bool) -> SMeshResult<()> {
+        let o = h.opposite().run(self)?;
+        let edges = self.sharp_edges.get_or_insert_with(SecondaryMap::new);
+        edges.insert(h, sharp);
+        edges.insert(o, sharp);
+        Ok(())
+    }
+
+    /// Whether the edge the halfedge belongs to is marked as uv seam
+    pub fn is_seam(&self, h: HalfedgeId) -> bool {
+        self.seams
+            .as_ref()
+            .and_then(|seams| seams.get(h).copied())
+            .unwrap_or(false)
+    }
+
+    /// Mark or unmark the edge the halfedge belongs to as uv seam
+    pub fn set_seam(&mut self, h: HalfedgeId, seam: bool) -> SMeshResult<()> {
+        let o = h.opposite().run(self)?;
+        let seams = self.seams.get_or_insert_with(SecondaryMap::new);
+        seams.insert(h, seam);
+        seams.insert(o, seam);
+        Ok(())
+    }
+
+    /// Smoothing group of the face, if one is set
+    pub fn smoothing_group(&self, f: FaceId) -> Option<u32> {
+        self.smoothing_groups
+            .as_ref()
+            .and_then(|groups| groups.get(f).copied())
+    }
+
+    /// Set the smoothing group of the face, 0 disables smoothing for the face
+    pub fn set_smoothing_group(&mut self, f: FaceId, group: u32) -> SMeshResult<()> {
+        if !self.faces().contains_key(f) {
+            bail!(FaceNotFound, f);
+        }
+        self.smoothing_groups
+            .get_or_insert_with(SecondaryMap::new)
+            .insert(f, group);
+        Ok(())
+    }
+
+    /// Remove the smoothing group from the face
+    pub fn clear_smoothing_group(&mut self, f: FaceId) {
+        if let Some(groups) = self.smoothing_groups.as_mut() {
+            groups.remove(f);
+        }
+    }
+
+    /// Material index of the face, 0 if none is set
+    pub fn material_index(&self, f: FaceId) -> u32 {
+        self.material_indices
+            .as_ref()
+            .and_then(|indices| indices.get(f).copied())
+            .unwrap_or(0)
+    }
+
+    /// Set the material index of the face
+    pub fn set_material_index(&mut self, f: FaceId, index: u32) -> SMeshResult<()> {
+        if !self.faces().contains_key(f) {
+            bail!(FaceNotFound, f);
+        }
+        self.material_indices
+            .get_or_insert_with(SecondaryMap::new)
+            .insert(f, index);
+        Ok(())
+    }
+
+    /// Remove the material index from the face
+    pub fn clear_material_index(&mut self, f: FaceId) {
+        if let Some(indices) = self.material_indices.as_mut() {
+            indices.remove(f);
+        }
+    }
+
+    /// Faces grouped by their material index, sorted by index.
+    /// Faces without a material index belong to material 0.
+    pub fn faces_by_material(&self) -> BTreeMap<u32, Vec<FaceId>> {
+        let mut groups: BTreeMap<u32, Vec<FaceId>> = BTreeMap::new();
+        for f in self.faces().keys() {
+            groups.entry(self.material_index(f)).or_default().push(f);
+        }
+        groups
+    }
 }
 
 /// Operations for adding mesh elements
@@ -137,13 +393,14 @@ impl SMesh {
     pub fn add_vertex(&mut self, position: Vec3) -> VertexId {
         let id = self.vertices_mut().insert(Vertex::default());
         self.positions.insert(id, position);
+        self.connectivity.notify(MeshChange::VertexAdded(id));
         id
     }
 
     /// Create an edge (2 halfedges) between two isolated vertices
     /// CARE!: This does not take care of connectivity for next/prev edges
     pub fn add_edge(&mut self, v0: VertexId, v1: VertexId) -> (HalfedgeId, HalfedgeId) {
-        let halfedges = self.halfedges_mut();
+        let halfedges = &mut self.connectivity.halfedges;
         let he_0_id = halfedges.insert(Halfedge::default());
         let he_1_id = halfedges.insert(Halfedge::default());
         let he_0 = halfedges.get_mut(he_0_id).unwrap();
@@ -152,6 +409,9 @@ impl SMesh {
         let he_1 = halfedges.get_mut(he_1_id).unwrap();
         he_1.vertex = v0;
         he_1.opposite = Some(he_0_id);
+        self.connectivity.index_edge(he_0_id);
+        self.connectivity.notify(MeshChange::HalfedgeAdded(he_0_id));
+        self.connectivity.notify(MeshChange::HalfedgeAdded(he_1_id));
         (he_0_id, he_1_id)
     }
 
@@ -222,7 +482,7 @@ impl SMesh {
                     // search a free gap
                     // free gap will be between boundaryPrev and boundaryNext
                     let outer_prev = inner_next.opposite();
-                    let outer_next = inner_prev.opposite();
+                    let _outer_next = inner_prev.opposite();
                     let mut boundary_prev = outer_prev.run(self)?;
                     loop {
                         boundary_prev = boundary_prev.next().opposite().run(self)?;
@@ -256,6 +516,7 @@ impl SMesh {
             halfedge: Some(halfedeges.get(n - 1).unwrap().0),
         };
         let face_id = self.faces_mut().insert(face);
+        self.connectivity.notify(MeshChange::FaceAdded(face_id));
 
         for (i, ii) in (0..n).circular_tuple_windows() {
             let v = vertices[ii];
@@ -296,13 +557,13 @@ impl SMesh {
             }
 
             // set face id
-            self.he_mut(halfedeges[i].0).face = Some(face_id);
+            self.connectivity.he_mut(halfedeges[i].0)?.face = Some(face_id);
         }
 
         // process next halfedge cache
         for (first, second) in next_cache {
-            self.he_mut(first).next = Some(second);
-            self.he_mut(second).prev = Some(first);
+            self.connectivity.he_mut(first)?.next = Some(second);
+            self.connectivity.he_mut(second)?.prev = Some(first);
         }
 
         for v_id in needs_adjust {
@@ -311,6 +572,156 @@ impl SMesh {
 
         Ok(face_id)
     }
+
+    /// Construct many faces at once from lists of isolated vertices.
+    /// This builds the connectivity of all faces in a single pass instead of
+    /// searching the existing halfedges for every edge like [`SMesh::add_face`].
+    /// Falls back to adding the faces one by one if any of the vertices is
+    /// already connected or the faces don't form a manifold surface around it.
+    pub fn add_faces(&mut self, faces: &[Vec<VertexId>]) -> SMeshResult<Vec<FaceId>> {
+        if faces.iter().any(|f| f.len() < 3) {
+            bail!(DefaultError);
+        }
+        let Some(links) = self.link_faces(faces) else {
+            return faces.iter().map(|f| self.add_face(f.clone())).collect();
+        };
+
+        let mut halfedges = HashMap::with_capacity(links.len());
+        for ((v0, v1), _) in &links {
+            if !halfedges.contains_key(&(*v0, *v1)) {
+                let (h, o) = self.add_edge(*v0, *v1);
+                halfedges.insert((*v0, *v1), h);
+                halfedges.insert((*v1, *v0), o);
+            }
+        }
+        for (edge, next) in &links {
+            let (h, next) = (halfedges[edge], halfedges[next]);
+            self.connectivity.he_mut(h)?.next = Some(next);
+            self.connectivity.he_mut(next)?.prev = Some(h);
+        }
+
+        let mut face_ids = Vec::with_capacity(faces.len());
+        for vertices in faces {
+            let face_id = self.faces_mut().insert(Face::default());
+            self.connectivity.notify(MeshChange::FaceAdded(face_id));
+            for (v0, v1) in vertices.iter().circular_tuple_windows() {
+                let h = halfedges[&(*v0, *v1)];
+                self.connectivity.he_mut(h)?.face = Some(face_id);
+                self.vert_mut(*v0).halfedge.get_or_insert(h);
+            }
+            let last = (vertices[vertices.len() - 1], vertices[0]);
+            self.face_mut(face_id).halfedge = Some(halfedges[&last]);
+            face_ids.push(face_id);
+        }
+        // outgoing halfedges of boundary vertices have to be boundary halfedges
+        for ((v0, _), h) in halfedges {
+            if h.is_boundary(self) {
+                self.vert_mut(v0).halfedge = Some(h);
+            }
+        }
+        Ok(face_ids)
+    }
+
+    /// The next halfedge of every halfedge of the faces, as pairs of source and
+    /// target vertices, or `None` if the faces can't be built in a single pass
+    #[allow(clippy::type_complexity)]
+    fn link_faces(
+        &self,
+        faces: &[Vec<VertexId>],
+    ) -> Option<Vec<((VertexId, VertexId), (VertexId, VertexId))>> {
+        let mut next = HashMap::new();
+        let mut links = vec![];
+        for vertices in faces {
+            for (v0, v1, v2) in vertices.iter().copied().circular_tuple_windows() {
+                if self.vertices().get(v0)?.halfedge.is_some() || v0 == v1 {
+                    return None;
+                }
+                if next.insert((v0, v1), (v1, v2)).is_some() {
+                    return None;
+                }
+                links.push(((v0, v1), (v1, v2)));
+            }
+        }
+
+        // every vertex can have at most one boundary gap
+        let face_links = links.len();
+        let mut boundary_out = HashMap::new();
+        for ((v0, v1), _) in &links[..face_links] {
+            if !next.contains_key(&(*v1, *v0)) && boundary_out.insert(*v1, (*v1, *v0)).is_some() {
+                return None;
+            }
+        }
+        for i in 0..face_links {
+            let (v0, v1) = links[i].0;
+            if !next.contains_key(&(v1, v0)) {
+                let boundary = (v1, v0);
+                let link = (boundary, *boundary_out.get(&v0)?);
+                links.push(link);
+            }
+        }
+        for (edge, n) in &links[face_links..] {
+            next.insert(*edge, *n);
+        }
+
+        // the halfedges around every vertex have to form a single ring
+        let prev: HashMap<_, _> = next.iter().map(|(h, n)| (*n, *h)).collect();
+        let mut outgoing: HashMap<VertexId, Vec<(VertexId, VertexId)>> = HashMap::new();
+        for (v0, v1) in next.keys() {
+            outgoing.entry(*v0).or_default().push((*v0, *v1));
+        }
+        for (_, edges) in outgoing {
+            let start = edges[0];
+            let mut h = start;
+            let mut count = 0;
+            loop {
+                h = (h.0, prev[&h].0);
+                count += 1;
+                if h == start || count > edges.len() {
+                    break;
+                }
+            }
+            if h != start || count != edges.len() {
+                return None;
+            }
+        }
+        Some(links)
+    }
+}
+
+/// Transactions
+impl SMesh {
+    /// Run `f` on the mesh and restore the previous state of the mesh, including all
+    /// attributes, if it returns an error. This keeps multi-step operations from leaving
+    /// the mesh half modified, at the cost of a copy of the mesh.
+    /// ```
+    /// use glam::vec3;
+    /// use smesh::prelude::*;
+    ///
+    /// let mut mesh = SMesh::new();
+    /// let result: SMeshResult<()> = mesh.transaction(|tx| {
+    ///     let v0 = tx.add_vertex(vec3(0.0, 0.0, 0.0));
+    ///     let v1 = tx.add_vertex(vec3(1.0, 0.0, 0.0));
+    ///     tx.add_face(vec![v0, v1])?;
+    ///     Ok(())
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(mesh.vertices().len(), 0);
+    /// ```
+    pub fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut SMesh) -> SMeshResult<T>,
+    ) -> SMeshResult<T> {
+        let snapshot = self.clone();
+        let result = f(self);
+        if result.is_err() {
+            // the snapshot is a clone without observers
+            let observers = std::mem::take(&mut self.connectivity.observers);
+            *self = snapshot;
+            self.connectivity.observers = observers;
+            self.connectivity.notify(MeshChange::Replaced);
+        }
+        result
+    }
 }
 
 pub struct MeshMutator<'a, T> {
@@ -345,7 +756,9 @@ impl MeshMutator<'_, VertexId> {
     }
 
     pub fn delete(self) -> SMeshResult<()> {
-        self.conn.vertices.remove(self.value);
+        if self.conn.vertices.remove(self.value).is_some() {
+            self.conn.notify(MeshChange::VertexRemoved(self.value));
+        }
         Ok(())
     }
 }
@@ -377,12 +790,14 @@ impl MeshMutator<'_, HalfedgeId> {
         let he = self.value;
         self.conn.he_mut(he)?.opposite = Some(opposite);
         self.conn.he_mut(opposite)?.opposite = Some(he);
+        self.conn.index_edge(he);
         Ok(())
     }
 
     /// Set the dst vertex id
     pub fn set_vertex(&mut self, vertex: VertexId) -> SMeshResult<()> {
         self.conn.he_mut(self.value)?.vertex = vertex;
+        self.conn.index_edge(self.value);
         Ok(())
     }
 
@@ -392,10 +807,13 @@ impl MeshMutator<'_, HalfedgeId> {
         Ok(())
     }
 
-    pub fn delete(mut self) -> SMeshResult<()> {
+    pub fn delete(self) -> SMeshResult<()> {
         if let Some(h) = self.conn.halfedges.remove(self.value) {
+            self.conn.notify(MeshChange::HalfedgeRemoved(self.value));
             if let Some(o) = h.opposite {
-                self.conn.halfedges.remove(o);
+                if self.conn.halfedges.remove(o).is_some() {
+                    self.conn.notify(MeshChange::HalfedgeRemoved(o));
+                }
             }
         }
         Ok(())
@@ -410,8 +828,10 @@ impl MeshMutator<'_, FaceId> {
         Ok(())
     }
 
-    pub fn delete(mut self) -> SMeshResult<()> {
-        self.conn.faces.remove(self.value);
+    pub fn delete(self) -> SMeshResult<()> {
+        if self.conn.faces.remove(self.value).is_some() {
+            self.conn.notify(MeshChange::FaceRemoved(self.value));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,434 @@
+use glam::Vec3;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::prelude::*;
+
+/// How the normals of the faces around a vertex are weighted
+/// when computing the vertex normal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalWeighting {
+    /// Every face contributes equally
+    Uniform,
+    /// Faces contribute proportional to their area
+    Area,
+    /// Faces contribute proportional to their corner angle at the vertex,
+    /// which is independent of how the faces are tessellated
+    #[default]
+    Angle,
+}
+
+impl SMesh {
+    /// Compute [`SMesh::face_normals`], [`SMesh::vertex_normals`] and
+    /// [`SMesh::halfedge_normals`] for the whole mesh.
+    /// Face corners are smoothly shaded across regular edges, but not across
+    /// edges marked as sharp (see [`SMesh::set_sharp`]) or between faces of different
+    /// smoothing groups (see [`SMesh::set_smoothing_group`]), so corner normals only
+    /// average the faces between the hard edges around their vertex.
+    pub fn recalculate_normals(&mut self, weighting: NormalWeighting) -> SMeshResult<()> {
+        let faces = self.faces().keys().collect_vec();
+        let vertices = self.vertices().keys().collect_vec();
+        self.face_normals = Some(SecondaryMap::new());
+        self.vertex_normals = Some(SecondaryMap::new());
+        self.halfedge_normals = Some(SecondaryMap::new());
+        self.dirty_vertices.clear();
+        self.update_normals(&faces, &vertices, weighting)
+    }
+
+    /// Recompute the normals around the selected vertices only, which is much cheaper
+    /// than [`SMesh::recalculate_normals`] after small local edits.
+    /// This updates the normals of all faces around the selected vertices and
+    /// the vertex and corner normals of these faces.
+    /// Falls back to a full recalculation if the mesh has no normals yet.
+    pub fn recalculate_normals_partial<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        weighting: NormalWeighting,
+    ) -> SMeshResult<()> {
+        if self.face_normals.is_none()
+            || self.vertex_normals.is_none()
+            || self.halfedge_normals.is_none()
+        {
+            return self.recalculate_normals(weighting);
+        }
+        let selected = selection.into().resolve_to_vertices(self)?;
+        let faces = selected
+            .iter()
+            .filter(|v| !v.is_isolated(self))
+            .flat_map(|v| v.faces(self))
+            .unique()
+            .collect_vec();
+        let vertices = faces
+            .iter()
+            .flat_map(|f| f.vertices(self))
+            .chain(selected.iter().copied())
+            .unique()
+            .collect_vec();
+        for v in &vertices {
+            self.dirty_vertices.remove(v);
+        }
+        self.update_normals(&faces, &vertices, weighting)
+    }
+
+    /// Mark the normals around the selected vertices as outdated,
+    /// see [`SMesh::recalculate_dirty_normals`]
+    pub fn mark_normals_dirty<S: Into<MeshSelection>>(&mut self, selection: S) -> SMeshResult<()> {
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        self.dirty_vertices.extend(vertices);
+        Ok(())
+    }
+
+    /// Whether any normals were marked as outdated since the last recalculation
+    pub fn has_dirty_normals(&self) -> bool {
+        !self.dirty_vertices.is_empty()
+    }
+
+    /// Recompute the normals around all vertices that were changed by edit operations
+    /// or marked with [`SMesh::mark_normals_dirty`] since the last recalculation
+    pub fn recalculate_dirty_normals(&mut self, weighting: NormalWeighting) -> SMeshResult<()> {
+        let dirty = std::mem::take(&mut self.dirty_vertices)
+            .into_iter()
+            .filter(|v| self.vertices().contains_key(*v))
+            .collect_vec();
+        if dirty.is_empty() {
+            return Ok(());
+        }
+        self.recalculate_normals_partial(dirty, weighting)
+    }
+
+    /// Recompute the normals of the given faces, then the vertex and corner normals
+    /// of the given vertices
+    fn update_normals(
+        &mut self,
+        faces: &[FaceId],
+        vertices: &[VertexId],
+        weighting: NormalWeighting,
+    ) -> SMeshResult<()> {
+        let updated_faces = faces
+            .iter()
+            .map(|f| Ok((*f, f.normal(self)?)))
+            .collect::<SMeshResult<Vec<_>>>()?;
+        let face_normals = self.face_normals.get_or_insert_default();
+        face_normals.extend(updated_faces);
+        let face_normals = self.face_normals.as_ref().unwrap();
+
+        let mut updated_vertices = vec![];
+        let mut updated_corners = vec![];
+        for v in vertices.iter().copied() {
+            if v.is_isolated(self) {
+                updated_vertices.push((v, Vec3::ZERO));
+                continue;
+            }
+            // weighted face normal of every corner, in ccw order around the vertex
+            let mut corners = vec![];
+            for h in v.halfedges(self) {
+                let normal = match h.face().run(self) {
+                    Ok(f) => {
+                        let normal = match face_normals.get(f) {
+                            Some(normal) => *normal,
+                            None => f.normal(self)?,
+                        };
+                        normal * self.corner_weight(h, f, weighting)?
+                    }
+                    Err(_) => Vec3::ZERO,
+                };
+                corners.push((h, normal));
+            }
+            let vertex_normal = corners.iter().map(|(_, n)| *n).sum::<Vec3>();
+            updated_vertices.push((v, vertex_normal.normalize_or_zero()));
+
+            // the corners of h and its ccw neighbour are separated by the neighbour's edge
+            let n = corners.len();
+            let smooth = (0..n)
+                .map(|i| self.is_smooth_edge(corners[i].0))
+                .collect::<SMeshResult<Vec<_>>>()?;
+            for i in 0..n {
+                let (h, normal) = corners[i];
+                if h.is_boundary(self) {
+                    continue;
+                }
+                let mut sum = normal;
+                let mut j = (i + 1) % n;
+                while j != i && smooth[j] {
+                    sum += corners[j].1;
+                    j = (j + 1) % n;
+                }
+                if j != i {
+                    let mut j = i;
+                    while smooth[j] {
+                        j = (j + n - 1) % n;
+                        sum += corners[j].1;
+                    }
+                }
+                updated_corners.push((h, sum.normalize_or_zero()));
+            }
+        }
+        self.vertex_normals
+            .get_or_insert_default()
+            .extend(updated_vertices);
+        self.halfedge_normals
+            .get_or_insert_default()
+            .extend(updated_corners);
+        Ok(())
+    }
+
+    /// Mark all edges as sharp where the angle between the normals of the two
+    /// adjacent faces is larger than `threshold` (in radians).
+    /// Other edges keep their current sharpness.
+    pub fn mark_sharp_by_angle(&mut self, threshold: f32) -> SMeshResult<()> {
+        let mut sharp = vec![];
+        for h in self.halfedges().keys() {
+            let o = h.opposite().run(self)?;
+            if h > o {
+                continue;
+            }
+            let (Ok(f0), Ok(f1)) = (h.face().run(self), o.face().run(self)) else {
+                continue;
+            };
+            if f0.normal(self)?.angle_between(f1.normal(self)?) > threshold {
+                sharp.push(h);
+            }
+        }
+        for h in sharp {
+            self.set_sharp(h, true)?;
+        }
+        Ok(())
+    }
+
+    /// Whether normals get smoothed across the edge of `h`
+    fn is_smooth_edge(&self, h: HalfedgeId) -> SMeshResult<bool> {
+        let (Ok(f0), Ok(f1)) = (h.face().run(self), h.opposite().face().run(self)) else {
+            return Ok(false);
+        };
+        if self.is_sharp(h) {
+            return Ok(false);
+        }
+        Ok(match (self.smoothing_group(f0), self.smoothing_group(f1)) {
+            (Some(0), _) | (_, Some(0)) => false,
+            (Some(g0), Some(g1)) => g0 == g1,
+            _ => true,
+        })
+    }
+
+    /// Weighted average of the normals of the faces around `v`, computed from the
+    /// current positions. Zero for isolated vertices.
+    pub(crate) fn vertex_normal(
+        &self,
+        v: VertexId,
+        weighting: NormalWeighting,
+    ) -> SMeshResult<Vec3> {
+        let mut normal = Vec3::ZERO;
+        if v.is_isolated(self) {
+            return Ok(normal);
+        }
+        for h in v.halfedges(self) {
+            if let Ok(f) = h.face().run(self) {
+                normal += f.normal(self)? * self.corner_weight(h, f, weighting)?;
+            }
+        }
+        Ok(normal.normalize_or_zero())
+    }
+
+    /// Weight of the face normal at the corner of the source vertex of `h`
+    fn corner_weight(
+        &self,
+        h: HalfedgeId,
+        f: FaceId,
+        weighting: NormalWeighting,
+    ) -> SMeshResult<f32> {
+        Ok(match weighting {
+            NormalWeighting::Uniform => 1.0,
+            NormalWeighting::Area => f.area(self)?,
+            NormalWeighting::Angle => {
+                let p = self.positions[h.src_vert().run(self)?];
+                let next = self.positions[h.dst_vert().run(self)?];
+                let prev = self.positions[h.prev().src_vert().run(self)?];
+                (next - p).angle_between(prev - p)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, uv_sphere};
+
+    /// Cube with one of the faces at the min corner split into two triangles
+    fn split_cube() -> SMeshResult<(SMesh, VertexId)> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let corner = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == Vec3::splat(-0.5))
+            .unwrap();
+        let face = corner
+            .faces(&mesh)
+            .find(|f| f.normal(&mesh).unwrap() == Vec3::NEG_Z)
+            .unwrap();
+        let halfedges = face.halfedges(&mesh).collect_vec();
+        let into_corner = halfedges
+            .iter()
+            .position(|h| h.dst_vert().run(&mesh) == Ok(corner))
+            .unwrap();
+        mesh.insert_edge(halfedges[into_corner], halfedges[(into_corner + 2) % 4])?;
+        Ok((mesh, corner))
+    }
+
+    #[test]
+    fn recalculate_normals() -> SMeshResult<()> {
+        let (mut mesh, corner) = split_cube()?;
+        let expected = Vec3::splat(-1.0).normalize();
+
+        mesh.recalculate_normals(NormalWeighting::Uniform)?;
+        assert_eq!(mesh.face_normals.as_ref().unwrap().len(), 7);
+        let uniform = mesh.vertex_normals.as_ref().unwrap()[corner];
+        assert!(uniform.distance(expected) > 0.1);
+
+        for weighting in [NormalWeighting::Area, NormalWeighting::Angle] {
+            mesh.recalculate_normals(weighting)?;
+            let normal = mesh.vertex_normals.as_ref().unwrap()[corner];
+            assert!(normal.distance(expected) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sharp_edges() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        mesh.mark_sharp_by_angle(30f32.to_radians())?;
+        assert!(mesh.halfedges().keys().all(|h| mesh.is_sharp(h)));
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let normals = mesh.halfedge_normals.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            let n = f.normal(&mesh)?;
+            assert!(f.halfedges(&mesh).all(|h| normals[h].distance(n) < 1e-5));
+        }
+
+        // only one sharp edge around a vertex keeps it smooth
+        let h = mesh.halfedges().keys().next().unwrap();
+        let v = h.src_vert().run(&mesh)?;
+        for h in v.halfedges(&mesh).collect_vec() {
+            mesh.set_sharp(h, false)?;
+        }
+        mesh.set_sharp(h, true)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let normal = mesh.vertex_normals.as_ref().unwrap()[v];
+        let normals = mesh.halfedge_normals.as_ref().unwrap();
+        assert!(v
+            .halfedges(&mesh)
+            .all(|h| normals[h].distance(normal) < 1e-5));
+
+        // two sharp edges split the corners around the vertex, the corner
+        // between them is flat shaded
+        let corners = v.halfedges(&mesh).collect_vec();
+        mesh.set_sharp(corners[0], true)?;
+        mesh.set_sharp(corners[1], true)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let normals = mesh.halfedge_normals.as_ref().unwrap();
+        let flat = corners[0].face().run(&mesh)?.normal(&mesh)?;
+        assert!(normals[corners[0]].distance(flat) < 1e-5);
+        assert!(normals[corners[1]].distance(normals[corners[2]]) < 1e-5);
+        assert!(normals[corners[1]].distance(flat) > 0.1);
+        Ok(())
+    }
+
+    #[test]
+    fn smooth_sphere_normals() -> SMeshResult<()> {
+        let mut mesh = uv_sphere(1.0, 8, 16)?;
+        mesh.mark_sharp_by_angle(60f32.to_radians())?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        assert!(mesh.halfedges().keys().all(|h| !mesh.is_sharp(h)));
+        let vertex_normals = mesh.vertex_normals.as_ref().unwrap();
+        for (h, n) in mesh.halfedge_normals.as_ref().unwrap() {
+            let v = h.src_vert().run(&mesh)?;
+            assert!(n.distance(vertex_normals[v]) < 1e-5);
+            assert!(n.dot(mesh.positions[v]) > 0.99);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn smoothing_groups() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        for f in &faces {
+            mesh.set_smoothing_group(*f, 1)?;
+        }
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        for (h, n) in mesh.halfedge_normals.as_ref().unwrap() {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            assert!(n.distance(p.normalize()) < 1e-5);
+        }
+
+        // a flat face and a separate group
+        mesh.set_smoothing_group(faces[0], 0)?;
+        mesh.set_smoothing_group(faces[1], 2)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let normals = mesh.halfedge_normals.as_ref().unwrap();
+        for f in &faces[0..2] {
+            let n = f.normal(&mesh)?;
+            assert!(f.halfedges(&mesh).all(|h| normals[h].distance(n) < 1e-5));
+        }
+        let f = faces[2];
+        assert!(f
+            .halfedges(&mesh)
+            .all(|h| normals[h].distance(f.normal(&mesh).unwrap()) > 0.1));
+
+        mesh.clear_smoothing_group(faces[0]);
+        assert_eq!(mesh.smoothing_group(faces[0]), None);
+        Ok(())
+    }
+
+    fn assert_normals_eq(a: &SMesh, b: &SMesh) {
+        let (fa, fb) = (
+            a.face_normals.as_ref().unwrap(),
+            b.face_normals.as_ref().unwrap(),
+        );
+        assert!(a.faces().keys().all(|f| fa[f].distance(fb[f]) < 1e-5));
+        let (va, vb) = (
+            a.vertex_normals.as_ref().unwrap(),
+            b.vertex_normals.as_ref().unwrap(),
+        );
+        assert!(a.vertices().keys().all(|v| va[v].distance(vb[v]) < 1e-5));
+        let (ha, hb) = (
+            a.halfedge_normals.as_ref().unwrap(),
+            b.halfedge_normals.as_ref().unwrap(),
+        );
+        for h in a.halfedges().keys().filter(|h| !h.is_boundary(a)) {
+            assert!(ha[h].distance(hb[h]) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn recalculate_normals_partial() -> SMeshResult<()> {
+        let mut mesh = uv_sphere(1.0, 8, 16)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let v = mesh.vertices().keys().nth(20).unwrap();
+        mesh.positions[v] *= 1.5;
+        mesh.recalculate_normals_partial(v, NormalWeighting::Angle)?;
+
+        let mut expected = mesh.clone();
+        expected.recalculate_normals(NormalWeighting::Angle)?;
+        assert_normals_eq(&mesh, &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn recalculate_dirty_normals() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        assert!(!mesh.has_dirty_normals());
+
+        let face = mesh.faces().keys().next().unwrap();
+        mesh.extrude_faces_along_normals(face, 0.5)?;
+        assert!(mesh.has_dirty_normals());
+        mesh.recalculate_dirty_normals(NormalWeighting::Angle)?;
+        assert!(!mesh.has_dirty_normals());
+
+        let mut expected = mesh.clone();
+        expected.recalculate_normals(NormalWeighting::Angle)?;
+        assert_normals_eq(&mesh, &expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// Topology change reported to the observers registered with [`SMesh::on_change`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshChange {
+    VertexAdded(VertexId),
+    VertexRemoved(VertexId),
+    HalfedgeAdded(HalfedgeId),
+    HalfedgeRemoved(HalfedgeId),
+    FaceAdded(FaceId),
+    FaceRemoved(FaceId),
+    /// The whole mesh was replaced, e.g. by a rolled back [`SMesh::transaction`],
+    /// so any element may have changed
+    Replaced,
+}
+
+type Observer = Arc<dyn Fn(MeshChange) + Send + Sync>;
+
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Observer>);
+
+/// A cloned mesh starts without observers, the callbacks of the original
+/// would get the ids of the clone's elements
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Observers {
+    pub(crate) fn notify(&self, change: MeshChange) {
+        for observer in &self.0 {
+            observer(change);
+        }
+    }
+}
+
+impl Debug for Observers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+impl SMesh {
+    /// Register a callback that gets called for every vertex, halfedge and face that
+    /// is added to or removed from the mesh, so external caches can be updated
+    /// incrementally. Clones of the mesh start without observers.
+    pub fn on_change(&mut self, observer: impl Fn(MeshChange) + Send + Sync + 'static) {
+        self.connectivity.observers.0.push(Arc::new(observer));
+    }
+
+    /// Remove all observers registered with [`SMesh::on_change`]
+    pub fn clear_observers(&mut self) {
+        self.connectivity.observers.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+    use glam::Vec3;
+    use std::sync::Mutex;
+
+    #[test]
+    fn on_change() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let changes = Arc::new(Mutex::new(vec![]));
+        let log = changes.clone();
+        mesh.on_change(move |change| log.lock().unwrap().push(change));
+
+        let face = mesh.faces().keys().next().unwrap();
+        let halfedges = mesh.halfedges().len();
+        mesh.extrude_faces(face)?;
+        let changes = std::mem::take(&mut *changes.lock().unwrap());
+        let count = |f: fn(&MeshChange) -> bool| changes.iter().filter(|c| f(c)).count();
+        assert_eq!(count(|c| matches!(c, MeshChange::VertexAdded(_))), 4);
+        assert_eq!(count(|c| matches!(c, MeshChange::FaceRemoved(_))), 1);
+        assert_eq!(count(|c| matches!(c, MeshChange::FaceAdded(_))), 5);
+        assert!(changes.contains(&MeshChange::FaceRemoved(face)));
+
+        let added = count(|c| matches!(c, MeshChange::HalfedgeAdded(_)));
+        let removed = count(|c| matches!(c, MeshChange::HalfedgeRemoved(_)));
+        assert_eq!(added - removed, mesh.halfedges().len() - halfedges);
+        Ok(())
+    }
+
+    #[test]
+    fn clone_has_no_observers() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let changes = Arc::new(Mutex::new(vec![]));
+        let log = changes.clone();
+        mesh.on_change(move |change| log.lock().unwrap().push(change));
+
+        let mut copy = mesh.clone();
+        let face = copy.faces().keys().next().unwrap();
+        copy.extrude_faces(face)?;
+        assert!(changes.lock().unwrap().is_empty());
+
+        // a rolled back transaction keeps the observers
+        let result: SMeshResult<()> = mesh.transaction(|tx| {
+            tx.extrude_faces(face)?;
+            Err(SMeshError::DefaultError)
+        });
+        assert!(result.is_err());
+        assert_eq!(changes.lock().unwrap().last(), Some(&MeshChange::Replaced));
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use itertools::Itertools;
+use rayon::prelude::*;
+use slotmap::{Key, SecondaryMap};
+
+use crate::prelude::*;
+use crate::smesh::attribute::{sealed::AttributeMaps, CustomAttributeOps};
+
+/// Parallel element iterators. The ids are collected up front,
+/// so the iterators are indexed and split evenly between threads.
+impl SMesh {
+    pub fn par_vertices(&self) -> impl IndexedParallelIterator<Item = VertexId> {
+        self.vertices().keys().collect_vec().into_par_iter()
+    }
+
+    pub fn par_halfedges(&self) -> impl IndexedParallelIterator<Item = HalfedgeId> {
+        self.halfedges().keys().collect_vec().into_par_iter()
+    }
+
+    pub fn par_faces(&self) -> impl IndexedParallelIterator<Item = FaceId> {
+        self.faces().keys().collect_vec().into_par_iter()
+    }
+}
+
+impl SMesh {
+    /// Parallel iterator over the elements of the mesh that have a value in both maps.
+    /// Entries of deleted elements, which stay in maps like [`SMesh::positions`],
+    /// are skipped.
+    /// ```
+    /// use glam::vec3;
+    /// use rayon::prelude::*;
+    /// use slotmap::SecondaryMap;
+    /// use smesh::prelude::*;
+    ///
+    /// let mut mesh = SMesh::new();
+    /// let v0 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+    /// let v1 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+    /// let mut weights = SecondaryMap::new();
+    /// weights.insert(v0, 2.0);
+    /// weights.insert(v1, 3.0);
+    /// mesh.delete_vertex(v1).unwrap();
+    /// let weighted: Vec<_> = mesh
+    ///     .par_zip(&mesh.positions, &weights)
+    ///     .map(|(_, p, w)| *p * *w)
+    ///     .collect();
+    /// assert_eq!(weighted, vec![vec3(2.0, 0.0, 0.0)]);
+    /// ```
+    pub fn par_zip<'a, K, A, B>(
+        &self,
+        a: &'a SecondaryMap<K, A>,
+        b: &'a SecondaryMap<K, B>,
+    ) -> impl IndexedParallelIterator<Item = (K, &'a A, &'a B)>
+    where
+        K: Key + Send,
+        A: Sync,
+        B: Sync,
+        Self: CustomAttributeOps<K>,
+    {
+        self.element_keys_internal()
+            .into_iter()
+            .filter_map(|k| Some((k, a.get(k)?, b.get(k)?)))
+            .collect_vec()
+            .into_par_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn parallel_iterators() -> SMeshResult<()> {
+        let mesh = crate::test_utils::quad_grid(4)?;
+        assert_eq!(mesh.par_vertices().count(), mesh.vertices().len());
+        assert_eq!(mesh.par_halfedges().count(), mesh.halfedges().len());
+        let areas: f32 = mesh
+            .par_faces()
+            .map(|f| f.area(&mesh))
+            .collect::<SMeshResult<Vec<_>>>()?
+            .iter()
+            .sum();
+        assert!((areas - mesh.surface_area(mesh.faces().keys().collect_vec())?).abs() < 1e-5);
+
+        let mut doubled = SecondaryMap::new();
+        for v in mesh.vertices().keys().take(5) {
+            doubled.insert(v, mesh.positions[v] * 2.0);
+        }
+        let zipped = mesh.par_zip(&mesh.positions, &doubled).collect::<Vec<_>>();
+        assert_eq!(zipped.len(), 5);
+        assert!(zipped.iter().all(|(_, p, d)| **p * 2.0 == **d));
+        let sum: Vec3 = mesh
+            .par_zip(&mesh.positions, &doubled)
+            .map(|(_, p, _)| *p)
+            .sum();
+        assert_eq!(sum * 2.0, doubled.values().sum::<Vec3>());
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use glam::{vec3, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Arrow pointing along +y with its base at the origin,
+/// a cylindrical shaft with a cone as head
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arrow {
+    pub shaft_radius: f32,
+    pub shaft_length: f32,
+    pub head_radius: f32,
+    pub head_length: f32,
+    /// Number of vertices around the axis
+    pub segments: usize,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        Self {
+            shaft_radius: 0.05,
+            shaft_length: 0.8,
+            head_radius: 0.1,
+            head_length: 0.2,
+            segments: 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowData {
+    /// The vertex at the tip of the head
+    pub tip: VertexId,
+    /// The cap at the base of the shaft
+    pub base_face: FaceId,
+}
+
+impl Primitive for Arrow {
+    type PrimitiveData = ArrowData;
+
+    /// Fails with less than three segments, non positive dimensions
+    /// or a head that is not wider than the shaft
+    fn generate(self) -> SMeshResult<(SMesh, ArrowData)> {
+        if self.segments < 3
+            || self.shaft_radius <= 0.0
+            || self.shaft_length <= 0.0
+            || self.head_length <= 0.0
+            || self.head_radius <= self.shaft_radius
+        {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let ring = |mesh: &mut SMesh, radius: f32, y: f32| {
+            (0..self.segments)
+                .map(|i| {
+                    let angle = i as f32 / self.segments as f32 * std::f32::consts::TAU;
+                    mesh.add_vertex(vec3(angle.cos() * radius, y, -angle.sin() * radius))
+                })
+                .collect_vec()
+        };
+        let base = ring(&mut mesh, self.shaft_radius, 0.0);
+        let shaft_end = ring(&mut mesh, self.shaft_radius, self.shaft_length);
+        let head = ring(&mut mesh, self.head_radius, self.shaft_length);
+        mesh.loft(&[base.clone(), shaft_end, head.clone()])?;
+
+        let tip = mesh.add_vertex(Vec3::Y * (self.shaft_length + self.head_length));
+        for (a, b) in head.iter().circular_tuple_windows() {
+            mesh.add_triangle(*a, *b, tip)?;
+        }
+        let base_face = mesh.add_face(base.into_iter().rev().collect())?;
+        Ok((mesh, ArrowData { tip, base_face }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    #[test]
+    fn arrow() -> SMeshResult<()> {
+        let (mesh, data) = Arrow {
+            segments: 6,
+            ..Default::default()
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 19);
+        assert_eq!(mesh.faces().len(), 6 * 3 + 1);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!(mesh.volume()? > 0.0);
+        assert_eq!(mesh.positions[data.tip], Vec3::Y);
+        assert!(data.base_face.normal(&mesh)?.distance(Vec3::NEG_Y) < 1e-5);
+
+        assert!(Arrow {
+            head_radius: 0.05,
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use glam::{vec3, Vec2, Vec3};
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Axis aligned box made of six quads
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cube {
+    pub size: Vec3,
+    /// Center of the box
+    pub origin: Vec3,
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self {
+            size: Vec3::ONE,
+            origin: Vec3::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CubeData {
+    /// Corners ordered by x, then y, then z: `(min x, min y, min z)`, `(max x, min y, min z)`,
+    /// `(max x, max y, min z)`, `(min x, max y, min z)` and the same at max z
+    pub vertices: [VertexId; 8],
+    /// The sides facing -z, +z, -y, +y, -x and +x
+    pub faces: [FaceId; 6],
+}
+
+impl Primitive for Cube {
+    type PrimitiveData = CubeData;
+
+    /// Every side gets its own uv rectangle starting at (0, 0), sized relative
+    /// to the largest dimension so the uvs keep the aspect ratio of the side.
+    /// Fails if a dimension is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, CubeData)> {
+        if self.size.cmple(Vec3::ZERO).any() {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let min = self.origin - self.size * 0.5;
+        let v = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 1.0),
+            vec3(1.0, 1.0, 1.0),
+            vec3(0.0, 1.0, 1.0),
+        ]
+        .map(|p| mesh.add_vertex(min + p * self.size));
+        let faces = [
+            mesh.add_quad(v[0], v[3], v[2], v[1])?,
+            mesh.add_quad(v[4], v[5], v[6], v[7])?,
+            mesh.add_quad(v[0], v[1], v[5], v[4])?,
+            mesh.add_quad(v[3], v[7], v[6], v[2])?,
+            mesh.add_quad(v[0], v[4], v[7], v[3])?,
+            mesh.add_quad(v[1], v[2], v[6], v[5])?,
+        ];
+
+        mesh.cube_project_uvs(faces.to_vec(), 1.0 / self.size.max_element())?;
+        let sides = faces.map(|f| f.halfedges(&mesh).collect::<Vec<_>>());
+        if let Some(uvs) = mesh.uvs.as_mut() {
+            for corners in sides {
+                let min = corners.iter().fold(Vec2::MAX, |min, h| min.min(uvs[*h]));
+                for h in corners {
+                    uvs[h] -= min;
+                }
+            }
+        }
+        Ok((mesh, CubeData { vertices: v, faces }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::vec2;
+
+    #[test]
+    fn cube() -> SMeshResult<()> {
+        let (mesh, data) = Cube {
+            size: vec3(4.0, 2.0, 1.0),
+            origin: vec3(0.0, 1.0, 0.0),
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert!((mesh.volume()? - 8.0).abs() < 1e-5);
+        assert_eq!(mesh.positions[data.vertices[0]], vec3(-2.0, 0.0, -0.5));
+        assert_eq!(mesh.positions[data.vertices[6]], vec3(2.0, 2.0, 0.5));
+        let normals = [
+            Vec3::NEG_Z,
+            Vec3::Z,
+            Vec3::NEG_Y,
+            Vec3::Y,
+            Vec3::NEG_X,
+            Vec3::X,
+        ];
+        for (f, n) in data.faces.iter().zip(normals) {
+            assert!(f.normal(&mesh)?.distance(n) < 1e-5);
+        }
+
+        // uv rectangles with the aspect ratio of the sides
+        let uvs = mesh.uvs.as_ref().unwrap();
+        let extent = |f: FaceId| {
+            f.halfedges(&mesh)
+                .fold(Vec2::ZERO, |max, h| max.max(uvs[h]))
+        };
+        assert_eq!(extent(data.faces[1]), vec2(1.0, 0.5));
+        assert_eq!(extent(data.faces[3]), vec2(1.0, 0.25));
+        assert_eq!(extent(data.faces[5]), vec2(0.25, 0.5));
+
+        assert!(Cube {
+            size: vec3(1.0, 0.0, 1.0),
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use glam::vec3;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// How the open end of a [`Cylinder`] is closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CapMode {
+    /// Leave the end open
+    None,
+    /// A single face with one vertex per segment
+    #[default]
+    Ngon,
+    /// Triangles around an additional center vertex
+    TriangleFan,
+}
+
+/// Cylinder along the y axis, centered at the origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cylinder {
+    pub radius: f32,
+    pub height: f32,
+    /// Number of vertices around the axis
+    pub segments: usize,
+    pub top_cap: CapMode,
+    pub bottom_cap: CapMode,
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            height: 1.0,
+            segments: 16,
+            top_cap: CapMode::default(),
+            bottom_cap: CapMode::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CylinderData {
+    /// Vertices of the bottom ring, counter clockwise seen from above
+    pub bottom_vertices: Vec<VertexId>,
+    /// Vertices of the top ring, counter clockwise seen from above
+    pub top_vertices: Vec<VertexId>,
+    /// One quad per segment, starting at the +x axis
+    pub side_faces: Vec<FaceId>,
+    /// Faces of the top cap, empty without a cap
+    pub top_faces: Vec<FaceId>,
+    /// Faces of the bottom cap, empty without a cap
+    pub bottom_faces: Vec<FaceId>,
+}
+
+impl Primitive for Cylinder {
+    type PrimitiveData = CylinderData;
+
+    /// Fails with less than three segments
+    fn generate(self) -> SMeshResult<(SMesh, CylinderData)> {
+        if self.segments < 3 {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let mut data = CylinderData::default();
+        let half_height = self.height * 0.5;
+        for i in 0..self.segments {
+            let angle = i as f32 / self.segments as f32 * std::f32::consts::TAU;
+            let (x, z) = (angle.cos() * self.radius, -angle.sin() * self.radius);
+            data.bottom_vertices
+                .push(mesh.add_vertex(vec3(x, -half_height, z)));
+            data.top_vertices
+                .push(mesh.add_vertex(vec3(x, half_height, z)));
+        }
+        for i in 0..self.segments {
+            let j = (i + 1) % self.segments;
+            data.side_faces.push(mesh.add_quad(
+                data.bottom_vertices[i],
+                data.bottom_vertices[j],
+                data.top_vertices[j],
+                data.top_vertices[i],
+            )?);
+        }
+        data.top_faces = add_cap(
+            &mut mesh,
+            self.top_cap,
+            data.top_vertices.clone(),
+            half_height,
+        )?;
+        let bottom = data.bottom_vertices.iter().rev().copied().collect();
+        data.bottom_faces = add_cap(&mut mesh, self.bottom_cap, bottom, -half_height)?;
+        Ok((mesh, data))
+    }
+}
+
+/// Close the counter clockwise `ring` of vertices at height `y`
+fn add_cap(
+    mesh: &mut SMesh,
+    mode: CapMode,
+    ring: Vec<VertexId>,
+    y: f32,
+) -> SMeshResult<Vec<FaceId>> {
+    match mode {
+        CapMode::None => Ok(vec![]),
+        CapMode::Ngon => Ok(vec![mesh.add_face(ring)?]),
+        CapMode::TriangleFan => {
+            let center = mesh.add_vertex(vec3(0.0, y, 0.0));
+            (0..ring.len())
+                .map(|i| mesh.add_triangle(center, ring[i], ring[(i + 1) % ring.len()]))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::Vec3;
+
+    #[test]
+    fn cylinder_caps() -> SMeshResult<()> {
+        let cylinder = Cylinder {
+            segments: 8,
+            ..Default::default()
+        };
+        let (mesh, data) = cylinder.generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 10);
+        assert_eq!(data.top_faces.len(), 1);
+        assert!(data.top_faces[0].normal(&mesh)?.distance(Vec3::Y) < 1e-5);
+        assert!(data.bottom_faces[0].normal(&mesh)?.distance(Vec3::NEG_Y) < 1e-5);
+        // the volume of an octagonal prism
+        let expected = 4.0 * 0.25 * std::f32::consts::FRAC_PI_4.sin();
+        assert!((mesh.volume()? - expected).abs() < 1e-4);
+
+        let (mesh, data) = Cylinder {
+            segments: 8,
+            top_cap: CapMode::TriangleFan,
+            bottom_cap: CapMode::None,
+            ..Default::default()
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 17);
+        assert_eq!(data.top_faces.len(), 8);
+        assert!(data
+            .top_faces
+            .iter()
+            .all(|f| f.normal(&mesh).unwrap().y > 0.99));
+        assert!(data.bottom_faces.is_empty());
+        assert!(data.bottom_vertices.iter().all(|v| v.is_boundary(&mesh)));
+
+        assert!(Cylinder {
+            segments: 2,
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        Ok(())
+    }
+}
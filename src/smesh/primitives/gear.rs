@@ -0,0 +1,131 @@
+use glam::{vec3, Quat, Vec3};
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Spur gear along the y axis, centered at the origin, with trapezoid teeth
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gear {
+    pub teeth: usize,
+    /// Radius at the root of the teeth
+    pub inner_radius: f32,
+    /// Radius at the tip of the teeth
+    pub outer_radius: f32,
+    pub thickness: f32,
+    /// Rotation of the top face against the bottom face around the y axis, in radians,
+    /// for helical gears
+    pub twist: f32,
+}
+
+impl Default for Gear {
+    fn default() -> Self {
+        Self {
+            teeth: 12,
+            inner_radius: 0.8,
+            outer_radius: 1.0,
+            thickness: 0.25,
+            twist: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GearData {
+    pub top_face: FaceId,
+    pub bottom_face: FaceId,
+}
+
+impl Primitive for Gear {
+    type PrimitiveData = GearData;
+
+    /// The bottom profile is created as one ngon and extruded upwards.
+    /// Fails with less than three teeth, a non positive thickness or
+    /// an outer radius that is not larger than the inner radius.
+    fn generate(self) -> SMeshResult<(SMesh, GearData)> {
+        if self.teeth < 3
+            || self.thickness <= 0.0
+            || self.inner_radius <= 0.0
+            || self.outer_radius <= self.inner_radius
+        {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let pitch = std::f32::consts::TAU / self.teeth as f32;
+        let y = -self.thickness * 0.5;
+        let mut profile = vec![];
+        for tooth in 0..self.teeth {
+            // flank up, tip, flank down, root
+            for (fraction, radius) in [
+                (0.0, self.inner_radius),
+                (0.25, self.outer_radius),
+                (0.5, self.outer_radius),
+                (0.75, self.inner_radius),
+            ] {
+                let angle = (tooth as f32 + fraction) * pitch;
+                let p = vec3(angle.cos(), 0.0, -angle.sin()) * radius + Vec3::Y * y;
+                profile.push(mesh.add_vertex(p));
+            }
+        }
+        let face = mesh.add_face(profile.clone())?;
+        let top = mesh.extrude_faces(face)?;
+        mesh.translate(top.clone(), Vec3::Y * self.thickness)?;
+        mesh.rotate(
+            top.clone(),
+            Quat::from_rotation_y(self.twist),
+            Pivot::Origin,
+        )?;
+        let top_face = top.faces().next().unwrap();
+        let bottom_face = mesh.add_face(profile.into_iter().rev().collect())?;
+        Ok((
+            mesh,
+            GearData {
+                top_face,
+                bottom_face,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    #[test]
+    fn gear() -> SMeshResult<()> {
+        let (mesh, data) = Gear {
+            teeth: 8,
+            twist: 0.2,
+            ..Default::default()
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 64);
+        assert_eq!(mesh.faces().len(), 34);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!(mesh.volume()? > 0.0);
+        assert!(data.top_face.normal(&mesh)?.distance(Vec3::Y) < 1e-5);
+        assert!(data.bottom_face.normal(&mesh)?.distance(Vec3::NEG_Y) < 1e-5);
+        // the top profile is the twisted bottom profile
+        let rotation = Quat::from_rotation_y(0.2);
+        for v in data.bottom_face.vertices(&mesh) {
+            let p = mesh.positions[v];
+            let radius = vec3(p.x, 0.0, p.z).length();
+            assert!((radius - 0.8).abs() < 1e-5 || (radius - 1.0).abs() < 1e-5);
+            let twisted = rotation * p + Vec3::Y * 0.25;
+            assert!(data
+                .top_face
+                .vertices(&mesh)
+                .any(|v| mesh.positions[v].distance(twisted) < 1e-5));
+        }
+
+        assert!(Gear {
+            inner_radius: 1.0,
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        Ok(())
+    }
+}
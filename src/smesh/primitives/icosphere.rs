@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use glam::{vec2, vec3, Vec2, Vec3};
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::prelude::*;
+
+/// Sphere of evenly sized triangles, created by subdividing an icosahedron.
+/// The icosahedron has a vertex at each pole, on the y axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Icosphere {
+    pub radius: f32,
+    /// Number of times every triangle is split into four
+    pub subdivisions: usize,
+}
+
+impl Default for Icosphere {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            subdivisions: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IcosphereData {
+    /// The vertices at the top and the bottom of the sphere
+    pub poles: [VertexId; 2],
+    /// The twelve vertices of the icosahedron, starting with the poles
+    pub base_vertices: [VertexId; 12],
+    /// All vertices by subdivision level: the vertices of the icosahedron followed by
+    /// the vertices created by each subdivision. Every level is a prefix of the next,
+    /// so a vertex has the same index for any number of subdivisions.
+    pub vertices: Vec<VertexId>,
+}
+
+impl Primitive for Icosphere {
+    type PrimitiveData = IcosphereData;
+
+    /// The faces get spherical uvs, with u going around the y axis starting at +x and
+    /// v going from the bottom to the top. Faces crossing the seam get u values above 1,
+    /// corners at the poles get the u of the middle of their face.
+    /// The final subdivision level is computed on plain index triangles and all faces
+    /// are connected in a single pass, so no intermediate meshes get built.
+    fn generate(self) -> SMeshResult<(SMesh, IcosphereData)> {
+        let (mut points, mut triangles) = icosahedron();
+        points.reserve(10 * 4usize.pow(self.subdivisions as u32) + 2 - points.len());
+        for _ in 0..self.subdivisions {
+            triangles = subdivide(&mut points, &triangles);
+        }
+
+        let mut mesh = SMesh::new();
+        let vertices = points
+            .iter()
+            .map(|p| mesh.add_vertex(*p * self.radius))
+            .collect_vec();
+        let faces = mesh.add_faces(
+            &triangles
+                .iter()
+                .map(|t| t.map(|i| vertices[i]).to_vec())
+                .collect_vec(),
+        )?;
+        let mut uvs = SecondaryMap::new();
+        for (f, triangle) in faces.into_iter().zip(triangles) {
+            let corner_uvs = triangle_uvs(triangle.map(|i| points[i]));
+            for h in f.halfedges(&mesh) {
+                let v = h.src_vert().run(&mesh)?;
+                let corner = triangle.iter().position(|i| vertices[*i] == v).unwrap();
+                uvs.insert(h, corner_uvs[corner]);
+            }
+        }
+        mesh.uvs = Some(uvs);
+        Ok((
+            mesh,
+            IcosphereData {
+                poles: [vertices[0], vertices[1]],
+                base_vertices: std::array::from_fn(|i| vertices[i]),
+                vertices,
+            },
+        ))
+    }
+}
+
+/// Unit icosahedron with the poles as first two points and outward facing triangles
+pub(super) fn icosahedron() -> (Vec<Vec3>, Vec<[usize; 3]>) {
+    let y = 1.0 / 5f32.sqrt();
+    let r = 2.0 * y;
+    let mut points = vec![Vec3::Y, Vec3::NEG_Y];
+    for k in 0..10 {
+        // alternating between the upper and the lower ring
+        let angle = k as f32 * std::f32::consts::TAU / 10.0;
+        let height = if k % 2 == 0 { y } else { -y };
+        points.push(vec3(angle.cos() * r, height, -angle.sin() * r));
+    }
+    let ring = |k: usize| 2 + k % 10;
+    let mut triangles = vec![];
+    for k in (0..10).step_by(2) {
+        triangles.push([0, ring(k), ring(k + 2)]);
+        triangles.push([1, ring(k + 3), ring(k + 1)]);
+        triangles.push([ring(k), ring(k + 1), ring(k + 2)]);
+        triangles.push([ring(k + 1), ring(k + 3), ring(k + 2)]);
+    }
+    (points, triangles)
+}
+
+/// Split every triangle into four, with new points on the unit sphere
+fn subdivide(points: &mut Vec<Vec3>, triangles: &[[usize; 3]]) -> Vec<[usize; 3]> {
+    let mut midpoints = HashMap::new();
+    let mut midpoint = |a: usize, b: usize| {
+        *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+            points.push((points[a] + points[b]).normalize());
+            points.len() - 1
+        })
+    };
+    let mut result = Vec::with_capacity(triangles.len() * 4);
+    for [a, b, c] in triangles.iter().copied() {
+        let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+        result.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]);
+    }
+    result
+}
+
+/// Spherical uvs of the corners of a triangle on the unit sphere
+fn triangle_uvs(points: [Vec3; 3]) -> [Vec2; 3] {
+    let mut uvs = points.map(|p| {
+        let u = (-p.z).atan2(p.x).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU;
+        let v = 0.5 + p.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+        vec2(u, v)
+    });
+    // continue across the seam instead of wrapping around
+    let max = uvs.iter().map(|uv| uv.x).fold(f32::MIN, f32::max);
+    for uv in uvs.iter_mut() {
+        if max - uv.x > 0.5 {
+            uv.x += 1.0;
+        }
+    }
+    // the u at a pole is undefined, use the middle of the opposite edge
+    for i in 0..3 {
+        if points[i].y.abs() > 1.0 - 1e-6 {
+            uvs[i].x = (uvs[(i + 1) % 3].x + uvs[(i + 2) % 3].x) * 0.5;
+        }
+    }
+    uvs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    #[test]
+    fn icosphere() -> SMeshResult<()> {
+        let (mesh, data) = Icosphere {
+            radius: 2.0,
+            subdivisions: 2,
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 162);
+        assert_eq!(mesh.faces().len(), 320);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!(mesh
+            .positions
+            .values()
+            .all(|p| (p.length() - 2.0).abs() < 1e-5));
+        assert!(mesh.volume()? > 0.0);
+        assert_eq!(mesh.positions[data.poles[0]], Vec3::Y * 2.0);
+
+        // handles are stable across subdivision levels
+        let (coarse, coarse_data) = Icosphere {
+            radius: 2.0,
+            subdivisions: 1,
+        }
+        .generate()?;
+        assert_eq!(coarse_data.vertices.len(), 42);
+        for (a, b) in coarse_data.vertices.iter().zip(&data.vertices) {
+            assert_eq!(coarse.positions[*a], mesh.positions[*b]);
+        }
+
+        // no face gets stretched across the uv square
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            let corners = f.halfedges(&mesh).map(|h| uvs[h]).collect_vec();
+            for (a, b) in corners.iter().circular_tuple_windows() {
+                assert!(a.distance(*b) < 0.3);
+            }
+            let signed_area = (corners[1] - corners[0]).perp_dot(corners[2] - corners[0]);
+            assert!(signed_area > 0.0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+pub mod arrow;
+pub mod cube;
+pub mod cylinder;
+pub mod gear;
+pub mod icosphere;
+pub mod parametric;
+pub mod platonic;
+pub mod revolve;
+pub mod skin;
+pub mod stairs;
+#[cfg(feature = "text")]
+pub mod text;
+pub mod tube;
+
+pub use arrow::*;
+pub use cube::*;
+pub use cylinder::*;
+pub use gear::*;
+pub use icosphere::*;
+pub use parametric::*;
+pub use platonic::*;
+pub use revolve::*;
+pub use skin::*;
+pub use stairs::*;
+#[cfg(feature = "text")]
+pub use text::*;
+pub use tube::*;
+
+use glam::Mat4;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// A parametric shape that can be generated as a mesh
+pub trait Primitive {
+    /// Ids of notable elements of the generated mesh, like the faces of a cap
+    type PrimitiveData;
+
+    fn generate(self) -> SMeshResult<(SMesh, Self::PrimitiveData)>;
+}
+
+/// Helpers available on every [`Primitive`]
+pub trait PrimitiveExt: Primitive + Sized {
+    /// Generate the primitive with `transform` applied to its positions,
+    /// so it can be combined into another mesh in place.
+    /// Fails if the primitive does, or if the transform is degenerate or mirrors
+    /// the mesh, which would turn its faces inside out.
+    fn generate_at(self, transform: Mat4) -> SMeshResult<(SMesh, Self::PrimitiveData)> {
+        if transform.determinant() <= 0.0 {
+            bail!(DefaultError);
+        }
+        let (mut mesh, data) = self.generate()?;
+        for p in mesh.positions.values_mut() {
+            *p = transform.transform_point3(*p);
+        }
+        Ok((mesh, data))
+    }
+}
+
+impl<P: Primitive> PrimitiveExt for P {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::{vec3, Quat, Vec3};
+
+    #[test]
+    fn generate_at() -> SMeshResult<()> {
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            vec3(5.0, 0.0, 0.0),
+        );
+        let (mesh, data) = Cube::default().generate_at(transform)?;
+        assert!((mesh.volume()? - 8.0).abs() < 1e-4);
+        assert!(mesh.positions[data.vertices[0]].distance(vec3(6.0, -1.0, -1.0)) < 1e-5);
+        assert!(data.faces[5].normal(&mesh)?.distance(Vec3::Y) < 1e-5);
+
+        let mirror = Mat4::from_scale(vec3(-1.0, 1.0, 1.0));
+        assert!(Cube::default().generate_at(mirror).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use glam::{vec2, Vec3};
+use slotmap::SecondaryMap;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Surface sampled from a function of the parameters u and v, both going from 0 to 1,
+/// as a grid of quads. Wrapped parameters connect the last row of quads back to the
+/// first, for closed surfaces like cylinders or tori. The wrapped sides need to meet
+/// with the same orientation, non orientable surfaces like a möbius strip are not supported.
+/// Faces point along `df/du x df/dv`.
+pub struct ParametricSurface<F: Fn(f32, f32) -> Vec3> {
+    pub u_steps: usize,
+    pub v_steps: usize,
+    pub wrap_u: bool,
+    pub wrap_v: bool,
+    pub f: F,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParametricSurfaceData {
+    /// Vertex of every sample, indexed by `[v][u]`. Wrapped parameters have no samples at 1.
+    pub vertices: Vec<Vec<VertexId>>,
+}
+
+impl<F: Fn(f32, f32) -> Vec3> Primitive for ParametricSurface<F> {
+    type PrimitiveData = ParametricSurfaceData;
+
+    /// The uvs of the faces are the surface parameters.
+    /// Fails without steps or with less than three steps along a wrapped parameter.
+    fn generate(self) -> SMeshResult<(SMesh, ParametricSurfaceData)> {
+        let min_steps = |wrap: bool| if wrap { 3 } else { 1 };
+        if self.u_steps < min_steps(self.wrap_u) || self.v_steps < min_steps(self.wrap_v) {
+            bail!(DefaultError);
+        }
+        let columns = if self.wrap_u {
+            self.u_steps
+        } else {
+            self.u_steps + 1
+        };
+        let rows = if self.wrap_v {
+            self.v_steps
+        } else {
+            self.v_steps + 1
+        };
+        let parameter = |i: usize, steps: usize| i as f32 / steps as f32;
+
+        let mut mesh = SMesh::new();
+        let vertices = (0..rows)
+            .map(|j| {
+                (0..columns)
+                    .map(|i| {
+                        let p = (self.f)(parameter(i, self.u_steps), parameter(j, self.v_steps));
+                        mesh.add_vertex(p)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut uvs = SecondaryMap::new();
+        for j in 0..self.v_steps {
+            for i in 0..self.u_steps {
+                let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+                let face_vertices = corners.map(|(i, j)| vertices[j % rows][i % columns]);
+                let face = mesh.add_face(face_vertices.to_vec())?;
+                for h in face.halfedges(&mesh) {
+                    let v = h.src_vert().run(&mesh)?;
+                    let k = face_vertices.iter().position(|fv| *fv == v).unwrap();
+                    let (i, j) = corners[k];
+                    uvs.insert(
+                        h,
+                        vec2(parameter(i, self.u_steps), parameter(j, self.v_steps)),
+                    );
+                }
+            }
+        }
+        mesh.uvs = Some(uvs);
+        Ok((mesh, ParametricSurfaceData { vertices }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::vec3;
+    use std::f32::consts::{PI, TAU};
+
+    #[test]
+    fn parametric_surface() -> SMeshResult<()> {
+        // a plane with the parameters as positions
+        let (mesh, data) = ParametricSurface {
+            u_steps: 4,
+            v_steps: 2,
+            wrap_u: false,
+            wrap_v: false,
+            f: |u, v| vec3(u, v, 0.0),
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 15);
+        assert_eq!(mesh.positions[data.vertices[2][4]], vec3(1.0, 1.0, 0.0));
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for (h, uv) in uvs {
+            assert_eq!(mesh.positions[h.src_vert().run(&mesh)?], uv.extend(0.0));
+        }
+        assert!(mesh
+            .faces()
+            .keys()
+            .all(|f| f.normal(&mesh).unwrap().distance(Vec3::Z) < 1e-5));
+
+        // a closed torus
+        let (mesh, _) = ParametricSurface {
+            u_steps: 64,
+            v_steps: 32,
+            wrap_u: true,
+            wrap_v: true,
+            f: |u, v| {
+                let (u, v) = (u * TAU, v * TAU);
+                let r = 1.0 + 0.25 * v.cos();
+                vec3(r * u.cos(), 0.25 * v.sin(), -r * u.sin())
+            },
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 64 * 32);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        let expected = 2.0 * PI * PI * 0.25 * 0.25;
+        assert!((mesh.volume()? - expected).abs() / expected < 0.02);
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use glam::{vec3, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+use super::icosphere::icosahedron;
+
+/// Regular solid with four triangles
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tetrahedron {
+    /// Distance of the vertices from the center
+    pub radius: f32,
+}
+
+/// Regular solid with eight triangles, with its vertices on the axes
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Octahedron {
+    /// Distance of the vertices from the center
+    pub radius: f32,
+}
+
+/// Regular solid with twelve pentagons, the dual of the [`Icosahedron`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dodecahedron {
+    /// Distance of the vertices from the center
+    pub radius: f32,
+}
+
+/// Regular solid with twenty triangles, the base of the [`Icosphere`].
+/// It has a vertex at each pole, on the y axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Icosahedron {
+    /// Distance of the vertices from the center
+    pub radius: f32,
+}
+
+impl Default for Tetrahedron {
+    fn default() -> Self {
+        Self { radius: 1.0 }
+    }
+}
+
+impl Default for Octahedron {
+    fn default() -> Self {
+        Self { radius: 1.0 }
+    }
+}
+
+impl Default for Dodecahedron {
+    fn default() -> Self {
+        Self { radius: 1.0 }
+    }
+}
+
+impl Default for Icosahedron {
+    fn default() -> Self {
+        Self { radius: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlatonicSolidData {
+    pub vertices: Vec<VertexId>,
+    pub faces: Vec<FaceId>,
+}
+
+impl Primitive for Tetrahedron {
+    type PrimitiveData = PlatonicSolidData;
+
+    /// Fails if the radius is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, PlatonicSolidData)> {
+        let points = [
+            vec3(1.0, 1.0, 1.0),
+            vec3(1.0, -1.0, -1.0),
+            vec3(-1.0, 1.0, -1.0),
+            vec3(-1.0, -1.0, 1.0),
+        ];
+        let polygons = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+        build_solid(self.radius, &points, &polygons.map(|p| p.to_vec()))
+    }
+}
+
+impl Primitive for Octahedron {
+    type PrimitiveData = PlatonicSolidData;
+
+    /// Fails if the radius is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, PlatonicSolidData)> {
+        let points = [
+            Vec3::X,
+            Vec3::NEG_X,
+            Vec3::Y,
+            Vec3::NEG_Y,
+            Vec3::Z,
+            Vec3::NEG_Z,
+        ];
+        // one triangle per octant
+        let polygons = (0..8)
+            .map(|octant| vec![octant & 1, 2 + (octant >> 1 & 1), 4 + (octant >> 2 & 1)])
+            .collect_vec();
+        build_solid(self.radius, &points, &polygons)
+    }
+}
+
+impl Primitive for Dodecahedron {
+    type PrimitiveData = PlatonicSolidData;
+
+    /// The vertices are the centers of the triangles of the [`Icosahedron`],
+    /// in the same order.
+    /// Fails if the radius is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, PlatonicSolidData)> {
+        let (points, triangles) = icosahedron();
+        let centers = triangles
+            .iter()
+            .map(|t| t.iter().map(|i| points[*i]).sum::<Vec3>())
+            .collect_vec();
+        // a pentagon around every vertex of the icosahedron
+        let polygons = (0..points.len())
+            .map(|v| {
+                let around = |t: &[usize; 3]| {
+                    let k = t.iter().position(|i| *i == v)?;
+                    Some((t[(k + 1) % 3], t[(k + 2) % 3]))
+                };
+                let (first, mut next) = (0..triangles.len())
+                    .find_map(|t| Some((t, around(&triangles[t])?.1)))
+                    .unwrap();
+                let mut polygon = vec![first];
+                while let Some(t) = (0..triangles.len())
+                    .find(|t| *t != first && around(&triangles[*t]).map(|e| e.0) == Some(next))
+                {
+                    polygon.push(t);
+                    next = around(&triangles[t]).unwrap().1;
+                }
+                polygon
+            })
+            .collect_vec();
+        build_solid(self.radius, &centers, &polygons)
+    }
+}
+
+impl Primitive for Icosahedron {
+    type PrimitiveData = PlatonicSolidData;
+
+    /// The first two vertices are the poles, at the top and the bottom.
+    /// Fails if the radius is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, PlatonicSolidData)> {
+        let (points, triangles) = icosahedron();
+        build_solid(
+            self.radius,
+            &points,
+            &triangles.iter().map(|t| t.to_vec()).collect_vec(),
+        )
+    }
+}
+
+/// Build a convex solid centered at the origin, with the `points` scaled onto a sphere of
+/// the given radius. The polygons are oriented to face away from the center.
+fn build_solid(
+    radius: f32,
+    points: &[Vec3],
+    polygons: &[Vec<usize>],
+) -> SMeshResult<(SMesh, PlatonicSolidData)> {
+    if radius <= 0.0 {
+        bail!(DefaultError);
+    }
+    let points = points.iter().map(|p| p.normalize() * radius).collect_vec();
+    let mut mesh = SMesh::new();
+    let vertices = points.iter().map(|p| mesh.add_vertex(*p)).collect_vec();
+    let polygons = polygons
+        .iter()
+        .map(|polygon| {
+            let p = |k: usize| points[polygon[k]];
+            let normal = (p(1) - p(0)).cross(p(2) - p(0));
+            let mut face = polygon.iter().map(|i| vertices[*i]).collect_vec();
+            if normal.dot(p(0)) < 0.0 {
+                face.reverse();
+            }
+            face
+        })
+        .collect_vec();
+    let faces = mesh.add_faces(&polygons)?;
+    Ok((mesh, PlatonicSolidData { vertices, faces }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    fn check_solid(
+        (mesh, data): (SMesh, PlatonicSolidData),
+        vertices: usize,
+        faces: usize,
+        volume: f32,
+    ) -> SMeshResult<()> {
+        check_connectivity(&mesh)?;
+        assert_eq!(data.vertices.len(), vertices);
+        assert_eq!(data.faces.len(), faces);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!(mesh
+            .positions
+            .values()
+            .all(|p| (p.length() - 2.0).abs() < 1e-5));
+        assert!((mesh.volume()? - volume * 8.0).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn platonic_solids() -> SMeshResult<()> {
+        check_solid(Tetrahedron { radius: 2.0 }.generate()?, 4, 4, 0.51320)?;
+        check_solid(Octahedron { radius: 2.0 }.generate()?, 6, 8, 4.0 / 3.0)?;
+        check_solid(Dodecahedron { radius: 2.0 }.generate()?, 20, 12, 2.78516)?;
+        check_solid(Icosahedron { radius: 2.0 }.generate()?, 12, 20, 2.53615)?;
+        assert!(Tetrahedron { radius: 0.0 }.generate().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use glam::{vec3, Vec2};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Lathe a profile in the xy plane around the y axis. Profiles running upwards at
+/// positive x produce outward facing faces. Profile points on the axis become a single
+/// vertex shared by all rings. Partial sweeps can be closed with the profile as caps,
+/// which only closes the mesh if the profile starts and ends on the axis.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Revolve {
+    /// Points with x as distance to the axis and y as height
+    pub profile: Vec<Vec2>,
+    /// Number of steps around the axis
+    pub segments: usize,
+    /// Sweep angle in radians, a full turn connects the last ring to the first
+    pub angle: f32,
+    /// Close partial sweeps with the profile at both ends
+    pub caps: bool,
+}
+
+impl Default for Revolve {
+    fn default() -> Self {
+        Self {
+            profile: vec![],
+            segments: 16,
+            angle: std::f32::consts::TAU,
+            caps: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RevolveData {
+    /// The vertices of the profile at every step around the axis
+    pub rings: Vec<Vec<VertexId>>,
+    pub start_cap: Option<FaceId>,
+    pub end_cap: Option<FaceId>,
+}
+
+impl Primitive for Revolve {
+    type PrimitiveData = RevolveData;
+
+    /// Fails with less than two profile points, points at negative x, a non positive
+    /// angle or less than three segments for a full turn
+    fn generate(self) -> SMeshResult<(SMesh, RevolveData)> {
+        let full_turn = self.angle >= std::f32::consts::TAU - 1e-5;
+        if self.profile.len() < 2
+            || self.profile.iter().any(|p| p.x < 0.0)
+            || self.angle <= 0.0
+            || self.segments < if full_turn { 3 } else { 1 }
+        {
+            bail!(DefaultError);
+        }
+        let mut mesh = SMesh::new();
+        let on_axis = self.profile.iter().map(|p| p.x <= 1e-6).collect_vec();
+        let poles = self
+            .profile
+            .iter()
+            .zip(&on_axis)
+            .map(|(p, on_axis)| on_axis.then(|| mesh.add_vertex(vec3(0.0, p.y, 0.0))))
+            .collect_vec();
+        let ring_count = if full_turn {
+            self.segments
+        } else {
+            self.segments + 1
+        };
+        let mut data = RevolveData::default();
+        for k in 0..ring_count {
+            let angle = k as f32 / self.segments as f32 * self.angle;
+            let ring = self
+                .profile
+                .iter()
+                .zip(&poles)
+                .map(|(p, pole)| {
+                    pole.unwrap_or_else(|| {
+                        mesh.add_vertex(vec3(angle.cos() * p.x, p.y, -angle.sin() * p.x))
+                    })
+                })
+                .collect_vec();
+            data.rings.push(ring);
+        }
+
+        for k in 0..self.segments {
+            let (r0, r1) = (&data.rings[k], &data.rings[(k + 1) % ring_count]);
+            for i in 0..self.profile.len() - 1 {
+                let face = [r0[i], r1[i], r1[i + 1], r0[i + 1]]
+                    .into_iter()
+                    .dedup()
+                    .collect_vec();
+                if face.len() >= 3 {
+                    mesh.add_face(face)?;
+                }
+            }
+        }
+        if self.caps && !full_turn {
+            let cap = |ring: &Vec<VertexId>| ring.iter().copied().dedup().collect_vec();
+            let start = cap(&data.rings[0]);
+            let end = cap(&data.rings[self.segments]);
+            if start.len() >= 3 {
+                data.start_cap = Some(mesh.add_face(start)?);
+                data.end_cap = Some(mesh.add_face(end.into_iter().rev().collect())?);
+            }
+        }
+        Ok((mesh, data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::{vec2, Vec3};
+    use std::f32::consts::PI;
+
+    #[test]
+    fn revolve() -> SMeshResult<()> {
+        // a cylinder closed with triangle fans at the poles
+        let profile = vec![
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 1.0),
+            vec2(0.0, 1.0),
+        ];
+        let (mesh, data) = Revolve {
+            profile: profile.clone(),
+            segments: 64,
+            ..Default::default()
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.vertices().len(), 2 + 2 * 64);
+        assert_eq!(mesh.faces().len(), 3 * 64);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!((mesh.volume()? - PI).abs() < 0.01);
+        assert!(data.start_cap.is_none());
+
+        // half of it, closed with the profile
+        let (mesh, data) = Revolve {
+            profile,
+            segments: 32,
+            angle: PI,
+            caps: true,
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!((mesh.volume()? - PI / 2.0).abs() < 0.01);
+        let start = data.start_cap.unwrap().normal(&mesh)?;
+        assert!(start.distance(Vec3::Z) < 1e-5);
+
+        assert!(Revolve {
+            profile: vec![vec2(-1.0, 0.0), vec2(1.0, 0.0)],
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+
+use glam::{Quat, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Quad tubes wrapped around the edges of a graph of nodes with a radius each,
+/// welded into one closed surface per connected part of the graph.
+/// Each part is swept from a leaf, continuing through every node along the edge that
+/// bends the least. The other edges of a node branch off the sides of a box around it,
+/// so a node can have up to six edges. Nodes without edges become boxes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Skin {
+    /// Position and radius of every node
+    pub nodes: Vec<(Vec3, f32)>,
+    /// Pairs of node indices
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// Sweep from a node over its neighbor, replacing the face of a hub if it starts at one
+type Branch = (usize, usize, Option<Vec<VertexId>>);
+
+#[derive(Debug, Clone, Default)]
+pub struct SkinData {
+    /// The cross sections created around every node, two for branching nodes
+    pub node_rings: Vec<Vec<Vec<VertexId>>>,
+}
+
+impl Primitive for Skin {
+    type PrimitiveData = SkinData;
+
+    /// Fails if an edge refers to a missing node, connects a node to itself or has no
+    /// length, or if a radius is not positive.
+    /// Graphs with cycles and nodes with more than six edges are not supported.
+    fn generate(self) -> SMeshResult<(SMesh, SkinData)> {
+        let n = self.nodes.len();
+        if self.nodes.iter().any(|(_, r)| *r <= 0.0) {
+            bail!(DefaultError);
+        }
+        let mut adjacency = vec![vec![]; n];
+        let mut components = (0..n).collect_vec();
+        for (a, b) in self.edges.iter().copied() {
+            if a >= n || b >= n || self.nodes[a].0.distance(self.nodes[b].0) <= f32::EPSILON {
+                bail!(DefaultError);
+            }
+            let (root_a, root_b) = (find(&mut components, a), find(&mut components, b));
+            if root_a == root_b {
+                bail!(UnsupportedOperation);
+            }
+            components[root_a] = root_b;
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+
+        let mut mesh = SMesh::new();
+        let mut data = SkinData {
+            node_rings: vec![vec![]; n],
+        };
+        let mut visited = vec![false; n];
+        for root in 0..n {
+            if visited[root] || adjacency[root].len() > 1 {
+                continue;
+            }
+            visited[root] = true;
+            let Some(first) = adjacency[root].first().copied() else {
+                self.add_box(&mut mesh, root, &mut data)?;
+                continue;
+            };
+            let mut branches = VecDeque::from([(root, first, None)]);
+            while let Some((from, to, hub_face)) = branches.pop_front() {
+                let path = self.walk(&adjacency, from, to);
+                for node in &path {
+                    visited[*node] = true;
+                }
+                branches
+                    .extend(self.add_branch(&mut mesh, &adjacency, &path, hub_face, &mut data)?);
+            }
+        }
+        Ok((mesh, data))
+    }
+}
+
+impl Skin {
+    fn position(&self, node: usize) -> Vec3 {
+        self.nodes[node].0
+    }
+
+    fn direction(&self, from: usize, to: usize) -> Vec3 {
+        (self.position(to) - self.position(from)).normalize()
+    }
+
+    /// Nodes from `from` over `to` to a leaf, taking the straightest edge at every node
+    fn walk(&self, adjacency: &[Vec<usize>], from: usize, to: usize) -> Vec<usize> {
+        let mut path = vec![from, to];
+        loop {
+            let [prev, current] = [path[path.len() - 2], path[path.len() - 1]];
+            let incoming = self.direction(prev, current);
+            let next = adjacency[current]
+                .iter()
+                .filter(|next| **next != prev)
+                .max_by(|a, b| {
+                    let bend = |next: usize| incoming.dot(self.direction(current, next));
+                    bend(**a).total_cmp(&bend(**b))
+                });
+            let Some(next) = next else {
+                return path;
+            };
+            path.push(*next);
+        }
+    }
+
+    /// Sweep the cross section along the path. A branch starting at a hub face replaces it,
+    /// otherwise it starts at a leaf and gets a cap. Returns the side branches of the path.
+    fn add_branch(
+        &self,
+        mesh: &mut SMesh,
+        adjacency: &[Vec<usize>],
+        path: &[usize],
+        hub_face: Option<Vec<VertexId>>,
+        data: &mut SkinData,
+    ) -> SMeshResult<Vec<Branch>> {
+        let directions = path
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| self.direction(*a, *b))
+            .collect_vec();
+        let mut frame = directions[0];
+        let mut rings = vec![];
+        let mut offsets = match &hub_face {
+            Some(face) => {
+                let center = face.iter().map(|v| mesh.positions[*v]).sum::<Vec3>() / 4.0;
+                rings.push(face.clone());
+                face.iter()
+                    .map(|v| (mesh.positions[*v] - center).reject_from(frame).normalize())
+                    .collect_vec()
+            }
+            None => {
+                let normal = frame.any_orthonormal_vector();
+                let binormal = frame.cross(normal);
+                vec![normal, binormal, -normal, -binormal]
+            }
+        };
+        let mut add_ring = |mesh: &mut SMesh, node: usize, center: Vec3, direction: Vec3| {
+            // parallel transport, rotating the frame as little as possible
+            let rotation = Quat::from_rotation_arc(frame, direction);
+            frame = direction;
+            for offset in offsets.iter_mut() {
+                *offset = rotation * *offset;
+            }
+            let radius = self.nodes[node].1;
+            let ring = offsets
+                .iter()
+                .map(|offset| mesh.add_vertex(center + *offset * radius))
+                .collect_vec();
+            data.node_rings[node].push(ring.clone());
+            ring
+        };
+
+        let mut hubs = vec![];
+        for (k, node) in path.iter().copied().enumerate() {
+            let p = self.position(node);
+            if k == 0 {
+                if hub_face.is_none() {
+                    rings.push(add_ring(mesh, node, p, directions[0]));
+                }
+            } else if k == path.len() - 1 {
+                rings.push(add_ring(mesh, node, p, directions[k - 1]));
+            } else {
+                let (incoming, outgoing) = (directions[k - 1], directions[k]);
+                let sides = adjacency[node]
+                    .iter()
+                    .copied()
+                    .filter(|n| *n != path[k - 1] && *n != path[k + 1])
+                    .collect_vec();
+                if sides.is_empty() {
+                    let direction = (incoming + outgoing).try_normalize().unwrap_or(incoming);
+                    rings.push(add_ring(mesh, node, p, direction));
+                } else {
+                    // a box around the node with a square on each side
+                    let offset = self.nodes[node].1 * std::f32::consts::FRAC_1_SQRT_2;
+                    rings.push(add_ring(mesh, node, p - incoming * offset, incoming));
+                    hubs.push((rings.len() - 1, node, sides));
+                    rings.push(add_ring(mesh, node, p + outgoing * offset, outgoing));
+                }
+            }
+        }
+
+        let faces = mesh.loft(&rings)?;
+        if hub_face.is_none() {
+            mesh.add_face(rings[0].iter().rev().copied().collect_vec())?;
+        }
+        mesh.add_face(rings[rings.len() - 1].clone())?;
+
+        let mut branches = vec![];
+        for (ring, node, sides) in hubs {
+            let mut free = faces[ring * 4..ring * 4 + 4].to_vec();
+            for side in sides {
+                let direction = self.direction(node, side);
+                let Some(best) = free.iter().position_max_by(|a, b| {
+                    let alignment = |f: FaceId| f.normal(mesh).unwrap_or_default().dot(direction);
+                    alignment(**a).total_cmp(&alignment(**b))
+                }) else {
+                    bail!(UnsupportedOperation);
+                };
+                let face = free.swap_remove(best);
+                let vertices = face.vertices(mesh).collect_vec();
+                mesh.delete_face(face)?;
+                branches.push((node, side, Some(vertices)));
+            }
+        }
+        Ok(branches)
+    }
+
+    /// Cube around a node without edges
+    fn add_box(&self, mesh: &mut SMesh, node: usize, data: &mut SkinData) -> SMeshResult<()> {
+        let (p, radius) = self.nodes[node];
+        let offset = radius * std::f32::consts::FRAC_1_SQRT_2;
+        let rings = [-offset, offset]
+            .map(|y| {
+                [Vec3::X, Vec3::NEG_Z, Vec3::NEG_X, Vec3::Z]
+                    .map(|d| mesh.add_vertex(p + Vec3::Y * y + d * radius))
+                    .to_vec()
+            })
+            .to_vec();
+        mesh.loft_with_caps(&rings)?;
+        data.node_rings[node] = rings;
+        Ok(())
+    }
+}
+
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::vec3;
+
+    #[test]
+    fn skin() -> SMeshResult<()> {
+        // a cross with a second branching node, and an isolated node
+        let nodes = vec![
+            (Vec3::ZERO, 0.5),
+            (vec3(0.0, -2.0, 0.0), 0.3),
+            (vec3(0.0, 2.0, 0.0), 0.4),
+            (vec3(2.0, 0.0, 0.0), 0.3),
+            (vec3(-2.0, 0.5, 0.0), 0.3),
+            (vec3(0.0, 4.0, 0.0), 0.2),
+            (vec3(0.0, 3.0, 2.0), 0.2),
+            (vec3(5.0, 5.0, 5.0), 1.0),
+        ];
+        let edges = vec![(0, 1), (0, 2), (0, 3), (0, 4), (2, 5), (2, 6)];
+        let (mesh, data) = Skin {
+            nodes: nodes.clone(),
+            edges: edges.clone(),
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        // the main sweep, three side branches replacing a hub face each, and a cube
+        assert_eq!(mesh.faces().len(), (5 * 4 + 2) + 3 * (4 + 1 - 1) + 6);
+        assert!(mesh.volume()? > 0.0);
+        assert_eq!(data.node_rings[0].len(), 2);
+        for v in &data.node_rings[3][0] {
+            assert!((mesh.positions[*v].distance(nodes[3].0) - 0.3).abs() < 1e-5);
+        }
+
+        let skin = |edges: Vec<(usize, usize)>| {
+            Skin {
+                nodes: nodes.clone(),
+                edges,
+            }
+            .generate()
+            .err()
+        };
+        assert_eq!(
+            skin(vec![(0, 1), (1, 2), (2, 0)]),
+            Some(SMeshError::UnsupportedOperation)
+        );
+        assert_eq!(skin(vec![(0, 0)]), Some(SMeshError::DefaultError));
+        assert_eq!(skin(vec![(0, 8)]), Some(SMeshError::DefaultError));
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use glam::{vec3, Vec2, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// How the steps of [`Stairs`] are supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StairsMode {
+    /// One solid staircase standing on the ground
+    #[default]
+    Closed,
+    /// Every step is a separate block
+    Floating,
+}
+
+/// Staircase starting at the origin and climbing along +z, spanning the width along +x
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stairs {
+    pub steps: usize,
+    /// Width (x), rise (y) and depth (z) of a single step
+    pub step_size: Vec3,
+    pub mode: StairsMode,
+}
+
+impl Default for Stairs {
+    fn default() -> Self {
+        Self {
+            steps: 8,
+            step_size: vec3(1.0, 0.2, 0.3),
+            mode: StairsMode::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StairsData {
+    /// The top face of every step, from bottom to top
+    pub tread_faces: Vec<FaceId>,
+}
+
+impl Primitive for Stairs {
+    type PrimitiveData = StairsData;
+
+    /// The side profile of the stairs is created as one ngon and extruded along the width.
+    /// Fails without steps or with a step size that is not positive.
+    fn generate(self) -> SMeshResult<(SMesh, StairsData)> {
+        if self.steps == 0 || self.step_size.cmple(Vec3::ZERO).any() {
+            bail!(DefaultError);
+        }
+        let (rise, depth) = (self.step_size.y, self.step_size.z);
+        let mut mesh = SMesh::new();
+        match self.mode {
+            StairsMode::Closed => {
+                // (z, y) counter clockwise when looking along +x
+                let mut profile = vec![Vec2::ZERO];
+                for i in 0..self.steps {
+                    let y = (i + 1) as f32 * rise;
+                    profile.push(Vec2::new(i as f32 * depth, y));
+                    profile.push(Vec2::new((i + 1) as f32 * depth, y));
+                }
+                profile.push(Vec2::new(self.steps as f32 * depth, 0.0));
+                extrude_profile(&mut mesh, &profile, self.step_size.x)?;
+            }
+            StairsMode::Floating => {
+                for i in 0..self.steps {
+                    let (z, y) = (i as f32 * depth, i as f32 * rise);
+                    let profile = [
+                        Vec2::new(z, y),
+                        Vec2::new(z, y + rise),
+                        Vec2::new(z + depth, y + rise),
+                        Vec2::new(z + depth, y),
+                    ];
+                    extrude_profile(&mut mesh, &profile, self.step_size.x)?;
+                }
+            }
+        }
+        let tread_faces = mesh
+            .faces()
+            .keys()
+            .filter(|f| f.normal(&mesh).is_ok_and(|n| n.y > 0.5))
+            .map(|f| {
+                (
+                    f,
+                    f.vertices(&mesh).map(|v| mesh.positions[v].y).sum::<f32>(),
+                )
+            })
+            .sorted_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(f, _)| f)
+            .collect();
+        Ok((mesh, StairsData { tread_faces }))
+    }
+}
+
+/// Create the (z, y) profile as face at x = 0 and extrude it to x = `width`
+fn extrude_profile(mesh: &mut SMesh, profile: &[Vec2], width: f32) -> SMeshResult<()> {
+    let vertices = profile
+        .iter()
+        .map(|p| mesh.add_vertex(vec3(0.0, p.y, p.x)))
+        .collect_vec();
+    let face = mesh.add_face(vertices.clone())?;
+    let extruded = mesh.extrude_faces(face)?;
+    mesh.translate(extruded, Vec3::X * width)?;
+    mesh.add_face(vertices.into_iter().rev().collect())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    #[test]
+    fn stairs() -> SMeshResult<()> {
+        let stairs = Stairs {
+            steps: 3,
+            step_size: vec3(2.0, 1.0, 1.0),
+            mode: StairsMode::Closed,
+        };
+        let (mesh, data) = stairs.generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 8 + 2);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!((mesh.volume()? - 2.0 * 6.0).abs() < 1e-4);
+        assert_eq!(data.tread_faces.len(), 3);
+        for (i, f) in data.tread_faces.iter().enumerate() {
+            assert!(f
+                .vertices(&mesh)
+                .all(|v| mesh.positions[v].y == i as f32 + 1.0));
+        }
+
+        let (mesh, data) = Stairs {
+            mode: StairsMode::Floating,
+            ..stairs
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 18);
+        assert!((mesh.volume()? - 2.0 * 3.0).abs() < 1e-4);
+        assert_eq!(data.tread_faces.len(), 3);
+
+        assert!(Stairs { steps: 0, ..stairs }.generate().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,383 @@
+use glam::{vec2, Vec2};
+use itertools::Itertools;
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Number of line segments a curve of a glyph outline gets flattened into
+const CURVE_STEPS: usize = 8;
+
+/// Extruded letters of a font, starting at the origin with the baseline on the x axis.
+/// The front of the letters faces +z at z = 0, lines of the text continue downwards.
+#[derive(Debug, Clone)]
+pub struct TextMesh<'a> {
+    pub text: String,
+    /// Data of a TrueType or OpenType font file
+    pub font: &'a [u8],
+    /// Height of an em, the nominal size of the font
+    pub size: f32,
+    /// Depth of the letters along -z. Letters without depth only get front faces.
+    pub extrude_depth: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextMeshData {
+    pub front_faces: Vec<FaceId>,
+    pub back_faces: Vec<FaceId>,
+    pub side_faces: Vec<FaceId>,
+}
+
+impl Primitive for TextMesh<'_> {
+    type PrimitiveData = TextMeshData;
+
+    /// Characters missing from the font get its placeholder glyph.
+    /// Fails if the font can not be parsed, the size is not positive or the depth is negative.
+    fn generate(self) -> SMeshResult<(SMesh, TextMeshData)> {
+        if self.size <= 0.0 || self.extrude_depth < 0.0 {
+            bail!(DefaultError);
+        }
+        let Ok(face) = Face::parse(self.font, 0) else {
+            bail!(DefaultError);
+        };
+        let scale = self.size / face.units_per_em() as f32;
+        let line_height =
+            (face.ascender() as f32 - face.descender() as f32 + face.line_gap() as f32) * scale;
+
+        let mut mesh = SMesh::new();
+        let mut data = TextMeshData::default();
+        let mut cursor = Vec2::ZERO;
+        for c in self.text.chars() {
+            if c == '\n' {
+                cursor = vec2(0.0, cursor.y - line_height);
+                continue;
+            }
+            let glyph = face.glyph_index(c).unwrap_or(GlyphId(0));
+            let mut outline = Outline::default();
+            face.outline_glyph(glyph, &mut outline);
+            let contours = outline
+                .contours
+                .into_iter()
+                .map(|contour| {
+                    contour
+                        .into_iter()
+                        .map(|p| cursor + p * scale)
+                        .collect_vec()
+                })
+                .collect_vec();
+            add_outline(&mut mesh, &contours, self.extrude_depth, &mut data)?;
+            cursor.x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+        }
+        Ok((mesh, data))
+    }
+}
+
+/// Closed contours of a glyph, with the curves flattened
+#[derive(Default)]
+struct Outline {
+    contours: Vec<Vec<Vec2>>,
+    current: Vec<Vec2>,
+}
+
+impl Outline {
+    fn last(&self) -> Vec2 {
+        self.current.last().copied().unwrap_or_default()
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.current.push(vec2(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push(vec2(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.last(), vec2(x1, y1), vec2(x, y));
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let s = 1.0 - t;
+            self.current
+                .push(p0 * s * s + p1 * 2.0 * s * t + p2 * t * t);
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (self.last(), vec2(x1, y1), vec2(x2, y2), vec2(x, y));
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let s = 1.0 - t;
+            self.current.push(
+                p0 * s * s * s + p1 * 3.0 * s * s * t + p2 * 3.0 * s * t * t + p3 * t * t * t,
+            );
+        }
+    }
+
+    fn close(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        contour.dedup_by(|a, b| a.distance(*b) < 1e-6);
+        while contour.len() > 1 && contour[0].distance(contour[contour.len() - 1]) < 1e-6 {
+            contour.pop();
+        }
+        if contour.len() >= 3 {
+            self.contours.push(contour);
+        }
+    }
+}
+
+/// Add the faces of a flat outline made of nested contours, extruded by `depth`.
+/// Contours inside an odd number of other contours are holes, their orientation is ignored.
+fn add_outline(
+    mesh: &mut SMesh,
+    contours: &[Vec<Vec2>],
+    depth: f32,
+    data: &mut TextMeshData,
+) -> SMeshResult<()> {
+    let contours = contours
+        .iter()
+        .filter(|c| signed_area(c).abs() > 1e-9)
+        .collect_vec();
+    let nesting = contours
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            contours
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| i != *j && contains(other, c[0]))
+                .count()
+        })
+        .collect_vec();
+    // outer contours counter clockwise, holes clockwise
+    let contours = contours
+        .iter()
+        .zip(&nesting)
+        .map(|(c, n)| {
+            let mut c = c.to_vec();
+            if (signed_area(&c) > 0.0) != (n % 2 == 0) {
+                c.reverse();
+            }
+            c
+        })
+        .collect_vec();
+
+    for (outer, n) in contours.iter().zip(&nesting) {
+        if n % 2 == 1 {
+            continue;
+        }
+        let loops = std::iter::once(outer)
+            .chain(
+                contours
+                    .iter()
+                    .zip(&nesting)
+                    .filter(|(c, m)| **m == n + 1 && contains(outer, c[0]))
+                    .map(|(c, _)| c),
+            )
+            .collect_vec();
+        let points = loops.iter().copied().flatten().copied().collect_vec();
+        let mut start = 0;
+        let index_loops = loops
+            .iter()
+            .map(|l| {
+                start += l.len();
+                (start - l.len()..start).collect_vec()
+            })
+            .collect_vec();
+
+        let front = points
+            .iter()
+            .map(|p| mesh.add_vertex(p.extend(0.0)))
+            .collect_vec();
+        let triangles = triangulate(&points, &index_loops);
+        let faces = triangles
+            .iter()
+            .map(|t| t.iter().map(|i| front[*i]).collect_vec())
+            .collect_vec();
+        data.front_faces.extend(mesh.add_faces(&faces)?);
+        if depth <= 0.0 {
+            continue;
+        }
+
+        let back = points
+            .iter()
+            .map(|p| mesh.add_vertex(p.extend(-depth)))
+            .collect_vec();
+        let faces = triangles
+            .iter()
+            .map(|t| t.iter().rev().map(|i| back[*i]).collect_vec())
+            .collect_vec();
+        data.back_faces.extend(mesh.add_faces(&faces)?);
+        let faces = index_loops
+            .iter()
+            .flat_map(|l| {
+                l.iter()
+                    .circular_tuple_windows()
+                    .map(|(a, b)| vec![front[*b], front[*a], back[*a], back[*b]])
+            })
+            .collect_vec();
+        data.side_faces.extend(mesh.add_faces(&faces)?);
+    }
+    Ok(())
+}
+
+fn signed_area(contour: &[Vec2]) -> f32 {
+    contour
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum::<f32>()
+        * 0.5
+}
+
+/// Whether `p` is inside the contour, by the even-odd rule
+fn contains(contour: &[Vec2], p: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in contour.iter().circular_tuple_windows() {
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Ear clipping of a counter clockwise polygon with clockwise holes, given as loops of
+/// indices into `points`. The holes get connected to the outer loop first.
+fn triangulate(points: &[Vec2], loops: &[Vec<usize>]) -> Vec<[usize; 3]> {
+    let mut polygon = loops[0].clone();
+    let max_x = |l: &Vec<usize>| l.iter().map(|i| points[*i].x).fold(f32::MIN, f32::max);
+    let holes = loops[1..]
+        .iter()
+        .sorted_by(|a, b| max_x(b).total_cmp(&max_x(a)))
+        .collect_vec();
+    for hole in holes {
+        let m = hole
+            .iter()
+            .position_max_by(|a, b| points[**a].x.total_cmp(&points[**b].x))
+            .unwrap();
+        let k = bridge(points, &polygon, points[hole[m]]);
+        let mut bridged = polygon[..=k].to_vec();
+        bridged.extend(hole[m..].iter().chain(&hole[..=m]));
+        bridged.extend(&polygon[k..]);
+        polygon = bridged;
+    }
+
+    let cross =
+        |a: usize, b: usize, c: usize| (points[b] - points[a]).perp_dot(points[c] - points[b]);
+    let mut triangles = Vec::with_capacity(polygon.len());
+    let (mut i, mut stalled) = (0, 0);
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        i %= n;
+        let (a, b, c) = (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+        let is_ear = cross(a, b, c) > 0.0
+            && polygon.iter().all(|p| {
+                [a, b, c].contains(p)
+                    || cross(a, b, *p) < 0.0
+                    || cross(b, c, *p) < 0.0
+                    || cross(c, a, *p) < 0.0
+            });
+        // clip anyway when no ear is left, for self intersecting outlines
+        if is_ear || stalled > n {
+            triangles.push([a, b, c]);
+            polygon.remove(i);
+            stalled = 0;
+        } else {
+            i += 1;
+            stalled += 1;
+        }
+    }
+    triangles.push([polygon[0], polygon[1], polygon[2]]);
+    triangles
+}
+
+/// Position in the polygon of a vertex that is visible from the point `m` of a hole,
+/// by casting a ray from `m` towards +x
+fn bridge(points: &[Vec2], polygon: &[usize], m: Vec2) -> usize {
+    let mut hit = None;
+    for (k, (a, b)) in polygon
+        .iter()
+        .map(|i| points[*i])
+        .circular_tuple_windows()
+        .enumerate()
+    {
+        if (a.y > m.y) == (b.y > m.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= m.x && hit.is_none_or(|(hit_x, _)| x < hit_x) {
+            let candidate = if a.x > b.x {
+                k
+            } else {
+                (k + 1) % polygon.len()
+            };
+            hit = Some((x, candidate));
+        }
+    }
+    let Some((x, candidate)) = hit else {
+        return 0;
+    };
+    // a vertex inside the triangle between the ray and the candidate would block the view,
+    // take the one closest in angle to the ray instead
+    let (i, p) = (vec2(x, m.y), points[polygon[candidate]]);
+    let inside = |q: Vec2| {
+        let signs = [(m, i), (i, p), (p, m)].map(|(a, b)| (b - a).perp_dot(q - a));
+        signs.iter().all(|s| *s >= 0.0) || signs.iter().all(|s| *s <= 0.0)
+    };
+    polygon
+        .iter()
+        .enumerate()
+        .filter(|(k, v)| *k != candidate && points[**v].x >= m.x && inside(points[**v]))
+        .min_by(|(_, a), (_, b)| {
+            let angle = |v: usize| {
+                let d = points[v] - m;
+                d.y.abs() / d.x.max(1e-9)
+            };
+            angle(**a).total_cmp(&angle(**b))
+        })
+        .map_or(candidate, |(k, _)| k)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+
+    fn square(center: Vec2, size: f32) -> Vec<Vec2> {
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(x, y)| center + vec2(x, y) * size * 0.5)
+            .to_vec()
+    }
+
+    #[test]
+    fn outline_with_holes() -> SMeshResult<()> {
+        // a clockwise outer contour, a hole and an island in the hole, like a font might have
+        let mut outer = square(Vec2::ZERO, 6.0);
+        outer.reverse();
+        let contours = [outer, square(Vec2::ZERO, 4.0), square(vec2(0.5, 0.0), 1.0)];
+        let mut mesh = SMesh::new();
+        let mut data = TextMeshData::default();
+        add_outline(&mut mesh, &contours, 0.5, &mut data)?;
+        check_connectivity(&mesh)?;
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert_eq!(data.side_faces.len(), 12);
+        assert!((mesh.volume()? - (36.0 - 16.0 + 1.0) * 0.5).abs() < 1e-4);
+        for f in &data.front_faces {
+            assert!(f.normal(&mesh)?.distance(glam::Vec3::Z) < 1e-5);
+        }
+
+        let flat = add_outline(&mut SMesh::new(), &contours, 0.0, &mut data);
+        assert!(flat.is_ok());
+
+        let invalid = TextMesh {
+            text: "a".to_string(),
+            font: &[0, 1, 2, 3],
+            size: 1.0,
+            extrude_depth: 0.1,
+        };
+        assert_eq!(invalid.generate().err(), Some(SMeshError::DefaultError));
+        Ok(())
+    }
+}
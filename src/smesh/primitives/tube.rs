@@ -0,0 +1,96 @@
+use glam::Vec3;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Circular cross section swept along a polyline, closed with ngons at both ends.
+/// The cross sections are oriented with the [`Polyline::frames`] of the path, so the
+/// tube does not twist around it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tube {
+    pub path: Vec<Vec3>,
+    pub radius: f32,
+    /// Number of vertices around the path
+    pub segments: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TubeData {
+    /// The vertices of the cross section at every point of the path
+    pub rings: Vec<Vec<VertexId>>,
+    pub start_cap: Option<FaceId>,
+    pub end_cap: Option<FaceId>,
+}
+
+impl Primitive for Tube {
+    type PrimitiveData = TubeData;
+
+    /// Fails with less than two path points, repeated path points or less than three segments
+    fn generate(self) -> SMeshResult<(SMesh, TubeData)> {
+        if self.segments < 3 {
+            bail!(DefaultError);
+        }
+        let frames = Polyline::new(self.path).frames()?;
+
+        let mut mesh = SMesh::new();
+        let mut data = TubeData::default();
+        for frame in frames {
+            let ring = (0..self.segments)
+                .map(|j| {
+                    let angle = j as f32 / self.segments as f32 * std::f32::consts::TAU;
+                    let offset = frame.normal * angle.cos() + frame.binormal * angle.sin();
+                    mesh.add_vertex(frame.position + offset * self.radius)
+                })
+                .collect::<Vec<_>>();
+            data.rings.push(ring);
+        }
+        let faces = mesh.loft_with_caps(&data.rings)?;
+        data.start_cap = faces.get(faces.len() - 2).copied();
+        data.end_cap = faces.last().copied();
+        Ok((mesh, data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::check_connectivity;
+    use glam::vec3;
+
+    #[test]
+    fn tube() -> SMeshResult<()> {
+        let path = vec![
+            Vec3::ZERO,
+            vec3(0.0, 1.0, 0.0),
+            vec3(1.0, 2.0, 0.0),
+            vec3(2.0, 2.0, 1.0),
+        ];
+        let (mesh, data) = Tube {
+            path: path.clone(),
+            radius: 0.25,
+            segments: 6,
+        }
+        .generate()?;
+        check_connectivity(&mesh)?;
+        assert_eq!(mesh.faces().len(), 3 * 6 + 2);
+        assert!(mesh.halfedges().keys().all(|h| !h.is_boundary(&mesh)));
+        assert!(mesh.volume()? > 0.0);
+        for (ring, p) in data.rings.iter().zip(&path) {
+            for v in ring {
+                assert!((mesh.positions[*v].distance(*p) - 0.25).abs() < 1e-5);
+            }
+        }
+        let end = data.end_cap.unwrap().normal(&mesh)?;
+        assert!(end.distance(vec3(1.0, 0.0, 1.0).normalize()) < 1e-4);
+
+        let tube = |path: Vec<Vec3>| Tube {
+            path,
+            radius: 1.0,
+            segments: 6,
+        };
+        assert!(tube(vec![Vec3::ZERO]).generate().is_err());
+        assert!(tube(vec![Vec3::ZERO, Vec3::ZERO]).generate().is_err());
+        Ok(())
+    }
+}
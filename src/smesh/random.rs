@@ -0,0 +1,36 @@
+/// Small deterministic pseudo random number generator (SplitMix64), so seeded
+/// operations give the same results on every platform
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in [0, n)
+    pub(crate) fn next_index(&mut self, n: usize) -> usize {
+        (((self.next_u64() >> 32) * n as u64) >> 32) as usize
+    }
+
+    /// Uniform value in [0, 1)
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Move `count` randomly chosen elements to the front of the slice
+    pub(crate) fn partial_shuffle<T>(&mut self, values: &mut [T], count: usize) {
+        for i in 0..count.min(values.len()) {
+            let j = i + self.next_index(values.len() - i);
+            values.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use glam::{Vec2, Vec3};
+
+use crate::prelude::*;
+
+/// Immutable triangulated snapshot of a mesh for rendering, see [`SMesh::freeze`].
+/// The buffers are shared, so clones are cheap and can be sent to other threads
+/// while the mesh keeps being edited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderMesh {
+    positions: Arc<[Vec3]>,
+    normals: Arc<[Vec3]>,
+    uvs: Arc<[Vec2]>,
+    indices: Arc<[u32]>,
+}
+
+impl RenderMesh {
+    /// Vertex positions, one per render vertex
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    /// Vertex normals, one per render vertex, or empty if the mesh has no normals
+    pub fn normals(&self) -> &[Vec3] {
+        &self.normals
+    }
+
+    /// UV coordinates, one per render vertex, or empty if the mesh has no uvs
+    pub fn uvs(&self) -> &[Vec2] {
+        &self.uvs
+    }
+
+    /// Three indices into the vertex buffers per triangle
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+impl SMesh {
+    /// Create a triangulated [`RenderMesh`] snapshot of the mesh.
+    /// Face corners share a render vertex if they have the same vertex, normal and uv.
+    /// Normals are taken from the halfedge normals if present, then from the vertex
+    /// normals and the face normals.
+    pub fn freeze(&self) -> SMeshResult<RenderMesh> {
+        let has_normals = self.halfedge_normals.is_some()
+            || self.vertex_normals.is_some()
+            || self.face_normals.is_some();
+        let mut positions = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+        let mut indices = vec![];
+        let mut render_vertices = HashMap::new();
+
+        for f in self.faces().keys() {
+            let face_normal = self.face_normals.as_ref().and_then(|n| n.get(f).copied());
+            let mut corners = vec![];
+            for h in f.halfedges(self) {
+                let v = h.src_vert().run(self)?;
+                let normal = self
+                    .halfedge_normals
+                    .as_ref()
+                    .and_then(|n| n.get(h).copied())
+                    .or_else(|| self.vertex_normals.as_ref().and_then(|n| n.get(v).copied()))
+                    .or(face_normal)
+                    .unwrap_or(Vec3::ZERO);
+                let uv = self
+                    .uvs
+                    .as_ref()
+                    .and_then(|uvs| uvs.get(h).copied())
+                    .unwrap_or(Vec2::ZERO);
+                let key = (
+                    v,
+                    normal.to_array().map(f32::to_bits),
+                    uv.to_array().map(f32::to_bits),
+                );
+                let index = *render_vertices.entry(key).or_insert_with(|| {
+                    positions.push(self.positions[v]);
+                    if has_normals {
+                        normals.push(normal);
+                    }
+                    if self.uvs.is_some() {
+                        uvs.push(uv);
+                    }
+                    positions.len() as u32 - 1
+                });
+                corners.push(index);
+            }
+            for i in 1..corners.len() - 1 {
+                indices.extend([corners[0], corners[i], corners[i + 1]]);
+            }
+        }
+
+        Ok(RenderMesh {
+            positions: positions.into(),
+            normals: normals.into(),
+            uvs: uvs.into(),
+            indices: indices.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+
+    #[test]
+    fn freeze() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let frozen = mesh.freeze()?;
+        assert_eq!(frozen.positions().len(), 8);
+        assert_eq!(frozen.triangle_count(), 12);
+        assert!(frozen.normals().is_empty());
+
+        // flat shading needs a render vertex per face corner
+        mesh.mark_sharp_by_angle(std::f32::consts::FRAC_PI_4)?;
+        mesh.recalculate_normals(NormalWeighting::Angle)?;
+        let flat = mesh.freeze()?;
+        assert_eq!(flat.positions().len(), 24);
+        assert_eq!(flat.normals().len(), 24);
+
+        // the snapshot stays unchanged while the mesh gets edited
+        let copy = flat.clone();
+        let vertices = mesh.vertices().keys().collect::<Vec<_>>();
+        mesh.translate(vertices, Vec3::X)?;
+        assert_eq!(copy, flat);
+        let handle = std::thread::spawn(move || copy.triangle_count());
+        assert_eq!(handle.join().unwrap(), 12);
+        Ok(())
+    }
+}
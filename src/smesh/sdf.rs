@@ -0,0 +1,144 @@
+use glam::{UVec3, Vec3};
+
+use crate::bail;
+use crate::prelude::*;
+use crate::smesh::bvh::Bvh;
+use crate::smesh::generate::sdf_to_mesh;
+
+/// Signed distances sampled on a regular grid.
+/// The grid spans `bounds` (min, max) with `resolution` cells on each axis,
+/// so it holds `resolution + 1` samples per axis, stored x first, then y, then z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfGrid {
+    pub bounds: (Vec3, Vec3),
+    pub resolution: UVec3,
+    pub values: Vec<f32>,
+}
+
+impl SdfGrid {
+    /// Number of samples on each axis
+    pub fn samples(&self) -> UVec3 {
+        self.resolution + UVec3::ONE
+    }
+
+    /// Size of a grid cell
+    pub fn cell_size(&self) -> Vec3 {
+        (self.bounds.1 - self.bounds.0) / self.resolution.as_vec3()
+    }
+
+    /// Position of the grid sample
+    pub fn position(&self, index: UVec3) -> Vec3 {
+        self.bounds.0 + index.as_vec3() * self.cell_size()
+    }
+
+    /// Value of the grid sample
+    pub fn get(&self, index: UVec3) -> f32 {
+        let samples = self.samples();
+        let index = index.min(self.resolution);
+        self.values[(index.x + samples.x * (index.y + samples.y * index.z)) as usize]
+    }
+
+    /// Trilinear interpolation of the samples at `p`. Points outside of the bounds
+    /// are clamped to the bounds.
+    pub fn sample(&self, p: Vec3) -> f32 {
+        let local =
+            ((p - self.bounds.0) / self.cell_size()).clamp(Vec3::ZERO, self.resolution.as_vec3());
+        let cell = local.floor().as_uvec3().min(self.resolution - UVec3::ONE);
+        let t = local - cell.as_vec3();
+        let value = |x: u32, y: u32, z: u32| self.get(cell + UVec3::new(x, y, z));
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let y0 = lerp(
+            lerp(value(0, 0, 0), value(1, 0, 0), t.x),
+            lerp(value(0, 1, 0), value(1, 1, 0), t.x),
+            t.y,
+        );
+        let y1 = lerp(
+            lerp(value(0, 0, 1), value(1, 0, 1), t.x),
+            lerp(value(0, 1, 1), value(1, 1, 1), t.x),
+            t.y,
+        );
+        lerp(y0, y1, t.z)
+    }
+
+    /// Polygonize the zero level set of the grid, see [`sdf_to_mesh`]
+    pub fn to_mesh(&self) -> SMeshResult<SMesh> {
+        sdf_to_mesh(|p| self.sample(p), self.bounds, self.resolution)
+    }
+}
+
+impl SMesh {
+    /// Sample the signed distance to the mesh surface on a regular grid spanning
+    /// `bounds` (min, max) with `resolution` cells on each axis.
+    /// Distances are negative inside of the mesh, which is determined with a ray parity
+    /// test and therefore only meaningful for closed meshes.
+    pub fn to_sdf(&self, bounds: (Vec3, Vec3), resolution: UVec3) -> SMeshResult<SdfGrid> {
+        let (min, max) = bounds;
+        if resolution.cmpeq(UVec3::ZERO).any() || max.cmple(min).any() {
+            bail!(DefaultError);
+        }
+        let bvh = Bvh::new(self);
+        let mut grid = SdfGrid {
+            bounds,
+            resolution,
+            values: vec![],
+        };
+        let samples = grid.samples();
+        grid.values
+            .reserve((samples.x * samples.y * samples.z) as usize);
+        for z in 0..samples.z {
+            for y in 0..samples.y {
+                for x in 0..samples.x {
+                    let p = grid.position(UVec3::new(x, y, z));
+                    let Some((_, q)) = bvh.closest_point(p) else {
+                        bail!(DefaultError);
+                    };
+                    let distance = p.distance(q);
+                    grid.values
+                        .push(if bvh.contains(p) { -distance } else { distance });
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::cube;
+    use glam::vec3;
+
+    #[test]
+    fn to_sdf() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 2.0)?;
+        let sdf = mesh.to_sdf((Vec3::splat(-2.0), Vec3::splat(2.0)), UVec3::splat(4))?;
+        assert_eq!(sdf.values.len(), 125);
+        assert!((sdf.get(UVec3::splat(2)) + 1.0).abs() < 1e-5);
+        assert!((sdf.get(UVec3::new(4, 2, 2)) - 1.0).abs() < 1e-5);
+        assert!((sdf.get(UVec3::new(3, 2, 2))).abs() < 1e-5);
+        assert!((sdf.sample(vec3(0.5, 0.0, 0.0)) + 0.5).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn sdf_roundtrip() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 2.0)?;
+        let sdf = mesh.to_sdf((Vec3::splat(-1.6), Vec3::splat(1.6)), UVec3::splat(8))?;
+        let remeshed = sdf.to_mesh()?;
+        assert!(remeshed
+            .halfedges()
+            .keys()
+            .all(|h| !h.is_boundary(&remeshed)));
+        for v in remeshed.vertices().keys() {
+            let p = remeshed.positions[v];
+            assert!((p.abs().max_element() - 1.0).abs() < 0.1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn to_sdf_empty_mesh() {
+        let result = SMesh::new().to_sdf((Vec3::ZERO, Vec3::ONE), UVec3::ONE);
+        assert_eq!(result.err(), Some(SMeshError::DefaultError));
+    }
+}
@@ -0,0 +1,834 @@
+use std::collections::HashSet;
+
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::attribute::{
+    sealed::AttributeMaps, CustomAttributeMapOps, CustomAttributeOps, MeshAttribute,
+};
+use crate::prelude::*;
+use crate::smesh::random::SplitMix64;
+
+/// A set of mesh elements (vertices, halfedges and faces) that mesh operations
+/// can be applied to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshSelection {
+    vertices: HashSet<VertexId>,
+    halfedges: HashSet<HalfedgeId>,
+    faces: HashSet<FaceId>,
+}
+
+/// Which faces a selection resolves to, see [`MeshSelection::resolve_to_faces_with_mode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResolveMode {
+    /// Faces with all of their vertices selected
+    #[default]
+    Strict,
+    /// Faces with at least one selected vertex
+    Partial,
+}
+
+pub trait MeshSelectionOps<T> {
+    fn insert(&mut self, id: T);
+    fn contains(&self, id: T) -> bool;
+}
+
+impl MeshSelectionOps<VertexId> for MeshSelection {
+    fn insert(&mut self, id: VertexId) {
+        self.vertices.insert(id);
+    }
+    fn contains(&self, id: VertexId) -> bool {
+        self.vertices.contains(&id)
+    }
+}
+
+impl MeshSelectionOps<HalfedgeId> for MeshSelection {
+    fn insert(&mut self, id: HalfedgeId) {
+        self.halfedges.insert(id);
+    }
+    fn contains(&self, id: HalfedgeId) -> bool {
+        self.halfedges.contains(&id)
+    }
+}
+
+impl MeshSelectionOps<FaceId> for MeshSelection {
+    fn insert(&mut self, id: FaceId) {
+        self.faces.insert(id);
+    }
+    fn contains(&self, id: FaceId) -> bool {
+        self.faces.contains(&id)
+    }
+}
+
+impl MeshSelection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.halfedges.is_empty() && self.faces.is_empty()
+    }
+
+    /// Number of selected elements of all types
+    pub fn len(&self) -> usize {
+        self.vertices.len() + self.halfedges.len() + self.faces.len()
+    }
+
+    /// Directly selected vertices, without resolving halfedges and faces
+    pub fn vertices(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertices.iter().copied()
+    }
+
+    /// Directly selected halfedges, without resolving vertices and faces
+    pub fn halfedges(&self) -> impl Iterator<Item = HalfedgeId> + '_ {
+        self.halfedges.iter().copied()
+    }
+
+    /// Directly selected faces, without resolving vertices and halfedges
+    pub fn faces(&self) -> impl Iterator<Item = FaceId> + '_ {
+        self.faces.iter().copied()
+    }
+
+    /// Elements that are selected in either selection
+    pub fn union(&self, other: &MeshSelection) -> MeshSelection {
+        MeshSelection {
+            vertices: &self.vertices | &other.vertices,
+            halfedges: &self.halfedges | &other.halfedges,
+            faces: &self.faces | &other.faces,
+        }
+    }
+
+    /// Elements that are selected in both selections
+    pub fn intersection(&self, other: &MeshSelection) -> MeshSelection {
+        MeshSelection {
+            vertices: &self.vertices & &other.vertices,
+            halfedges: &self.halfedges & &other.halfedges,
+            faces: &self.faces & &other.faces,
+        }
+    }
+
+    /// Elements that are selected in this selection but not in `other`
+    pub fn difference(&self, other: &MeshSelection) -> MeshSelection {
+        MeshSelection {
+            vertices: &self.vertices - &other.vertices,
+            halfedges: &self.halfedges - &other.halfedges,
+            faces: &self.faces - &other.faces,
+        }
+    }
+
+    /// All elements of the mesh that are not selected.
+    /// Vertices, halfedges and faces are inverted separately, and element types
+    /// without any selected element stay empty, so inverting a face selection
+    /// selects the remaining faces. An empty selection inverts to the whole mesh.
+    pub fn invert(&self, mesh: &SMesh) -> MeshSelection {
+        let all = self.is_empty();
+        MeshSelection {
+            vertices: invert_set(&self.vertices, mesh.vertices().keys(), all),
+            halfedges: invert_set(&self.halfedges, mesh.halfedges().keys(), all),
+            faces: invert_set(&self.faces, mesh.faces().keys(), all),
+        }
+    }
+
+    /// Random subset of `fraction` (in [0, 1]) of the selected elements of every type.
+    /// The same seed always picks the same elements. Elements that are not part of
+    /// the mesh anymore are ignored.
+    pub fn random_subset(&self, mesh: &SMesh, fraction: f32, seed: u64) -> MeshSelection {
+        let mut rng = SplitMix64::new(seed);
+        MeshSelection {
+            vertices: random_subset(&self.vertices, mesh.vertices(), fraction, &mut rng),
+            halfedges: random_subset(&self.halfedges, mesh.halfedges(), fraction, &mut rng),
+            faces: random_subset(&self.faces, mesh.faces(), fraction, &mut rng),
+        }
+    }
+
+    /// All selected vertices, including the vertices of selected halfedges and faces
+    pub fn resolve_to_vertices(&self, mesh: &SMesh) -> SMeshResult<HashSet<VertexId>> {
+        let mut vertices = self.vertices.clone();
+        for he in &self.halfedges {
+            vertices.insert(he.src_vert().run(mesh)?);
+            vertices.insert(he.dst_vert().run(mesh)?);
+        }
+        for f in &self.faces {
+            vertices.extend(f.vertices(mesh));
+        }
+        Ok(vertices)
+    }
+
+    /// All selected halfedges, including the halfedges of selected faces and
+    /// halfedges that connect two selected vertices
+    pub fn resolve_to_halfedges(&self, mesh: &SMesh) -> SMeshResult<HashSet<HalfedgeId>> {
+        let mut halfedges = self.halfedges.clone();
+        for f in &self.faces {
+            halfedges.extend(f.halfedges(mesh));
+        }
+        for v in &self.vertices {
+            for he in v.halfedges(mesh) {
+                if self.vertices.contains(&he.dst_vert().run(mesh)?) {
+                    halfedges.insert(he);
+                }
+            }
+        }
+        Ok(halfedges)
+    }
+
+    /// All selected faces, including faces where every vertex is selected
+    pub fn resolve_to_faces(&self, mesh: &SMesh) -> SMeshResult<HashSet<FaceId>> {
+        self.resolve_to_faces_with_mode(mesh, ResolveMode::Strict)
+    }
+
+    /// All selected faces, including faces with every vertex ([`ResolveMode::Strict`])
+    /// or any vertex ([`ResolveMode::Partial`]) selected. In partial mode the faces
+    /// of selected halfedges are included too, boundary halfedges are skipped.
+    pub fn resolve_to_faces_with_mode(
+        &self,
+        mesh: &SMesh,
+        mode: ResolveMode,
+    ) -> SMeshResult<HashSet<FaceId>> {
+        let mut faces = self.faces.clone();
+        let vertices = self.resolve_to_vertices(mesh)?;
+        if mode == ResolveMode::Partial {
+            faces.extend(
+                self.halfedges
+                    .iter()
+                    .filter_map(|h| h.face().run(mesh).ok()),
+            );
+        }
+        for v in &vertices {
+            if v.is_isolated(mesh) {
+                continue;
+            }
+            for f in v.faces(mesh) {
+                if mode == ResolveMode::Partial || f.vertices(mesh).all(|fv| vertices.contains(&fv))
+                {
+                    faces.insert(f);
+                }
+            }
+        }
+        Ok(faces)
+    }
+}
+
+/// Selection queries
+impl SMesh {
+    /// All vertices, halfedges and faces of the connected components that contain
+    /// the seed elements
+    pub fn select_linked<S: Into<MeshSelection>>(&self, seed: S) -> SMeshResult<MeshSelection> {
+        let mut stack = seed
+            .into()
+            .resolve_to_vertices(self)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut selection = MeshSelection::new();
+        for v in &stack {
+            if !self.vertices().contains_key(*v) {
+                return Err(SMeshError::VertexNotFound(*v));
+            }
+            selection.insert(*v);
+        }
+        while let Some(v) = stack.pop() {
+            if v.is_isolated(self) {
+                continue;
+            }
+            for h in v.halfedges(self) {
+                selection.insert(h);
+                selection.insert(h.opposite().run(self)?);
+                if let Ok(f) = h.face().run(self) {
+                    selection.insert(f);
+                }
+                let neighbour = h.dst_vert().run(self)?;
+                if !selection.contains(neighbour) {
+                    selection.insert(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Faces reachable from `seed_face` across edges whose uvs match on both sides,
+    /// the uv chart containing the face. Fails if the mesh has no uvs.
+    pub fn select_uv_island(&self, seed_face: FaceId) -> SMeshResult<MeshSelection> {
+        let Some(uvs) = self.uvs.as_ref() else {
+            bail!(UnsupportedOperation);
+        };
+        if !self.faces().contains_key(seed_face) {
+            bail!(FaceNotFound, seed_face);
+        }
+        // uvs are stored per corner, on the halfedge leaving the corner vertex
+        let same_uv = |a: HalfedgeId, b: HalfedgeId| match (uvs.get(a), uvs.get(b)) {
+            (Some(a), Some(b)) => a.distance_squared(*b) <= f32::EPSILON * f32::EPSILON,
+            _ => false,
+        };
+        let mut selection = MeshSelection::from(seed_face);
+        let mut stack = vec![seed_face];
+        while let Some(f) = stack.pop() {
+            for h in f.halfedges(self) {
+                let o = h.opposite().run(self)?;
+                let Ok(neighbour) = o.face().run(self) else {
+                    continue;
+                };
+                if selection.contains(neighbour) {
+                    continue;
+                }
+                if same_uv(h, o.next().run(self)?) && same_uv(h.next().run(self)?, o) {
+                    selection.insert(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Both halfedges of all edges in the edge loop through `h`.
+    /// The loop continues straight across vertices with four incident quads and stops
+    /// at boundaries and at other vertices, like poles or triangle fans.
+    pub fn select_edge_loop(&self, h: HalfedgeId) -> SMeshResult<MeshSelection> {
+        self.walk_edges(h, |mesh, h| {
+            let v = h.dst_vert().run(mesh)?;
+            if v.is_boundary(mesh) || v.valence(mesh) != 4 {
+                bail!(DefaultError);
+            }
+            for f in [h.face(), h.opposite().face()] {
+                if f.run(mesh)?.valence(mesh) != 4 {
+                    bail!(DefaultError);
+                }
+            }
+            h.next().opposite().next().run(mesh)
+        })
+    }
+
+    /// Both halfedges of all edges in the edge ring through `h`, the edges facing
+    /// each other across a strip of quads. The ring stops at boundaries and non-quad faces.
+    pub fn select_edge_ring(&self, h: HalfedgeId) -> SMeshResult<MeshSelection> {
+        self.walk_edges(h, |mesh, h| {
+            let f = h.face().run(mesh)?;
+            if f.valence(mesh) != 4 {
+                bail!(DefaultError);
+            }
+            h.next().next().opposite().run(mesh)
+        })
+    }
+
+    /// The strip of quads that the edge ring through `h` crosses
+    pub fn select_face_loop(&self, h: HalfedgeId) -> SMeshResult<MeshSelection> {
+        let ring = self.select_edge_ring(h)?;
+        Ok(ring
+            .halfedges()
+            .filter_map(|h| h.face().run(self).ok())
+            .filter(|f| f.valence(self) == 4)
+            .collect_vec()
+            .into())
+    }
+
+    /// Follow `step` from `h` and from its opposite until it fails
+    /// or the walk gets back to an edge that was already visited
+    fn walk_edges(
+        &self,
+        h: HalfedgeId,
+        step: impl Fn(&SMesh, HalfedgeId) -> SMeshResult<HalfedgeId>,
+    ) -> SMeshResult<MeshSelection> {
+        let opposite = h.opposite().run(self)?;
+        let mut selection = MeshSelection::from(vec![h, opposite]);
+        for start in [h, opposite] {
+            let mut current = start;
+            while let Ok(next) = step(self, current) {
+                if selection.contains(next) {
+                    break;
+                }
+                selection.insert(next);
+                selection.insert(next.opposite().run(self)?);
+                current = next;
+            }
+        }
+        Ok(selection)
+    }
+
+    /// All vertices inside of the axis aligned box spanned by `bounds` (min, max)
+    pub fn select_in_aabb(&self, bounds: (Vec3, Vec3)) -> MeshSelection {
+        let (min, max) = bounds;
+        self.select_vertices_where(|v, mesh| {
+            let p = mesh.positions[v];
+            p.cmpge(min).all() && p.cmple(max).all()
+        })
+    }
+
+    /// All vertices within `radius` of `center`
+    pub fn select_in_sphere(&self, center: Vec3, radius: f32) -> MeshSelection {
+        self.select_vertices_where(|v, mesh| {
+            mesh.positions[v].distance_squared(center) <= radius * radius
+        })
+    }
+
+    /// All vertices for which `predicate` returns true
+    pub fn select_vertices_where(
+        &self,
+        predicate: impl Fn(VertexId, &SMesh) -> bool,
+    ) -> MeshSelection {
+        self.vertices()
+            .keys()
+            .filter(|v| predicate(*v, self))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// All halfedges for which `predicate` returns true
+    pub fn select_halfedges_where(
+        &self,
+        predicate: impl Fn(HalfedgeId, &SMesh) -> bool,
+    ) -> MeshSelection {
+        self.halfedges()
+            .keys()
+            .filter(|h| predicate(*h, self))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// All faces for which `predicate` returns true
+    pub fn select_faces_where(&self, predicate: impl Fn(FaceId, &SMesh) -> bool) -> MeshSelection {
+        self.faces()
+            .keys()
+            .filter(|f| predicate(*f, self))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// All elements whose value in the attribute map `name` is of type `V` and matches
+    /// the `predicate`, including elements that only have the default value of the map.
+    /// Fails if there is no such attribute map.
+    pub fn select_by_attribute<K, V>(
+        &self,
+        name: &str,
+        predicate: impl Fn(V) -> bool,
+    ) -> SMeshResult<MeshSelection>
+    where
+        K: slotmap::Key,
+        V: TryFrom<MeshAttribute>,
+        MeshAttribute: From<V>,
+        Self: CustomAttributeOps<K>,
+        MeshSelection: From<Vec<K>>,
+    {
+        let Some(attribute) = self.attribute::<K>(name) else {
+            bail!(DefaultError);
+        };
+        Ok(self
+            .element_keys_internal()
+            .into_iter()
+            .filter(|k| attribute.get(*k).is_some_and(&predicate))
+            .collect_vec()
+            .into())
+    }
+
+    /// All faces with the material index, see [`SMesh::material_index`]
+    pub fn select_by_material(&self, index: u32) -> MeshSelection {
+        self.faces()
+            .keys()
+            .filter(|f| self.material_index(*f) == index)
+            .collect_vec()
+            .into()
+    }
+
+    /// All faces whose normal is within `max_angle` (in radians) of `reference`
+    pub fn select_by_normal(&self, reference: Vec3, max_angle: f32) -> SMeshResult<MeshSelection> {
+        let reference = reference.normalize_or_zero();
+        let mut selection = MeshSelection::new();
+        for f in self.faces().keys() {
+            if f.normal(self)?.angle_between(reference) <= max_angle {
+                selection.insert(f);
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Faces reachable from `seed_face` across edges, without crossing faces whose
+    /// normal differs more than `tolerance` (in radians) from the normal of the seed face
+    pub fn select_coplanar(&self, seed_face: FaceId, tolerance: f32) -> SMeshResult<MeshSelection> {
+        let normal = seed_face.normal(self)?;
+        let mut selection = MeshSelection::from(seed_face);
+        let mut stack = vec![seed_face];
+        while let Some(f) = stack.pop() {
+            for neighbour in f.neighbors(self) {
+                if !selection.contains(neighbour)
+                    && neighbour.normal(self)?.angle_between(normal) <= tolerance
+                {
+                    selection.insert(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        Ok(selection)
+    }
+}
+
+fn random_subset<K: slotmap::Key, V>(
+    selected: &HashSet<K>,
+    elements: &slotmap::SlotMap<K, V>,
+    fraction: f32,
+    rng: &mut SplitMix64,
+) -> HashSet<K> {
+    // sorted, since the iteration order of the set is random
+    let mut ids = selected
+        .iter()
+        .copied()
+        .filter(|id| elements.contains_key(*id))
+        .sorted()
+        .collect_vec();
+    let count = (ids.len() as f32 * fraction.clamp(0.0, 1.0)).round() as usize;
+    rng.partial_shuffle(&mut ids, count);
+    ids.into_iter().take(count).collect()
+}
+
+fn invert_set<T: Copy + Eq + std::hash::Hash>(
+    selected: &HashSet<T>,
+    all: impl Iterator<Item = T>,
+    invert_empty: bool,
+) -> HashSet<T> {
+    if selected.is_empty() && !invert_empty {
+        return HashSet::new();
+    }
+    all.filter(|id| !selected.contains(id)).collect()
+}
+
+macro_rules! impl_selection_from {
+    ($type:ident) => {
+        impl From<$type> for MeshSelection {
+            fn from(value: $type) -> Self {
+                let mut s = MeshSelection::new();
+                s.insert(value);
+                s
+            }
+        }
+
+        impl From<Vec<$type>> for MeshSelection {
+            fn from(value: Vec<$type>) -> Self {
+                let mut s = MeshSelection::new();
+                for id in value {
+                    s.insert(id);
+                }
+                s
+            }
+        }
+
+        impl From<HashSet<$type>> for MeshSelection {
+            fn from(value: HashSet<$type>) -> Self {
+                let mut s = MeshSelection::new();
+                for id in value {
+                    s.insert(id);
+                }
+                s
+            }
+        }
+    };
+}
+
+impl_selection_from!(VertexId);
+impl_selection_from!(HalfedgeId);
+impl_selection_from!(FaceId);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, quad_grid, triangulated_grid};
+    use glam::{vec2, vec3, Vec2};
+    use slotmap::SecondaryMap;
+
+    #[test]
+    fn resolve() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let v0 = mesh.add_vertex(vec3(-1.0, -1.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, -1.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(-1.0, 1.0, 0.0));
+        let v4 = mesh.add_vertex(vec3(0.0, -2.0, 0.0));
+        let f0 = mesh.add_face(vec![v0, v1, v2, v3])?;
+        mesh.add_face(vec![v0, v4, v1])?;
+
+        let selection = MeshSelection::from(f0);
+        assert_eq!(selection.resolve_to_vertices(mesh)?.len(), 4);
+        assert_eq!(selection.resolve_to_halfedges(mesh)?.len(), 4);
+
+        let selection = MeshSelection::from(vec![v0, v1, v2, v3]);
+        assert_eq!(selection.resolve_to_faces(mesh)?, HashSet::from([f0]));
+        // 4 inner halfedges and their opposites
+        assert_eq!(selection.resolve_to_halfedges(mesh)?.len(), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn select_linked() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let cube_face = mesh.faces().keys().next().unwrap();
+        let t0 = mesh.add_vertex(vec3(2.0, 0.0, 0.0));
+        let t1 = mesh.add_vertex(vec3(3.0, 0.0, 0.0));
+        let t2 = mesh.add_vertex(vec3(2.0, 1.0, 0.0));
+        let triangle = mesh.add_triangle(t0, t1, t2)?;
+        let isolated = mesh.add_vertex(Vec3::ZERO);
+
+        let linked = mesh.select_linked(t0)?;
+        assert_eq!(
+            linked.vertices().collect::<HashSet<_>>(),
+            HashSet::from([t0, t1, t2])
+        );
+        assert_eq!(linked.halfedges().count(), 6);
+        assert_eq!(linked.faces().collect::<Vec<_>>(), vec![triangle]);
+
+        let linked = mesh.select_linked(cube_face)?;
+        assert_eq!(linked.vertices().count(), 8);
+        assert_eq!(linked.halfedges().count(), 24);
+        assert_eq!(linked.faces().count(), 6);
+
+        assert_eq!(mesh.select_linked(isolated)?, MeshSelection::from(isolated));
+        Ok(())
+    }
+
+    #[test]
+    fn select_by_normal() -> SMeshResult<()> {
+        let mesh = cube(Vec3::ZERO, 1.0)?;
+        let top = mesh.select_by_normal(Vec3::Y, 0.1)?;
+        assert_eq!(top.faces().count(), 1);
+        let f = top.faces().next().unwrap();
+        assert_eq!(f.normal(&mesh)?, Vec3::Y);
+        let sides = mesh.select_by_normal(Vec3::Y, std::f32::consts::FRAC_PI_2)?;
+        assert_eq!(sides.faces().count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn select_spatial() -> SMeshResult<()> {
+        let mesh = triangulated_grid(4)?;
+        let in_box = mesh.select_in_aabb((vec3(0.5, 0.5, -1.0), vec3(2.0, 3.0, 1.0)));
+        assert_eq!(in_box.vertices().count(), 6);
+        assert_eq!(in_box.resolve_to_faces(&mesh)?.len(), 4);
+
+        let in_sphere = mesh.select_in_sphere(vec3(2.0, 2.0, 0.0), 1.0);
+        assert_eq!(in_sphere.vertices().count(), 5);
+        assert!(in_sphere
+            .vertices()
+            .all(|v| mesh.positions[v].distance(vec3(2.0, 2.0, 0.0)) <= 1.0));
+        Ok(())
+    }
+
+    #[test]
+    fn select_where() -> SMeshResult<()> {
+        let mesh = triangulated_grid(2)?;
+        let boundary = mesh.select_vertices_where(|v, mesh| v.is_boundary(mesh));
+        assert_eq!(boundary.len(), 8);
+        let boundary = mesh.select_halfedges_where(|h, mesh| h.is_boundary(mesh));
+        assert_eq!(boundary.len(), 8);
+        let lower =
+            mesh.select_faces_where(|f, mesh| f.vertices(mesh).all(|v| mesh.positions[v].y <= 1.0));
+        assert_eq!(lower.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn select_coplanar() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(4)?;
+        // fold the grid along x = 2
+        for v in mesh.vertices().keys().collect::<Vec<_>>() {
+            let p = mesh.positions[v];
+            if p.x > 2.0 {
+                mesh.positions[v].z = p.x - 2.0;
+            }
+        }
+        let seed = mesh
+            .faces()
+            .keys()
+            .find(|f| f.vertices(&mesh).all(|v| mesh.positions[v].x < 1.5))
+            .unwrap();
+        let flat = mesh.select_coplanar(seed, 0.1)?;
+        assert_eq!(flat.faces().count(), 16);
+        let all = mesh.select_coplanar(seed, std::f32::consts::FRAC_PI_2)?;
+        assert_eq!(all.faces().count(), 32);
+        Ok(())
+    }
+
+    #[test]
+    fn random_subset() -> SMeshResult<()> {
+        let mesh = triangulated_grid(4)?;
+        let faces = MeshSelection::from(mesh.faces().keys().collect_vec());
+        let subset = faces.random_subset(&mesh, 0.25, 7);
+        assert_eq!(subset.len(), 8);
+        assert_eq!(subset.intersection(&faces), subset);
+        assert_eq!(faces.random_subset(&mesh, 0.25, 7), subset);
+        assert_ne!(faces.random_subset(&mesh, 0.25, 8), subset);
+        assert_eq!(faces.random_subset(&mesh, 1.0, 7), faces);
+        Ok(())
+    }
+
+    #[test]
+    fn select_loops_and_rings() -> SMeshResult<()> {
+        let mesh = quad_grid(3)?;
+        let vertex = |p: Vec3| {
+            mesh.vertices()
+                .keys()
+                .find(|v| mesh.positions[*v] == p)
+                .unwrap()
+        };
+        let h = vertex(vec3(1.0, 1.0, 0.0))
+            .halfedge_to(vertex(vec3(2.0, 1.0, 0.0)))
+            .run(&mesh)?;
+
+        let edge_loop = mesh.select_edge_loop(h)?;
+        assert_eq!(edge_loop.halfedges().count(), 6);
+        let vertices = edge_loop.resolve_to_vertices(&mesh)?;
+        assert_eq!(vertices.len(), 4);
+        assert!(vertices.iter().all(|v| mesh.positions[*v].y == 1.0));
+
+        let ring = mesh.select_edge_ring(h)?;
+        assert_eq!(ring.halfedges().count(), 8);
+        let vertices = ring.resolve_to_vertices(&mesh)?;
+        assert!(vertices
+            .iter()
+            .all(|v| (1.0..=2.0).contains(&mesh.positions[*v].x)));
+
+        let faces = mesh.select_face_loop(h)?;
+        assert_eq!(faces.faces().count(), 3);
+        assert_eq!(faces.halfedges().count(), 0);
+        assert_eq!(faces.resolve_to_vertices(&mesh)?, vertices);
+
+        // closed ring around the cube, loops stop at the valence 3 corners
+        let mesh = cube(Vec3::ZERO, 1.0)?;
+        let h = mesh.halfedges().keys().next().unwrap();
+        assert_eq!(mesh.select_edge_ring(h)?.halfedges().count(), 8);
+        assert_eq!(mesh.select_face_loop(h)?.faces().count(), 4);
+        assert_eq!(mesh.select_edge_loop(h)?.halfedges().count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn select_uv_island() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        let face = mesh.faces().keys().next().unwrap();
+        assert_eq!(
+            mesh.select_uv_island(face),
+            Err(SMeshError::UnsupportedOperation)
+        );
+
+        // two charts, split at x = 1
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            let Ok(f) = h.face().run(&mesh) else {
+                continue;
+            };
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            let left = f.vertices(&mesh).all(|v| mesh.positions[v].x <= 1.0);
+            let offset = if left { vec2(10.0, 0.0) } else { Vec2::ZERO };
+            uvs.insert(h, vec2(p.x, p.y) + offset);
+        }
+        mesh.uvs = Some(uvs);
+        for face in mesh.faces().keys() {
+            let island = mesh.select_uv_island(face)?;
+            assert_eq!(island.faces().count(), 2);
+            let x = |f: FaceId| f.vertices(&mesh).map(|v| mesh.positions[v].x).sum::<f32>();
+            assert!(island.faces().all(|f| (x(f) < 4.0) == (x(face) < 4.0)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn select_by_attribute() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        let materials = mesh.add_attribute_map::<FaceId>("material").unwrap();
+        for (i, f) in faces.iter().enumerate() {
+            materials.insert(*f, i as i32 % 3);
+        }
+        let selection = mesh.select_by_attribute::<FaceId, i32>("material", |m| m == 2)?;
+        assert_eq!(selection, MeshSelection::from(vec![faces[2], faces[5]]));
+        // values of a different type never match
+        let selection = mesh.select_by_attribute::<FaceId, f32>("material", |_| true)?;
+        assert!(selection.is_empty());
+        assert!(mesh
+            .select_by_attribute::<VertexId, i32>("material", |_| true)
+            .is_err());
+
+        // elements without a value match by the default, deleted elements never match
+        let materials = mesh.add_attribute_map::<FaceId>("material").unwrap();
+        materials.set_default(2);
+        materials.insert(faces[0], 0);
+        materials.insert(faces[1], 2);
+        mesh.delete_face(faces[1])?;
+        let selection = mesh.select_by_attribute::<FaceId, i32>("material", |m| m == 2)?;
+        assert_eq!(selection, MeshSelection::from(faces[2..].to_vec()));
+        Ok(())
+    }
+
+    #[test]
+    fn select_by_material() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let faces = mesh.faces().keys().collect_vec();
+        mesh.set_material_index(faces[1], 2)?;
+        mesh.set_material_index(faces[4], 2)?;
+        mesh.set_material_index(faces[3], 1)?;
+        assert_eq!(
+            mesh.select_by_material(2),
+            MeshSelection::from(vec![faces[1], faces[4]])
+        );
+        assert_eq!(mesh.select_by_material(0).resolve_to_faces(&mesh)?.len(), 3);
+
+        let groups = mesh.faces_by_material();
+        assert_eq!(groups.keys().copied().collect_vec(), vec![0, 1, 2]);
+        assert_eq!(groups[&1], vec![faces[3]]);
+        mesh.clear_material_index(faces[3]);
+        assert_eq!(mesh.material_index(faces[3]), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_partial() -> SMeshResult<()> {
+        let mesh = quad_grid(2)?;
+        let corner = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == Vec3::ZERO)
+            .unwrap();
+        let center = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == vec3(1.0, 1.0, 0.0))
+            .unwrap();
+        let selection = MeshSelection::from(vec![corner, center]);
+        assert!(selection.resolve_to_faces(&mesh)?.is_empty());
+        let partial = selection.resolve_to_faces_with_mode(&mesh, ResolveMode::Partial)?;
+        assert_eq!(partial.len(), 4);
+
+        let boundary = mesh
+            .halfedges()
+            .keys()
+            .filter(|h| h.is_boundary(&mesh))
+            .collect_vec();
+        let selection = MeshSelection::from(boundary);
+        let partial = selection.resolve_to_faces_with_mode(&mesh, ResolveMode::Partial)?;
+        assert_eq!(partial.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_algebra() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let v0 = mesh.add_vertex(vec3(-1.0, -1.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, -1.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(-1.0, 1.0, 0.0));
+        let v4 = mesh.add_vertex(vec3(0.0, -2.0, 0.0));
+        let f0 = mesh.add_face(vec![v0, v1, v2, v3])?;
+        let f1 = mesh.add_face(vec![v0, v4, v1])?;
+
+        let a = MeshSelection::from(vec![v0, v1, v2]);
+        let b = MeshSelection::from(vec![v1, v2, v3]);
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(
+            a.intersection(&b).vertices().collect::<HashSet<_>>(),
+            HashSet::from([v1, v2])
+        );
+        assert_eq!(a.difference(&b), MeshSelection::from(v0));
+
+        let inverted = MeshSelection::from(f0).invert(mesh);
+        assert_eq!(inverted, MeshSelection::from(f1));
+        assert_eq!(a.invert(mesh), MeshSelection::from(vec![v3, v4]));
+        let all = MeshSelection::new().invert(mesh);
+        assert_eq!(all.len(), 5 + 12 + 2);
+        Ok(())
+    }
+}
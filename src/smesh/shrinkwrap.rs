@@ -0,0 +1,126 @@
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::prelude::*;
+use crate::smesh::bvh::Bvh;
+
+/// How vertices get moved onto the target surface by [`SMesh::shrinkwrap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShrinkwrapMode {
+    /// Move every vertex to the closest point on the target surface
+    #[default]
+    Nearest,
+    /// Move every vertex along its normal (in either direction) to the closest
+    /// intersection with the target surface. Vertices whose normal misses the
+    /// target stay in place.
+    Project,
+}
+
+impl SMesh {
+    /// Move the selected vertices onto the surface of the `target` mesh
+    pub fn shrinkwrap<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        target: &SMesh,
+        mode: ShrinkwrapMode,
+    ) -> SMeshResult<()> {
+        let bvh = Bvh::new(target);
+        let vertices = selection
+            .into()
+            .resolve_to_vertices(self)?
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        let mut updated = Vec::with_capacity(vertices.len());
+        for v in vertices {
+            let p = self.positions[v];
+            let projected = match mode {
+                ShrinkwrapMode::Nearest => bvh.closest_point(p).map(|(_, q)| q),
+                ShrinkwrapMode::Project => {
+                    let normal = self.shrinkwrap_normal(v)?;
+                    if normal == Vec3::ZERO {
+                        None
+                    } else {
+                        let mut closest: Option<f32> = None;
+                        for dir in [normal, -normal] {
+                            bvh.intersect_ray(p, dir, |_, d, _| {
+                                if closest.is_none_or(|c| d < c.abs()) {
+                                    closest = Some(if dir == normal { d } else { -d });
+                                }
+                            });
+                        }
+                        closest.map(|d| p + normal * d)
+                    }
+                }
+            };
+            if let Some(q) = projected {
+                updated.push((v, q));
+            }
+        }
+        for (v, p) in updated {
+            self.positions[v] = p;
+            self.dirty_vertices.insert(v);
+        }
+        Ok(())
+    }
+
+    /// Average normal of the faces around the vertex
+    fn shrinkwrap_normal(&self, v: VertexId) -> SMeshResult<Vec3> {
+        let mut normal = Vec3::ZERO;
+        for f in v.faces(self) {
+            normal += f.normal(self)?;
+        }
+        Ok(normal.normalize_or_zero())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, triangulated_grid};
+    use glam::vec3;
+
+    /// Grid spanning [-0.6, 0.6] in x and y, at z = 2, with normals along +z
+    fn grid_above_cube() -> SMeshResult<SMesh> {
+        let mut mesh = triangulated_grid(3)?;
+        for v in mesh.vertices().keys().collect_vec() {
+            let p = mesh.positions[v];
+            mesh.positions[v] = vec3(p.x * 0.4 - 0.6, p.y * 0.4 - 0.6, 2.0);
+        }
+        Ok(mesh)
+    }
+
+    #[test]
+    fn shrinkwrap_nearest() -> SMeshResult<()> {
+        let target = cube(Vec3::ZERO, 1.0)?;
+        let mut mesh = grid_above_cube()?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.shrinkwrap(vertices.clone(), &target, ShrinkwrapMode::Nearest)?;
+        for v in vertices {
+            let p = original[v];
+            let expected = vec3(p.x.clamp(-0.5, 0.5), p.y.clamp(-0.5, 0.5), 0.5);
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn shrinkwrap_project() -> SMeshResult<()> {
+        let target = cube(Vec3::ZERO, 1.0)?;
+        let mut mesh = grid_above_cube()?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.shrinkwrap(vertices.clone(), &target, ShrinkwrapMode::Project)?;
+        for v in vertices {
+            let p = original[v];
+            if p.x.abs() < 0.5 && p.y.abs() < 0.5 {
+                assert!(mesh.positions[v].distance(vec3(p.x, p.y, 0.5)) < 1e-5);
+            } else {
+                // normal misses the target
+                assert_eq!(mesh.positions[v], p);
+            }
+        }
+        Ok(())
+    }
+}
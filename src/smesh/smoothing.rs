@@ -0,0 +1,156 @@
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+impl SMesh {
+    /// Uniform laplacian smoothing of the selected vertices.
+    /// In each iteration every vertex is moved by `factor` towards the average
+    /// position of its neighbours. Boundary vertices are pinned in place.
+    pub fn smooth<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        iterations: usize,
+        factor: f32,
+    ) -> SMeshResult<()> {
+        let vertices = self.smoothing_vertices(selection)?;
+        for _ in 0..iterations {
+            self.laplacian_step(&vertices, factor);
+        }
+        Ok(())
+    }
+
+    /// Taubin (lambda|mu) smoothing of the selected vertices.
+    /// Every iteration performs a shrinking laplacian step with the positive `lambda`
+    /// followed by an inflating step with the negative `mu` (with `|mu| > lambda`), so
+    /// the mesh gets smoothed without the volume loss of plain laplacian smoothing.
+    /// Typical values are `lambda = 0.5` and `mu = -0.53`.
+    /// Boundary vertices are pinned in place.
+    pub fn smooth_taubin<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        lambda: f32,
+        mu: f32,
+        iterations: usize,
+    ) -> SMeshResult<()> {
+        let vertices = self.smoothing_vertices(selection)?;
+        for _ in 0..iterations {
+            self.laplacian_step(&vertices, lambda);
+            self.laplacian_step(&vertices, mu);
+        }
+        Ok(())
+    }
+
+    /// Selected non-boundary vertices in a stable order
+    fn smoothing_vertices<S: Into<MeshSelection>>(
+        &self,
+        selection: S,
+    ) -> SMeshResult<Vec<VertexId>> {
+        Ok(selection
+            .into()
+            .resolve_to_vertices(self)?
+            .into_iter()
+            .filter(|v| !v.is_isolated(self) && !v.is_boundary(self))
+            .sorted()
+            .collect_vec())
+    }
+
+    /// Move all vertices by `factor` along their uniform laplacian.
+    /// All displacements are computed before any vertex gets moved.
+    fn laplacian_step(&mut self, vertices: &[VertexId], factor: f32) {
+        let mut updated = Vec::with_capacity(vertices.len());
+        for v in vertices {
+            let p = self.positions[*v];
+            let (count, sum) = v
+                .vertices(self)
+                .fold((0, Vec3::ZERO), |(c, s), n| (c + 1, s + self.positions[n]));
+            if count == 0 {
+                continue;
+            }
+            let average = sum / count as f32;
+            updated.push((*v, p + (average - p) * factor));
+        }
+        for (v, p) in updated {
+            self.positions[v] = p;
+            self.dirty_vertices.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::vec3;
+
+    /// 3x3 vertex grid in the xz plane with a raised center vertex
+    fn bumped_grid(mesh: &mut SMesh) -> SMeshResult<(VertexId, Vec<VertexId>)> {
+        let mut ids = vec![];
+        for z in 0..3 {
+            for x in 0..3 {
+                let y = if x == 1 && z == 1 { 1.0 } else { 0.0 };
+                ids.push(mesh.add_vertex(vec3(x as f32, y, -z as f32)));
+            }
+        }
+        for z in 0..2 {
+            for x in 0..2 {
+                let i = z * 3 + x;
+                mesh.add_quad(ids[i], ids[i + 1], ids[i + 4], ids[i + 3])?;
+            }
+        }
+        Ok((ids[4], ids))
+    }
+
+    #[test]
+    fn smooth() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let (center, ids) = bumped_grid(mesh)?;
+        mesh.smooth(ids.clone(), 1, 0.5)?;
+        assert!((mesh.positions[center].y - 0.5).abs() < 1e-5);
+        // boundary is pinned
+        for v in ids.iter().filter(|v| **v != center) {
+            assert_eq!(mesh.positions[*v].y, 0.0);
+        }
+        mesh.smooth(ids, 10, 1.0)?;
+        assert!(mesh.positions[center].y.abs() < 1e-5);
+        Ok(())
+    }
+
+    /// Closed octagonal tube with caps
+    fn tube(mesh: &mut SMesh) -> SMeshResult<Vec<VertexId>> {
+        let profiles = (0..4)
+            .map(|i| {
+                (0..8)
+                    .map(|j| {
+                        let a = j as f32 / 8.0 * std::f32::consts::TAU;
+                        mesh.add_vertex(vec3(a.cos(), i as f32, -a.sin()))
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        mesh.loft_with_caps(&profiles)?;
+        Ok(mesh.vertices().keys().collect_vec())
+    }
+
+    fn mean_radius(mesh: &SMesh) -> f32 {
+        let positions = mesh.positions.values().collect_vec();
+        let center = positions.iter().copied().sum::<Vec3>() / positions.len() as f32;
+        positions.iter().map(|p| p.distance(center)).sum::<f32>() / positions.len() as f32
+    }
+
+    #[test]
+    fn taubin_preserves_volume() -> SMeshResult<()> {
+        let laplacian = &mut SMesh::new();
+        let ids = tube(laplacian)?;
+        let initial = mean_radius(laplacian);
+        laplacian.smooth(ids, 10, 0.5)?;
+
+        let taubin = &mut SMesh::new();
+        let ids = tube(taubin)?;
+        taubin.smooth_taubin(ids, 0.5, -0.53, 10)?;
+
+        let shrink_laplacian = initial - mean_radius(laplacian);
+        let shrink_taubin = initial - mean_radius(taubin);
+        assert!(shrink_taubin.abs() < shrink_laplacian * 0.5);
+        Ok(())
+    }
+}
@@ -1,6 +1,19 @@
+use std::collections::HashSet;
+
 use crate::smesh::iterators::*;
+use crate::smesh::selection::MeshSelection;
 use crate::smesh::*;
 
+/// Corners on one side of an edge that share the uvs of the face next to the edge
+pub(crate) struct UvWedge {
+    /// uv at the start and the target vertex of the edge
+    pub uv0: Vec2,
+    pub uv1: Vec2,
+    /// corners at the start and the target vertex with these uvs, up to the next uv seam
+    pub corners0: Vec<HalfedgeId>,
+    pub corners1: Vec<HalfedgeId>,
+}
+
 ///
 /// Higher-level Topological Operations
 ///
@@ -45,13 +58,19 @@ impl SMesh {
     ///  o------>o------->o
     ///   <------ <-------
     ///     o0       o1
+    ///
+    /// The attributes of v and of the new corners are interpolated by the position of
+    /// v along the edge, edge attributes are copied to both halves of the edge.
     pub fn insert_vertex(&mut self, h0: HalfedgeId, v: VertexId) -> SMeshResult<HalfedgeId> {
         let h2 = h0.next().run(self).ok();
         let o0 = h0.opposite().run(self)?;
         let o2 = o0.prev().run(self).ok();
+        let o0_next = o0.next().run(self).ok();
+        let v0 = h0.src_vert().run(self)?;
         let v2 = h0.dst_vert().run(self)?;
         let fh = h0.face().run(self).ok();
         let fo = o0.face().run(self).ok();
+        let t = edge_parameter(self.positions[v0], self.positions[v2], self.positions[v]);
 
         let (h1, o1) = self.add_edge(v, v2);
 
@@ -86,9 +105,88 @@ impl SMesh {
         if let Some(fo) = fo {
             self.face_mut(fo).halfedge = Some(o1);
         }
+
+        // attributes, o1 takes over the corner of o0 at v2 and o0 now starts at v
+        self.interpolate_vertex_attributes(v, v0, v2, t);
+        if let Some(h2) = h2 {
+            self.interpolate_corner_attributes(h1, h0, h2, t);
+        }
+        self.interpolate_corner_attributes(o1, o0, o0, 0.0);
+        if let Some(o0_next) = o0_next {
+            self.interpolate_corner_attributes(o0, o0_next, o1, t);
+        }
+        self.copy_edge_attributes(h0, h1);
+        self.copy_edge_attributes(o0, o1);
+
+        self.dirty_vertices.insert(v);
         Ok(o1)
     }
 
+    /// Split the face of h0 by inserting an edge between the target vertices
+    /// v0 of h0 and v1 of h1. Both halfedges need to belong to the same face.
+    /// The face keeps the part from v1 to v0, the part from v0 to v1 is
+    /// moved to a new face. Returns the new halfedge from v0 to v1.
+    /// The new face gets the attributes of the split face, the corners at the new edge
+    /// the attributes of the corners they were split from.
+    ///
+    /// before:
+    ///
+    ///    h0      h2
+    ///  o--->v0 o---->
+    ///
+    ///  <----o v1<---o
+    ///    h3      h1
+    ///
+    /// after:
+    ///
+    ///    h0      h2
+    ///  o--->v0 o---->
+    ///       h4| ^h5
+    ///         v |
+    ///  <----o v1<---o
+    ///    h3      h1
+    pub fn insert_edge(&mut self, h0: HalfedgeId, h1: HalfedgeId) -> SMeshResult<HalfedgeId> {
+        let f0 = h0.face().run(self)?;
+        if h1.face().run(self)? != f0 {
+            bail!(TopologyError);
+        }
+        let v0 = h0.dst_vert().run(self)?;
+        let v1 = h1.dst_vert().run(self)?;
+        let h2 = h0.next().run(self)?;
+        let h3 = h1.next().run(self)?;
+        if h2 == h1 || h3 == h0 {
+            // vertices are already connected
+            bail!(TopologyError);
+        }
+
+        let (h4, h5) = self.add_edge(v0, v1);
+        let f1 = self.faces_mut().insert(Face::default());
+        self.connectivity.notify(MeshChange::FaceAdded(f1));
+        self.face_mut(f0).halfedge = Some(h0);
+        self.face_mut(f1).halfedge = Some(h1);
+
+        self.get_mut(h0).set_next(Some(h4))?;
+        let mut h4_mut = self.get_mut(h4);
+        h4_mut.set_next(Some(h3))?;
+        h4_mut.set_face(Some(f0))?;
+        self.get_mut(h1).set_next(Some(h5))?;
+        self.get_mut(h5).set_next(Some(h2))?;
+
+        let mut h = h2;
+        loop {
+            self.get_mut(h).set_face(Some(f1))?;
+            h = h.next().run(self)?;
+            if h == h2 {
+                break;
+            }
+        }
+        self.copy_face_attributes(f0, f1);
+        self.interpolate_corner_attributes(h4, h2, h2, 0.0);
+        self.interpolate_corner_attributes(h5, h3, h3, 0.0);
+        self.dirty_vertices.extend([v0, v1]);
+        Ok(h4)
+    }
+
     pub fn delete_vertex(&mut self, v: VertexId) -> SMeshResult<()> {
         let incident_faces = v.faces(self).collect_vec();
         for f in incident_faces {
@@ -109,6 +207,15 @@ impl SMesh {
     }
 
     pub fn delete_face(&mut self, f: FaceId) -> SMeshResult<()> {
+        self.delete_face_internal(f, false)
+    }
+
+    /// Delete a face, but keep vertices that become isolated instead of removing them
+    pub(crate) fn delete_face_keep_vertices(&mut self, f: FaceId) -> SMeshResult<()> {
+        self.delete_face_internal(f, true)
+    }
+
+    fn delete_face_internal(&mut self, f: FaceId, keep_vertices: bool) -> SMeshResult<()> {
         let mut delete_edges = vec![];
         let mut adjust_edges = vec![];
         let mut delete_verts = vec![];
@@ -150,7 +257,11 @@ impl SMesh {
             // update v0
             if v0.halfedge().run(self)? == h1 {
                 if next0 == h1 {
-                    self.get_mut(v0).delete()?;
+                    if keep_vertices {
+                        self.get_mut(v0).set_halfedge(None)?;
+                    } else {
+                        self.get_mut(v0).delete()?;
+                    }
                 } else {
                     self.get_mut(v0).set_halfedge(Some(next0))?;
                 }
@@ -159,7 +270,11 @@ impl SMesh {
             // update v1
             if v1.halfedge().run(self)? == h0 {
                 if next1 == h0 {
-                    self.get_mut(v1).delete()?;
+                    if keep_vertices {
+                        self.get_mut(v1).set_halfedge(None)?;
+                    } else {
+                        self.get_mut(v1).delete()?;
+                    }
                 } else {
                     self.get_mut(v1).set_halfedge(Some(next1))?;
                 }
@@ -219,10 +334,7 @@ impl SMesh {
 
         // test intersection of the one-rings of v0 and v1
         for vv in v0.vertices(self) {
-            if vv != v1
-                && vv != vl.unwrap()
-                && vv != vr.unwrap()
-                && vv.halfedge_to(v1).run(self).is_ok()
+            if vv != v1 && Some(vv) != vl && Some(vv) != vr && vv.halfedge_to(v1).run(self).is_ok()
             {
                 bail!(DefaultError);
             }
@@ -238,11 +350,14 @@ impl SMesh {
     /// This function is only valid for triangle meshes.
     /// Halfedge collapses might lead to invalid faces. Call
     /// is_collapse_ok(Halfedge) to be sure the collapse is legal.
+    /// The uvs of the corners at the start vertex take the uvs of the target vertex in
+    /// the same uv wedge, corners across a uv seam from the collapsed edge keep their uvs.
     pub fn collapse(&mut self, h: HalfedgeId) -> SMeshResult<()> {
         let h0 = h;
         let h1 = h0.prev().run(self)?;
         let o0 = h0.opposite();
         let o1 = o0.next().run(self)?;
+        let wedges = self.edge_uv_wedges(h)?;
         // remove edge
         self.remove_edge_helper(h)?;
 
@@ -254,9 +369,78 @@ impl SMesh {
         if o1.next().next().run(self) == Ok(o1) {
             self.remove_loop_helper(o1)?;
         }
+
+        if let Some(uvs) = self.uvs.as_mut() {
+            for wedge in wedges {
+                for corner in wedge.corners0 {
+                    if let Some(uv) = uvs.get_mut(corner) {
+                        *uv = wedge.uv1;
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
+    /// The uv wedges on both sides of the edge of `h`, see [`UvWedge`]
+    pub(crate) fn edge_uv_wedges(&self, h: HalfedgeId) -> SMeshResult<Vec<UvWedge>> {
+        let Some(uvs) = self.uvs.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let o = h.opposite().run(self)?;
+        let mut visited = HashSet::new();
+        let mut wedges = Vec::new();
+        // corners at the start and target vertex in the faces of h and o, rotating
+        // counter clockwise around the start vertex leads away from the edge on the side of h
+        for (side, ccw) in [(h, true), (o, false)] {
+            if side.is_boundary(self) {
+                continue;
+            }
+            let (c0, c1) = if ccw {
+                (h, h.next().run(self)?)
+            } else {
+                (o.next().run(self)?, o)
+            };
+            let (Some(uv0), Some(uv1)) = (uvs.get(c0), uvs.get(c1)) else {
+                continue;
+            };
+            wedges.push(UvWedge {
+                uv0: *uv0,
+                uv1: *uv1,
+                corners0: self.uv_wedge(uvs, c0, ccw, &mut visited),
+                corners1: self.uv_wedge(uvs, c1, !ccw, &mut visited),
+            });
+        }
+        Ok(wedges)
+    }
+
+    /// Corners around the source vertex of `start` with the same uv, rotating until a
+    /// uv seam or the boundary
+    fn uv_wedge(
+        &self,
+        uvs: &SecondaryMap<HalfedgeId, Vec2>,
+        start: HalfedgeId,
+        ccw: bool,
+        visited: &mut HashSet<HalfedgeId>,
+    ) -> Vec<HalfedgeId> {
+        let uv = uvs.get(start);
+        let mut corners = Vec::new();
+        let mut corner = start;
+        while visited.insert(corner) {
+            corners.push(corner);
+            let next = if ccw {
+                corner.ccw_rotated_neighbour()
+            } else {
+                corner.cw_rotated_neighbour()
+            };
+            match next.run(self) {
+                Ok(next) if !next.is_boundary(self) && uvs.get(next) == uv => corner = next,
+                _ => break,
+            }
+        }
+        corners
+    }
+
     pub fn is_removal_ok(&self, h0: HalfedgeId) -> SMeshResult<()> {
         let h1 = h0.opposite().run(self)?;
         let v0 = h0.dst_vert().run(self)?;
@@ -342,6 +526,66 @@ impl SMesh {
         Ok(())
     }
 
+    /// Merge pairs of adjacent triangles into quads by removing their shared edge.
+    /// A pair is only merged if the angle between the two triangle normals is at most
+    /// `angle_limit` (in radians) and the resulting quad is convex. Pairs that produce
+    /// quads closest to a rectangle are merged first.
+    pub fn tris_to_quads(&mut self, angle_limit: f32) -> SMeshResult<()> {
+        let mut candidates = vec![];
+        for (h, _) in &self.connectivity.halfedges {
+            let o = h.opposite().run(self)?;
+            if h > o || h.is_boundary(self) || o.is_boundary(self) {
+                continue;
+            }
+            let f0 = h.face().run(self)?;
+            let f1 = o.face().run(self)?;
+            if f0.valence(self) != 3 || f1.valence(self) != 3 {
+                continue;
+            }
+            let n0 = f0.normal(self)?;
+            let n1 = f1.normal(self)?;
+            let angle = n0.angle_between(n1);
+            if angle > angle_limit {
+                continue;
+            }
+            // quad corners in face order, starting after the shared edge
+            let corners = [
+                h.dst_vert().run(self)?,
+                h.next().dst_vert().run(self)?,
+                h.src_vert().run(self)?,
+                o.next().dst_vert().run(self)?,
+            ]
+            .map(|v| self.positions[v]);
+            let normal = (n0 + n1).normalize_or_zero();
+            let mut convex = true;
+            let mut shape_error = 0.0;
+            for i in 0..4 {
+                let p = corners[i];
+                let e0 = corners[(i + 3) % 4] - p;
+                let e1 = corners[(i + 1) % 4] - p;
+                convex &= e1.cross(e0).dot(normal) > 0.0;
+                shape_error += (e0.angle_between(e1) - std::f32::consts::FRAC_PI_2).abs();
+            }
+            if convex {
+                candidates.push((angle + shape_error, h, f0, f1));
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged = HashSet::new();
+        for (_, h, f0, f1) in candidates {
+            if merged.contains(&f0) || merged.contains(&f1) {
+                continue;
+            }
+            if self.is_removal_ok(h).is_ok() {
+                self.remove_edge(h)?;
+                merged.insert(f0);
+                merged.insert(f1);
+            }
+        }
+        Ok(())
+    }
+
     fn remove_edge_helper(&mut self, h: HalfedgeId) -> SMeshResult<()> {
         let hn = h.next().run(self)?;
         let hp = h.prev().run(self)?;
@@ -440,11 +684,114 @@ impl SMesh {
 
         Ok(())
     }
+
+    /// Reverse the orientation of the selected faces, so their normals point the other way.
+    /// The corner attributes move with their corners and the stored normals are negated.
+    /// Fails with a topology error if a selected face shares an edge with a face that is
+    /// not selected, since the orientation of a surface can only be reversed as a whole.
+    pub fn flip_faces<S: Into<MeshSelection>>(&mut self, selection: S) -> SMeshResult<()> {
+        let faces = selection
+            .into()
+            .resolve_to_faces(self)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut halfedges = HashSet::new();
+        for f in &faces {
+            for h in f.halfedges(self) {
+                let o = h.opposite().run(self)?;
+                if o.face().run(self).is_ok_and(|of| !faces.contains(&of)) {
+                    bail!(TopologyError);
+                }
+                halfedges.extend([h, o]);
+            }
+        }
+        // boundary loops touching the faces need to be reversed completely as well
+        for h in &halfedges {
+            if h.is_boundary(self) && !halfedges.contains(&h.next().run(self)?) {
+                bail!(TopologyError);
+            }
+        }
+
+        let old = halfedges
+            .iter()
+            .map(|h| Ok((*h, self.halfedges()[*h].clone(), h.src_vert().run(self)?)))
+            .collect::<SMeshResult<Vec<_>>>()?;
+        let vertices = faces
+            .iter()
+            .flat_map(|f| f.vertices(self))
+            .collect::<HashSet<_>>();
+        for v in &vertices {
+            // the outgoing halfedge becomes incoming, keep a boundary halfedge if there is one
+            let h = v.halfedge().run(self)?;
+            let outgoing = if h.is_boundary(self) {
+                h.prev().run(self)?
+            } else {
+                h.opposite().run(self)?
+            };
+            self.vert_mut(*v).halfedge = Some(outgoing);
+        }
+        let old_uvs = self.uvs.clone();
+        let old_normals = self.halfedge_normals.clone();
+        for (h, he, src) in &old {
+            let halfedge = self.he_mut(*h);
+            halfedge.vertex = *src;
+            halfedge.next = he.prev;
+            halfedge.prev = he.next;
+            // the corner at the old source vertex now starts at the previous halfedge
+            let (Some(next), Some(_)) = (he.next, he.face) else {
+                continue;
+            };
+            if let (Some(uvs), Some(old_uvs)) = (self.uvs.as_mut(), old_uvs.as_ref()) {
+                match old_uvs.get(next) {
+                    Some(uv) => uvs.insert(*h, *uv),
+                    None => uvs.remove(*h),
+                };
+            }
+            if let (Some(normals), Some(old_normals)) =
+                (self.halfedge_normals.as_mut(), old_normals.as_ref())
+            {
+                match old_normals.get(next) {
+                    Some(n) => normals.insert(*h, -*n),
+                    None => normals.remove(*h),
+                };
+            }
+        }
+        if let Some(normals) = self.face_normals.as_mut() {
+            for f in &faces {
+                if let Some(n) = normals.get_mut(*f) {
+                    *n = -*n;
+                }
+            }
+        }
+        if let Some(normals) = self.vertex_normals.as_mut() {
+            for v in &vertices {
+                if let Some(n) = normals.get_mut(*v) {
+                    *n = -*n;
+                }
+            }
+        }
+        if self.has_edge_index() {
+            self.build_edge_index();
+        }
+        Ok(())
+    }
+}
+
+/// Parameter of the projection of `p` onto the segment from `p0` to `p1`,
+/// 0 at `p0` and 1 at `p1`
+fn edge_parameter(p0: Vec3, p1: Vec3, p: Vec3) -> f32 {
+    let edge = p1 - p0;
+    if edge.length_squared() > 0.0 {
+        ((p - p0).dot(edge) / edge.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.5
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::prelude::attribute::CustomAttributeMapOps;
     use crate::prelude::*;
     use glam::vec3;
 
@@ -477,8 +824,161 @@ mod test {
         Ok(())
     }
 
+    /// Two triangles sharing the diagonal from (0, 0) to (1, 1), with uvs matching
+    /// the positions and a float attribute on the vertices
+    fn attributed_quad() -> SMeshResult<(SMesh, HalfedgeId)> {
+        let mut mesh = SMesh::new();
+        let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));
+        let v1 = mesh.add_vertex(vec3(1.0, 0.0, 0.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        let v3 = mesh.add_vertex(vec3(0.0, 1.0, 0.0));
+        mesh.add_triangle(v0, v1, v2)?;
+        mesh.add_triangle(v0, v2, v3)?;
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            if !h.is_boundary(&mesh) {
+                uvs.insert(h, mesh.positions[h.src_vert().run(&mesh)?].truncate());
+            }
+        }
+        mesh.uvs = Some(uvs);
+        let weights = mesh.add_attribute_map::<VertexId>("weight").unwrap();
+        weights.insert(v0, 0.0);
+        weights.insert(v2, 1.0);
+        let h = v0.halfedge_to(v2).run(&mesh)?;
+        mesh.set_edge_crease(h, 0.5)?;
+        Ok((mesh, h))
+    }
+
+    fn assert_uvs_match_positions(mesh: &SMesh) -> SMeshResult<()> {
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(mesh) {
+                let p = mesh.positions[h.src_vert().run(mesh)?].truncate();
+                assert!(uvs[h].distance(p) < 1e-5);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn insert_vertex_interpolates_attributes() -> SMeshResult<()> {
+        let (mut mesh, h) = attributed_quad()?;
+        let v = mesh.add_vertex(vec3(0.25, 0.25, 0.0));
+        mesh.insert_vertex(h, v)?;
+        let weight: Option<f32> = mesh.attribute::<VertexId>("weight").unwrap().get(v);
+        assert_eq!(weight, Some(0.25));
+        assert_uvs_match_positions(&mesh)?;
+        for he in v.halfedges(&mesh) {
+            assert_eq!(mesh.edge_crease(he), 0.5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn insert_edge_copies_attributes() -> SMeshResult<()> {
+        let (mut mesh, h) = attributed_quad()?;
+        let v = mesh.add_vertex(vec3(0.5, 0.5, 0.0));
+        mesh.insert_vertex(h, v)?;
+        let f = h.face().run(&mesh)?;
+        mesh.set_smoothing_group(f, 3)?;
+        let corner = f
+            .halfedges(&mesh)
+            .find(|he| he.dst_vert().run(&mesh) == Ok(v))
+            .unwrap();
+        let h4 = mesh.insert_edge(corner, corner.next().next().run(&mesh)?)?;
+        let new_face = h4.opposite().face().run(&mesh)?;
+        assert_ne!(new_face, f);
+        assert_eq!(mesh.smoothing_group(new_face), Some(3));
+        assert_uvs_match_positions(&mesh)?;
+        Ok(())
+    }
+
+    #[test]
+    fn collapse_moves_uvs() -> SMeshResult<()> {
+        let mut mesh = crate::test_utils::triangulated_grid(3)?;
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            if !h.is_boundary(&mesh) {
+                uvs.insert(h, mesh.positions[h.src_vert().run(&mesh)?].truncate());
+            }
+        }
+        mesh.uvs = Some(uvs);
+        let h = mesh
+            .halfedges()
+            .keys()
+            .find(|h| !h.src_vert().is_boundary(&mesh) && mesh.is_collapse_ok(*h).is_ok())
+            .unwrap();
+        mesh.collapse(h)?;
+        assert_uvs_match_positions(&mesh)?;
+        Ok(())
+    }
+
+    #[test]
+    fn collapse_keeps_uvs_across_seam() -> SMeshResult<()> {
+        let mut mesh = crate::test_utils::triangulated_grid(3)?;
+        // the faces below y = 1 are a separate uv island
+        let offset = Vec2::new(10.0, 0.0);
+        let mut uvs = SecondaryMap::new();
+        let mut lower = Vec::new();
+        for f in mesh.faces().keys() {
+            let below = mesh.centroid(f, CentroidWeighting::Uniform)?.y < 1.0;
+            for h in f.halfedges(&mesh) {
+                let uv = mesh.positions[h.src_vert().run(&mesh)?].truncate();
+                uvs.insert(h, if below { uv + offset } else { uv });
+                if below {
+                    lower.push(h);
+                }
+            }
+        }
+        mesh.uvs = Some(uvs);
+        let v0 = mesh
+            .vertices()
+            .keys()
+            .find(|v| mesh.positions[*v] == vec3(1.0, 1.0, 0.0))
+            .unwrap();
+        let h = v0
+            .halfedges(&mesh)
+            .find(|h| h.dst_vert().run(&mesh).map(|v| mesh.positions[v]) == Ok(vec3(1.0, 2.0, 0.0)))
+            .unwrap();
+        let before = mesh.uvs.clone().unwrap();
+        mesh.collapse(h)?;
+
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(&mesh) {
+                if lower.contains(&h) {
+                    assert_eq!(uvs[h], before[h]);
+                } else {
+                    let p = mesh.positions[h.src_vert().run(&mesh)?].truncate();
+                    assert!(uvs[h].distance(p) < 1e-5);
+                }
+            }
+        }
+        Ok(())
+    }
+
     // #[test]
     // fn collapse() -> SMeshResult<()> {
     //     bail!(DefaultError)
     // }
+
+    #[test]
+    fn flip_faces() -> SMeshResult<()> {
+        let (mut mesh, h) = attributed_quad()?;
+        mesh.build_edge_index();
+        let faces = mesh.faces().keys().collect_vec();
+        mesh.flip_faces(faces.clone())?;
+        crate::test_utils::check_connectivity(&mesh)?;
+        for f in &faces {
+            assert!(f.normal(&mesh)?.distance(Vec3::NEG_Z) < 1e-5);
+        }
+        assert_uvs_match_positions(&mesh)?;
+        assert_eq!(mesh.edge_crease(h), 0.5);
+        let (v0, v2) = (h.src_vert().run(&mesh)?, h.dst_vert().run(&mesh)?);
+        assert_eq!(v0.halfedge_to(v2).run(&mesh)?, h);
+
+        // a single face can't be flipped without its neighbour
+        assert_eq!(mesh.flip_faces(faces[0]), Err(SMeshError::TopologyError));
+        Ok(())
+    }
 }
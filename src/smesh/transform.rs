@@ -0,0 +1,909 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::{Mat3, Mat4, Quat, Vec3};
+use itertools::Itertools;
+
+use crate::bail;
+use crate::prelude::*;
+
+/// Point that [`SMesh::scale`] and [`SMesh::rotate`] transform around
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pivot {
+    /// The world origin
+    Origin,
+    /// The average position of the selected vertices
+    #[default]
+    SelectionCenter,
+    Point(Vec3),
+}
+
+/// Proportional editing: vertices within `radius` of the selection are
+/// transformed too, weighted by the `curve`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Falloff {
+    pub radius: f32,
+    pub curve: FalloffCurve,
+    pub distance: FalloffDistance,
+}
+
+impl Falloff {
+    /// Smooth falloff over the euclidean distance
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            curve: FalloffCurve::default(),
+            distance: FalloffDistance::default(),
+        }
+    }
+
+    pub fn with_curve(mut self, curve: FalloffCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn with_distance(mut self, distance: FalloffDistance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Weight of a vertex at `distance` from the selection
+    pub fn weight(&self, distance: f32) -> f32 {
+        if distance <= 0.0 {
+            return 1.0;
+        }
+        if distance >= self.radius {
+            return 0.0;
+        }
+        let t = distance / self.radius;
+        match self.curve {
+            FalloffCurve::Smooth => {
+                let s = 1.0 - t;
+                s * s * (3.0 - 2.0 * s)
+            }
+            FalloffCurve::Linear => 1.0 - t,
+            FalloffCurve::Sphere => (1.0 - t * t).sqrt(),
+        }
+    }
+}
+
+/// Shape of the weight curve of a [`Falloff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FalloffCurve {
+    /// Smoothstep, with flat tangents at the selection and at the radius
+    #[default]
+    Smooth,
+    Linear,
+    /// Quarter circle
+    Sphere,
+}
+
+/// How the distance of a vertex to the selection is measured for a [`Falloff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FalloffDistance {
+    /// Straight line distance
+    #[default]
+    Euclidean,
+    /// Shortest path along the edges of the mesh,
+    /// so only connected vertices are affected
+    Geodesic,
+}
+
+impl SMesh {
+    /// Move the selected vertices by `offset`
+    pub fn translate<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        offset: Vec3,
+    ) -> SMeshResult<()> {
+        self.translate_impl(selection, offset, None)
+    }
+
+    /// Move the selected vertices by `offset`,
+    /// and nearby vertices partially according to the `falloff`
+    pub fn translate_with_falloff<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        offset: Vec3,
+        falloff: Falloff,
+    ) -> SMeshResult<()> {
+        self.translate_impl(selection, offset, Some(falloff))
+    }
+
+    /// Scale the selected vertices by `factor` around the `pivot`
+    pub fn scale<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        factor: Vec3,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        self.scale_impl(selection, factor, pivot, None)
+    }
+
+    /// Scale the selected vertices by `factor` around the `pivot`,
+    /// and nearby vertices partially according to the `falloff`
+    pub fn scale_with_falloff<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        factor: Vec3,
+        pivot: Pivot,
+        falloff: Falloff,
+    ) -> SMeshResult<()> {
+        self.scale_impl(selection, factor, pivot, Some(falloff))
+    }
+
+    /// Rotate the selected vertices by `rotation` around the `pivot`
+    pub fn rotate<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        rotation: Quat,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        self.rotate_impl(selection, rotation, pivot, None)
+    }
+
+    /// Rotate the selected vertices by `rotation` around the `pivot`,
+    /// and nearby vertices partially according to the `falloff`
+    pub fn rotate_with_falloff<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        rotation: Quat,
+        pivot: Pivot,
+        falloff: Falloff,
+    ) -> SMeshResult<()> {
+        self.rotate_impl(selection, rotation, pivot, Some(falloff))
+    }
+
+    /// Move the selected vertices along `direction` by `factor` times their distance from
+    /// the `pivot` along `axis`, shearing the plane spanned by the two.
+    /// Only the part of the direction perpendicular to the axis is used.
+    /// Fails if the axis is zero or the direction is parallel to it.
+    pub fn shear<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        direction: Vec3,
+        axis: Vec3,
+        factor: f32,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        let Some(axis) = axis.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let Some(direction) = direction.reject_from_normalized(axis).try_normalize() else {
+            bail!(DefaultError);
+        };
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, None)?;
+        self.transform_positions(&weights, |p| {
+            p + direction * (factor * (p - center).dot(axis))
+        });
+        self.record(|| Operation::Shear {
+            vertices,
+            direction,
+            axis,
+            factor,
+            pivot,
+        });
+        Ok(())
+    }
+
+    /// Apply an affine transformation to the selected vertices.
+    /// Transformations that mirror the mesh also reverse the orientation of the faces
+    /// between the selected vertices, so they keep facing outwards, see [`SMesh::flip_faces`].
+    /// Fails with a topology error if a mirrored selection covers only a part of
+    /// a connected surface.
+    pub fn transform<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        matrix: Mat4,
+    ) -> SMeshResult<()> {
+        let vertices = self.selected_vertices(selection)?;
+        if matrix.determinant() < 0.0 {
+            let selected = vertices.iter().copied().collect::<HashSet<_>>();
+            let faces = vertices
+                .iter()
+                .filter(|v| !v.is_isolated(self))
+                .flat_map(|v| v.faces(self))
+                .unique()
+                .collect_vec();
+            if faces
+                .iter()
+                .any(|f| f.vertices(self).any(|v| !selected.contains(&v)))
+            {
+                bail!(TopologyError);
+            }
+            self.flip_faces(faces)?;
+        }
+        let weights = self.falloff_weights(&vertices, None)?;
+        self.transform_positions(&weights, |p| matrix.transform_point3(p));
+        self.record(|| Operation::Transform { vertices, matrix });
+        Ok(())
+    }
+
+    /// Replace the position of every selected vertex by the result of `f`, which gets
+    /// the vertex and its current position.
+    /// Unlike the other transformations this is not recorded in the journal.
+    pub fn update_positions<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        mut f: impl FnMut(VertexId, Vec3) -> Vec3,
+    ) -> SMeshResult<()> {
+        for v in self.selected_vertices(selection)? {
+            self.positions[v] = f(v, self.positions[v]);
+            self.dirty_vertices.insert(v);
+        }
+        Ok(())
+    }
+
+    /// Mirror the selected vertices across the plane, reversing the orientation of
+    /// the faces between them like [`SMesh::transform`].
+    /// Fails if the normal of the plane is zero, or if the selection covers only a part
+    /// of a connected surface.
+    pub fn reflect<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        plane: Plane,
+    ) -> SMeshResult<()> {
+        let Some(n) = plane.normal.try_normalize() else {
+            bail!(DefaultError);
+        };
+        // Householder reflection around the plane origin
+        let matrix = Mat4::from_translation(plane.origin)
+            * Mat4::from_mat3(Mat3::IDENTITY - 2.0 * Mat3::from_cols(n * n.x, n * n.y, n * n.z))
+            * Mat4::from_translation(-plane.origin);
+        self.transform(selection, matrix)
+    }
+
+    /// Rotate the selected vertices around the line through the `pivot` along `axis`,
+    /// by an angle growing with their distance from the pivot along the axis.
+    /// Fails if the axis is zero.
+    pub fn twist<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        axis: Vec3,
+        angle_per_unit: f32,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        let Some(axis) = axis.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, None)?;
+        self.transform_positions(&weights, |p| {
+            let angle = (p - center).dot(axis) * angle_per_unit;
+            center + Quat::from_axis_angle(axis, angle) * (p - center)
+        });
+        self.record(|| Operation::Twist {
+            vertices,
+            axis,
+            angle_per_unit,
+            pivot,
+        });
+        Ok(())
+    }
+
+    /// Bend the selected vertices around a circle, so the line through the `pivot` along
+    /// `axis` curves towards `direction` by `angle_per_unit` per unit of length.
+    /// Lengths along the axis are kept, vertices on the pivot plane do not move.
+    /// Fails if the axis is zero or the direction is parallel to it.
+    pub fn bend<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        axis: Vec3,
+        direction: Vec3,
+        angle_per_unit: f32,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        let Some(axis) = axis.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let Some(direction) = direction.reject_from_normalized(axis).try_normalize() else {
+            bail!(DefaultError);
+        };
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, None)?;
+        self.transform_positions(&weights, |p| {
+            if angle_per_unit == 0.0 {
+                return p;
+            }
+            let d = p - center;
+            let (height, x) = (d.dot(axis), d.dot(direction));
+            let rest = d - axis * height - direction * x;
+            // distance to the center of the bend circle
+            let radius = 1.0 / angle_per_unit;
+            let r = radius - x;
+            let angle = height * angle_per_unit;
+            center + rest + direction * (radius - r * angle.cos()) + axis * (r * angle.sin())
+        });
+        self.record(|| Operation::Bend {
+            vertices,
+            axis,
+            direction,
+            angle_per_unit,
+            pivot,
+        });
+        Ok(())
+    }
+
+    /// Scale the selected vertices towards the line through the `pivot` along `axis`,
+    /// by `1 + factor_per_unit * h` at the distance `h` from the pivot along the axis.
+    /// The scale does not go below zero.
+    /// Fails if the axis is zero.
+    pub fn taper<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        axis: Vec3,
+        factor_per_unit: f32,
+        pivot: Pivot,
+    ) -> SMeshResult<()> {
+        let Some(axis) = axis.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, None)?;
+        self.transform_positions(&weights, |p| {
+            let d = p - center;
+            let along = axis * d.dot(axis);
+            let factor = (1.0 + d.dot(axis) * factor_per_unit).max(0.0);
+            center + along + (d - along) * factor
+        });
+        self.record(|| Operation::Taper {
+            vertices,
+            axis,
+            factor_per_unit,
+            pivot,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn translate_impl<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        offset: Vec3,
+        falloff: Option<Falloff>,
+    ) -> SMeshResult<()> {
+        let vertices = self.selected_vertices(selection)?;
+        let weights = self.falloff_weights(&vertices, falloff.as_ref())?;
+        self.transform_positions(&weights, |p| p + offset);
+        self.record(|| Operation::Translate {
+            vertices,
+            offset,
+            falloff,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn scale_impl<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        factor: Vec3,
+        pivot: Pivot,
+        falloff: Option<Falloff>,
+    ) -> SMeshResult<()> {
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, falloff.as_ref())?;
+        self.transform_positions(&weights, |p| center + (p - center) * factor);
+        self.record(|| Operation::Scale {
+            vertices,
+            factor,
+            pivot,
+            falloff,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn rotate_impl<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        rotation: Quat,
+        pivot: Pivot,
+        falloff: Option<Falloff>,
+    ) -> SMeshResult<()> {
+        let vertices = self.selected_vertices(selection)?;
+        let center = self.pivot_point(&vertices, pivot);
+        let weights = self.falloff_weights(&vertices, falloff.as_ref())?;
+        self.transform_positions(&weights, |p| center + rotation * (p - center));
+        self.record(|| Operation::Rotate {
+            vertices,
+            rotation,
+            pivot,
+            falloff,
+        });
+        Ok(())
+    }
+
+    /// Move the whole mesh so the center of its bounding box is at the origin
+    pub fn center_to_origin(&mut self) -> SMeshResult<()> {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+        let vertices = self.vertices().keys().collect_vec();
+        self.translate(vertices, -(min + max) * 0.5)
+    }
+
+    /// Center the whole mesh at the origin and scale it uniformly, so its longest side
+    /// has a length of one. Fails if the mesh is empty or all vertices are at one point.
+    pub fn fit_to_unit_cube(&mut self) -> SMeshResult<()> {
+        let Some((min, max)) = self.bounds() else {
+            bail!(DefaultError);
+        };
+        let size = (max - min).max_element();
+        if size <= 0.0 {
+            bail!(DefaultError);
+        }
+        self.center_to_origin()?;
+        let vertices = self.vertices().keys().collect_vec();
+        self.scale(vertices, Vec3::splat(1.0 / size), Pivot::Origin)
+    }
+
+    /// Rotate the whole mesh around the center of `reference_face`,
+    /// so the normal of the face points along `axis`.
+    /// Fails if the axis is zero or the face has no normal.
+    pub fn align_to_axis(&mut self, reference_face: FaceId, axis: Vec3) -> SMeshResult<()> {
+        let Some(axis) = axis.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let Some(normal) = reference_face.normal(self)?.try_normalize() else {
+            bail!(DefaultError);
+        };
+        let corners = reference_face.vertices(self).collect_vec();
+        let center = self.pivot_point(&corners, Pivot::SelectionCenter);
+        let vertices = self.vertices().keys().collect_vec();
+        self.rotate(
+            vertices,
+            Quat::from_rotation_arc(normal, axis),
+            Pivot::Point(center),
+        )
+    }
+
+    /// Smallest and largest coordinates of all vertices, `None` for an empty mesh
+    fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        if self.vertices().is_empty() {
+            return None;
+        }
+        Some(
+            self.vertices()
+                .keys()
+                .map(|v| self.positions[v])
+                .fold((Vec3::MAX, Vec3::MIN), |(min, max), p| {
+                    (min.min(p), max.max(p))
+                }),
+        )
+    }
+
+    fn selected_vertices<S: Into<MeshSelection>>(
+        &self,
+        selection: S,
+    ) -> SMeshResult<Vec<VertexId>> {
+        let vertices = selection.into().resolve_to_vertices(self)?;
+        for v in &vertices {
+            if !self.vertices().contains_key(*v) {
+                return Err(SMeshError::VertexNotFound(*v));
+            }
+        }
+        Ok(vertices.into_iter().sorted().collect_vec())
+    }
+
+    fn pivot_point(&self, vertices: &[VertexId], pivot: Pivot) -> Vec3 {
+        match pivot {
+            Pivot::Origin => Vec3::ZERO,
+            Pivot::Point(p) => p,
+            Pivot::SelectionCenter => {
+                if vertices.is_empty() {
+                    return Vec3::ZERO;
+                }
+                vertices.iter().map(|v| self.positions[*v]).sum::<Vec3>() / vertices.len() as f32
+            }
+        }
+    }
+
+    /// The selected vertices with weight 1, followed by the vertices
+    /// affected by the falloff in a stable order
+    fn falloff_weights(
+        &self,
+        vertices: &[VertexId],
+        falloff: Option<&Falloff>,
+    ) -> SMeshResult<Vec<(VertexId, f32)>> {
+        let mut weights = vertices.iter().map(|v| (*v, 1.0)).collect_vec();
+        let Some(falloff) = falloff else {
+            return Ok(weights);
+        };
+        if vertices.is_empty() || falloff.radius <= 0.0 {
+            return Ok(weights);
+        }
+        let distances = match falloff.distance {
+            FalloffDistance::Euclidean => self.euclidean_distances(vertices, falloff.radius),
+            FalloffDistance::Geodesic => {
+                let (distances, _) =
+                    self.dijkstra(vertices.iter().copied(), None, Some(falloff.radius))?;
+                distances.into_iter().collect()
+            }
+        };
+        weights.extend(
+            distances
+                .into_iter()
+                .filter(|(_, d)| *d > 0.0)
+                .map(|(v, d)| (v, falloff.weight(d)))
+                .filter(|(_, w)| *w > 0.0)
+                .sorted_by_key(|(v, _)| *v),
+        );
+        Ok(weights)
+    }
+
+    /// Distance to the closest selected vertex for every vertex closer than `radius`
+    fn euclidean_distances(&self, vertices: &[VertexId], radius: f32) -> HashMap<VertexId, f32> {
+        let selected = vertices.iter().map(|v| self.positions[*v]).collect_vec();
+        self.vertices()
+            .keys()
+            .filter_map(|v| {
+                let p = self.positions[v];
+                let d = selected
+                    .iter()
+                    .map(|s| s.distance(p))
+                    .fold(f32::INFINITY, f32::min);
+                (d < radius).then_some((v, d))
+            })
+            .collect()
+    }
+
+    /// Move the vertices towards the result of `f` by their weight.
+    /// With the `rayon` feature the new positions are computed in parallel.
+    fn transform_positions(
+        &mut self,
+        weights: &[(VertexId, f32)],
+        f: impl Fn(Vec3) -> Vec3 + Sync,
+    ) {
+        let apply = |(v, w): &(VertexId, f32)| {
+            let p = self.positions[*v];
+            let target = f(p);
+            (
+                *v,
+                if *w >= 1.0 {
+                    target
+                } else {
+                    p.lerp(target, *w)
+                },
+            )
+        };
+        #[cfg(feature = "rayon")]
+        let updated = {
+            use rayon::prelude::*;
+            weights.par_iter().map(apply).collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let updated = weights.iter().map(apply).collect_vec();
+        for (v, p) in updated {
+            self.positions[v] = p;
+            self.dirty_vertices.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{check_connectivity, cube, triangulated_grid};
+    use glam::vec3;
+
+    #[test]
+    fn translate() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.translate(vertices.clone(), vec3(1.0, 2.0, 3.0))?;
+        for v in vertices {
+            assert_eq!(mesh.positions[v], original[v] + vec3(1.0, 2.0, 3.0));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scale() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ONE, 1.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.scale(vertices.clone(), Vec3::splat(2.0), Pivot::SelectionCenter)?;
+        for v in &vertices {
+            let expected = Vec3::ONE + (original[*v] - Vec3::ONE) * 2.0;
+            assert!(mesh.positions[*v].distance(expected) < 1e-5);
+        }
+        mesh.scale(vertices.clone(), vec3(0.5, 1.0, 1.0), Pivot::Origin)?;
+        let v = vertices[0];
+        assert!((mesh.positions[v].x - (original[v].x * 2.0 - 1.0) * 0.5).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn rotate() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        mesh.rotate(
+            vertices.clone(),
+            rotation,
+            Pivot::Point(vec3(0.5, 0.5, 0.0)),
+        )?;
+        for v in vertices {
+            let p = original[v] - vec3(0.5, 0.5, 0.0);
+            let expected = vec3(0.5 - p.y, 0.5 + p.x, p.z);
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn shear() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 2.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.shear(vertices.clone(), Vec3::X, Vec3::Y, 0.5, Pivot::Origin)?;
+        for v in &vertices {
+            let p = original[*v];
+            assert!(mesh.positions[*v].distance(p + Vec3::X * p.y * 0.5) < 1e-5);
+        }
+        // shearing keeps the volume
+        assert!((mesh.volume()? - 8.0).abs() < 1e-4);
+        assert!(mesh
+            .shear(vertices, Vec3::Y, Vec3::Y, 1.0, Pivot::Origin)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn transform_mirrored() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ONE, 1.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let matrix = Mat4::from_scale(vec3(-1.0, 2.0, 1.0));
+        mesh.transform(vertices.clone(), matrix)?;
+        check_connectivity(&mesh)?;
+        for v in &vertices {
+            assert_eq!(mesh.positions[*v], matrix.transform_point3(original[*v]));
+        }
+        // the faces still point outwards
+        assert!((mesh.volume()? - 2.0).abs() < 1e-4);
+        for f in mesh.faces().keys() {
+            let center = f.vertices(&mesh).map(|v| mesh.positions[v]).sum::<Vec3>() / 4.0;
+            assert!(f.normal(&mesh)?.dot(center - vec3(-1.0, 2.0, 1.0)) > 0.0);
+        }
+
+        // mirroring part of the cube would turn it inside out on one side
+        assert_eq!(
+            mesh.transform(vertices[0], matrix),
+            Err(SMeshError::TopologyError)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_positions() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(4)?;
+        let original = mesh.positions.clone();
+        let vertices = mesh.vertices().keys().collect_vec();
+        let mut visited = 0;
+        mesh.update_positions(vertices[..5].to_vec(), |_, p| {
+            visited += 1;
+            p + Vec3::Z * p.x
+        })?;
+        assert_eq!(visited, 5);
+        for (i, v) in vertices.iter().enumerate() {
+            let expected = original[*v] + Vec3::Z * original[*v].x * (i < 5) as u8 as f32;
+            assert_eq!(mesh.positions[*v], expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reflect() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ONE, 1.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let plane = Plane::new(vec3(0.0, 2.0, 0.0), vec3(1.0, 1.0, 0.0));
+        mesh.reflect(vertices.clone(), plane)?;
+        check_connectivity(&mesh)?;
+        for v in &vertices {
+            let (a, b) = (original[*v], mesh.positions[*v]);
+            assert!((plane.signed_distance(a) + plane.signed_distance(b)).abs() < 1e-5);
+            assert!((b - a).cross(plane.normal).length() < 1e-5);
+        }
+        assert!((mesh.volume()? - 1.0).abs() < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn normalization() -> SMeshResult<()> {
+        let mut mesh = cube(vec3(3.0, -2.0, 1.0), 4.0)?;
+        mesh.scale(
+            mesh.vertices().keys().collect_vec(),
+            vec3(1.0, 0.5, 0.25),
+            Pivot::SelectionCenter,
+        )?;
+        mesh.fit_to_unit_cube()?;
+        let (min, max) = mesh.bounds().unwrap();
+        assert!(min.distance(vec3(-0.5, -0.25, -0.125)) < 1e-5);
+        assert!(max.distance(vec3(0.5, 0.25, 0.125)) < 1e-5);
+
+        let face = mesh
+            .faces()
+            .keys()
+            .find(|f| f.normal(&mesh).unwrap().dot(Vec3::X) > 0.9)
+            .unwrap();
+        mesh.align_to_axis(face, Vec3::Y)?;
+        assert!(face.normal(&mesh)?.distance(Vec3::Y) < 1e-5);
+        let (min, max) = mesh.bounds().unwrap();
+        assert!((max.y - min.y - 1.0).abs() < 1e-5);
+        // rotated around the center of the face, which stays in place
+        assert!(max.y.abs() < 1e-5);
+
+        assert!(SMesh::new().fit_to_unit_cube().is_err());
+        assert!(mesh.align_to_axis(face, Vec3::ZERO).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn normalization_ignores_deleted_vertices() -> SMeshResult<()> {
+        let mut mesh = SMesh::new();
+        let v0 = mesh.add_vertex(vec3(1.0, 1.0, 1.0));
+        let v1 = mesh.add_vertex(vec3(3.0, 1.0, 1.0));
+        let v2 = mesh.add_vertex(vec3(1.0, 2.0, 1.0));
+        let far = mesh.add_vertex(vec3(100.0, 100.0, 100.0));
+        mesh.add_triangle(v0, v1, v2)?;
+        mesh.delete_vertex(far)?;
+
+        mesh.center_to_origin()?;
+        assert!(mesh.positions[v0].distance(vec3(-1.0, -0.5, 0.0)) < 1e-5);
+        assert!(mesh.positions[v1].distance(vec3(1.0, -0.5, 0.0)) < 1e-5);
+
+        mesh.fit_to_unit_cube()?;
+        assert!(mesh.positions[v0].distance(vec3(-0.5, -0.25, 0.0)) < 1e-5);
+        assert!(mesh.positions[v2].distance(vec3(-0.5, 0.25, 0.0)) < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn twist() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(4)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let angle_per_unit = std::f32::consts::FRAC_PI_4;
+        mesh.twist(vertices.clone(), Vec3::Y, angle_per_unit, Pivot::Origin)?;
+        for v in vertices {
+            let p = original[v];
+            let angle = p.y * angle_per_unit;
+            let expected = vec3(p.x * angle.cos(), p.y, -p.x * angle.sin());
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+        assert!(mesh
+            .twist(
+                mesh.vertices().keys().collect_vec(),
+                Vec3::ZERO,
+                1.0,
+                Pivot::Origin
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bend() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(4)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        let angle_per_unit = std::f32::consts::PI / 8.0;
+        mesh.bend(
+            vertices.clone(),
+            Vec3::Y,
+            Vec3::X,
+            angle_per_unit,
+            Pivot::Origin,
+        )?;
+        // every column becomes an arc around the center of the bend
+        let radius = 1.0 / angle_per_unit;
+        for v in vertices {
+            let p = original[v];
+            let q = mesh.positions[v];
+            assert!((q.distance(vec3(radius, 0.0, 0.0)) - (radius - p.x).abs()).abs() < 1e-4);
+            assert!(q.z.abs() < 1e-5);
+            if p.y == 0.0 {
+                assert!(q.distance(p) < 1e-5);
+            }
+            if p.y == 4.0 {
+                assert!((q.y - (radius - p.x)).abs() < 1e-4);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn taper() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 2.0)?;
+        let vertices = mesh.vertices().keys().collect_vec();
+        let original = mesh.positions.clone();
+        mesh.taper(vertices.clone(), Vec3::Y, -0.5, Pivot::Point(Vec3::NEG_Y))?;
+        for v in vertices {
+            let p = original[v];
+            let expected = if p.y > 0.0 { vec3(0.0, 1.0, 0.0) } else { p };
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn falloff_curves() {
+        let falloff = Falloff::new(2.0);
+        assert_eq!(falloff.weight(0.0), 1.0);
+        assert_eq!(falloff.weight(1.0), 0.5);
+        assert_eq!(falloff.weight(2.0), 0.0);
+        let linear = falloff.with_curve(FalloffCurve::Linear);
+        assert_eq!(linear.weight(0.5), 0.75);
+        let sphere = falloff.with_curve(FalloffCurve::Sphere);
+        assert!((sphere.weight(1.0) - 0.75f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn translate_with_falloff() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(4)?;
+        let original = mesh.positions.clone();
+        let vertices = mesh.vertices().keys().collect_vec();
+        let center = *vertices
+            .iter()
+            .min_by(|a, b| {
+                let d = |v: &VertexId| original[*v].distance(vec3(2.0, 0.0, -2.0));
+                d(a).total_cmp(&d(b))
+            })
+            .unwrap();
+        let offset = vec3(0.0, 1.0, 0.0);
+        let falloff = Falloff::new(1.5).with_curve(FalloffCurve::Linear);
+        mesh.translate_with_falloff(center, offset, falloff)?;
+        for v in vertices {
+            let d = original[v].distance(original[center]);
+            let expected = original[v] + offset * falloff.weight(d);
+            assert!(mesh.positions[v].distance(expected) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn geodesic_falloff() -> SMeshResult<()> {
+        // two separate cubes, the second one within euclidean range
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let first = mesh.vertices().keys().collect_vec();
+        let other = cube(vec3(1.5, 0.0, 0.0), 1.0)?;
+        let ids = other
+            .vertices()
+            .keys()
+            .map(|v| mesh.add_vertex(other.positions[v]))
+            .collect_vec();
+        let seed = *first
+            .iter()
+            .max_by(|a, b| mesh.positions[**a].x.total_cmp(&mesh.positions[**b].x))
+            .unwrap();
+        let falloff = Falloff::new(1.5).with_distance(FalloffDistance::Geodesic);
+        let before = mesh.positions.clone();
+        mesh.translate_with_falloff(seed, Vec3::Y, falloff)?;
+        assert!(ids.iter().all(|v| mesh.positions[*v] == before[*v]));
+        assert!(first
+            .iter()
+            .filter(|v| **v != seed)
+            .any(|v| mesh.positions[*v] != before[*v]));
+
+        // an isolated vertex has no neighbors to fall off to
+        let before = mesh.positions.clone();
+        mesh.translate_with_falloff(ids[0], Vec3::Y, falloff)?;
+        let moved = mesh
+            .vertices()
+            .keys()
+            .filter(|v| mesh.positions[*v] != before[*v]);
+        assert_eq!(moved.collect_vec(), vec![ids[0]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,1313 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::{DMat2, DVec2, DVec3, Vec2, Vec3};
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::bail;
+use crate::prelude::*;
+use crate::smesh::attribute::CustomAttributeMapOps;
+
+/// Name of the face attribute map [`SMesh::unwrap_with_seams`] writes the chart index of every face to
+pub const UV_CHART_ATTRIBUTE: &str = "uv_chart";
+
+/// Relative residual at which the least squares solver stops
+const SOLVER_TOLERANCE: f64 = 1e-10;
+
+/// UV islands
+impl SMesh {
+    /// All uv islands of the mesh, the groups of faces connected by edges whose uvs
+    /// are continuous across the edge, see [`SMesh::select_uv_island`].
+    /// The islands are ordered by their first face in face order.
+    /// Fails if the mesh has no uvs.
+    pub fn uv_islands(&self) -> SMeshResult<Vec<MeshSelection>> {
+        if self.uvs.is_none() {
+            bail!(UnsupportedOperation);
+        }
+        let mut assigned = HashSet::new();
+        let mut islands = vec![];
+        for f in self.faces().keys() {
+            if assigned.contains(&f) {
+                continue;
+            }
+            let island = self.select_uv_island(f)?;
+            assigned.extend(island.faces());
+            islands.push(island);
+        }
+        Ok(islands)
+    }
+}
+
+/// Uv inspection
+impl SMesh {
+    /// A flat mesh of the uv layout, with one face per face of this mesh whose vertex
+    /// positions are the uvs of its corners on the xy plane. Corners of a vertex with the
+    /// same uv share a vertex, so every uv island is a connected piece. The faces keep
+    /// their uvs, so the layout can be rendered with the texture of the mesh.
+    /// Fails if the mesh has no uvs or a face corner is missing its uv.
+    pub fn uv_layout_mesh(&self) -> SMeshResult<SMesh> {
+        let Some(uvs) = self.uvs.as_ref() else {
+            bail!(UnsupportedOperation);
+        };
+        let mut layout = SMesh::new();
+        let mut layout_uvs = SecondaryMap::new();
+        let mut vertices = HashMap::new();
+        for f in self.faces().keys() {
+            let mut face = vec![];
+            for h in f.halfedges(self) {
+                let Some(uv) = uvs.get(h) else {
+                    bail!(UnsupportedOperation);
+                };
+                let key = (h.src_vert().run(self)?, uv.x.to_bits(), uv.y.to_bits());
+                let v = *vertices
+                    .entry(key)
+                    .or_insert_with(|| layout.add_vertex(uv.extend(0.0)));
+                face.push(v);
+            }
+            let layout_face = layout.add_face(face)?;
+            for h in layout_face.halfedges(&layout) {
+                let p = layout.positions[h.src_vert().run(&layout)?];
+                layout_uvs.insert(h, p.truncate());
+            }
+        }
+        layout.uvs = Some(layout_uvs);
+        Ok(layout)
+    }
+}
+
+/// Cleanup
+impl SMesh {
+    /// Merge the uvs of face corners at the same vertex that are closer than `epsilon`
+    /// into their average, removing hairline seams left by independent per face
+    /// projections. Returns the number of corners whose uv changed.
+    /// Fails if the mesh has no uvs.
+    pub fn weld_uvs(&mut self, epsilon: f32) -> SMeshResult<usize> {
+        let Some(mut uvs) = self.uvs.take() else {
+            bail!(UnsupportedOperation);
+        };
+        let mut changed = 0;
+        for v in self.vertices().keys() {
+            if v.is_isolated(self) {
+                continue;
+            }
+            let mut corners = v
+                .halfedges(self)
+                .filter(|h| uvs.contains_key(*h))
+                .collect_vec();
+            while let Some(first) = corners.pop() {
+                let (close, rest): (Vec<_>, Vec<_>) = corners
+                    .into_iter()
+                    .partition(|h| uvs[*h].distance(uvs[first]) <= epsilon);
+                corners = rest;
+                if close.is_empty() {
+                    continue;
+                }
+                let average = close.iter().fold(uvs[first], |sum, h| sum + uvs[*h])
+                    / (close.len() + 1) as f32;
+                for h in close.into_iter().chain([first]) {
+                    if uvs[h] != average {
+                        uvs[h] = average;
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        self.uvs = Some(uvs);
+        Ok(changed)
+    }
+}
+
+/// Point that [`SMesh::scale_uvs`] and [`SMesh::rotate_uvs`] transform around
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UvPivot {
+    /// The center of the uv square, (0.5, 0.5)
+    #[default]
+    Center,
+    /// The center of the bounds of the selected uvs
+    SelectionCenter,
+    Point(Vec2),
+}
+
+/// Uv transforms
+impl SMesh {
+    /// Scale the uvs of the selected faces by `scale` around the `pivot`.
+    /// Fails if the mesh has no uvs.
+    pub fn scale_uvs<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        scale: Vec2,
+        pivot: UvPivot,
+    ) -> SMeshResult<()> {
+        let corners = self.uv_corners(selection)?;
+        let pivot = self.uv_pivot(&corners, pivot);
+        self.map_uvs(&corners, |uv| pivot + (uv - pivot) * scale);
+        Ok(())
+    }
+
+    /// Rotate the uvs of the selected faces counter clockwise by `angle` (in radians)
+    /// around the `pivot`. Fails if the mesh has no uvs.
+    pub fn rotate_uvs<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        angle: f32,
+        pivot: UvPivot,
+    ) -> SMeshResult<()> {
+        let corners = self.uv_corners(selection)?;
+        let pivot = self.uv_pivot(&corners, pivot);
+        let rotation = Vec2::from_angle(angle);
+        self.map_uvs(&corners, |uv| pivot + rotation.rotate(uv - pivot));
+        Ok(())
+    }
+
+    /// Transform the uv island containing `face`, see [`SMesh::select_uv_island`].
+    /// The island is scaled and rotated counter clockwise by `rotation` (in radians)
+    /// around the center of its bounds and then moved by `offset`, other islands keep
+    /// their uvs even if they share vertices. Fails if the mesh has no uvs.
+    pub fn transform_uv_island(
+        &mut self,
+        face: FaceId,
+        scale: Vec2,
+        rotation: f32,
+        offset: Vec2,
+    ) -> SMeshResult<()> {
+        let island = self.select_uv_island(face)?;
+        let corners = self.uv_corners(island)?;
+        let pivot = self.uv_pivot(&corners, UvPivot::SelectionCenter);
+        let rotation = Vec2::from_angle(rotation);
+        self.map_uvs(&corners, |uv| {
+            pivot + offset + rotation.rotate((uv - pivot) * scale)
+        });
+        Ok(())
+    }
+
+    /// Scale every uv island around its center so that one unit of surface covers
+    /// `texels_per_unit` texels of a square texture with `texture_size` texels per side,
+    /// giving all parts of the mesh the same texture resolution. Islands without surface
+    /// or uv area are left untouched. Fails if the mesh has no uvs.
+    pub fn equalize_texel_density(
+        &mut self,
+        texels_per_unit: f32,
+        texture_size: u32,
+    ) -> SMeshResult<()> {
+        let density = texels_per_unit / texture_size as f32;
+        for island in self.uv_islands()? {
+            let surface_area = self.surface_area(island.clone())?;
+            let corners = self.uv_corners(island.clone())?;
+            let mut uv_area = 0.0;
+            if let Some(uvs) = self.uvs.as_ref() {
+                for f in island.faces() {
+                    let signed_area: f32 = f
+                        .halfedges(self)
+                        .map(|h| uvs[h])
+                        .collect_vec()
+                        .into_iter()
+                        .circular_tuple_windows()
+                        .map(|(a, b)| a.perp_dot(b))
+                        .sum();
+                    uv_area += 0.5 * signed_area.abs();
+                }
+            }
+            if surface_area <= f32::EPSILON || uv_area <= f32::EPSILON {
+                continue;
+            }
+            let scale = (surface_area * density * density / uv_area).sqrt();
+            let pivot = self.uv_pivot(&corners, UvPivot::SelectionCenter);
+            self.map_uvs(&corners, |uv| pivot + (uv - pivot) * scale);
+        }
+        Ok(())
+    }
+
+    fn uv_pivot(&self, corners: &[HalfedgeId], pivot: UvPivot) -> Vec2 {
+        match pivot {
+            UvPivot::Center => Vec2::splat(0.5),
+            UvPivot::SelectionCenter => self
+                .uv_bounds(corners)
+                .map(|(min, max)| (min + max) * 0.5)
+                .unwrap_or(Vec2::ZERO),
+            UvPivot::Point(p) => p,
+        }
+    }
+
+    /// Scale the uvs of the selected faces uniformly and move them so they fit into
+    /// the unit square, touching its lower left corner. Fails if the mesh has no uvs.
+    pub fn normalize_uvs<S: Into<MeshSelection>>(&mut self, selection: S) -> SMeshResult<()> {
+        let corners = self.uv_corners(selection)?;
+        let Some((min, max)) = self.uv_bounds(&corners) else {
+            return Ok(());
+        };
+        let scale = 1.0 / (max - min).max_element().max(f32::EPSILON);
+        self.map_uvs(&corners, |uv| (uv - min) * scale);
+        Ok(())
+    }
+
+    /// Scale and move the uvs of the selected faces so their bounds match `rect`,
+    /// given as `(min, max)`. The axes are scaled independently.
+    /// Fails if the mesh has no uvs.
+    pub fn fit_uvs_to_rect<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        rect: (Vec2, Vec2),
+    ) -> SMeshResult<()> {
+        let corners = self.uv_corners(selection)?;
+        let Some((min, max)) = self.uv_bounds(&corners) else {
+            return Ok(());
+        };
+        let scale = (rect.1 - rect.0) / (max - min).max(Vec2::splat(f32::EPSILON));
+        self.map_uvs(&corners, |uv| rect.0 + (uv - min) * scale);
+        Ok(())
+    }
+
+    /// Face corners of the selected faces that have a uv
+    fn uv_corners<S: Into<MeshSelection>>(&self, selection: S) -> SMeshResult<Vec<HalfedgeId>> {
+        let Some(uvs) = self.uvs.as_ref() else {
+            bail!(UnsupportedOperation);
+        };
+        let faces = selection.into().resolve_to_faces(self)?;
+        Ok(faces
+            .into_iter()
+            .flat_map(|f| f.halfedges(self))
+            .filter(|h| uvs.contains_key(*h))
+            .collect())
+    }
+
+    fn uv_bounds(&self, corners: &[HalfedgeId]) -> Option<(Vec2, Vec2)> {
+        let uvs = self.uvs.as_ref()?;
+        corners.iter().map(|h| uvs[*h]).fold(None, |bounds, uv| {
+            let (min, max) = bounds.unwrap_or((uv, uv));
+            Some((min.min(uv), max.max(uv)))
+        })
+    }
+
+    fn map_uvs(&mut self, corners: &[HalfedgeId], f: impl Fn(Vec2) -> Vec2) {
+        if let Some(uvs) = self.uvs.as_mut() {
+            for h in corners {
+                uvs[*h] = f(uvs[*h]);
+            }
+        }
+    }
+}
+
+/// Projections
+impl SMesh {
+    /// Planar projection of the selected faces, as seen when looking along `direction`
+    /// with `up` pointing up. u goes to the right and v up, in world units.
+    /// Fails if `direction` is zero or parallel to `up`.
+    pub fn planar_project_uvs<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        direction: Vec3,
+        up: Vec3,
+    ) -> SMeshResult<()> {
+        let right = direction.cross(up).normalize_or_zero();
+        if right == Vec3::ZERO {
+            bail!(DefaultError);
+        }
+        let up = right.cross(direction).normalize();
+        let faces = selection.into().resolve_to_faces(self)?;
+        let mut projected = vec![];
+        for f in faces {
+            for h in f.halfedges(self) {
+                let p = self.positions[h.src_vert().run(self)?];
+                projected.push((h, Vec2::new(p.dot(right), p.dot(up))));
+            }
+        }
+        self.uvs
+            .get_or_insert_with(SecondaryMap::new)
+            .extend(projected);
+        Ok(())
+    }
+
+    /// Box (triplanar) projection of the selected faces. Every face is projected onto the
+    /// axis plane its normal points at most, oriented as seen from outside so that textures
+    /// are not mirrored. The uvs are the projected positions multiplied by `scale`.
+    pub fn cube_project_uvs<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        scale: f32,
+    ) -> SMeshResult<()> {
+        let faces = selection.into().resolve_to_faces(self)?;
+        let mut projected = vec![];
+        for f in faces {
+            let n = f.normal(self)?;
+            let a = n.abs();
+            let axis = if a.x >= a.y && a.x >= a.z {
+                0
+            } else if a.y >= a.z {
+                1
+            } else {
+                2
+            };
+            let sign = if n[axis] < 0.0 { -1.0 } else { 1.0 };
+            for h in f.halfedges(self) {
+                let p = self.positions[h.src_vert().run(self)?];
+                let uv = match axis {
+                    0 => Vec2::new(-sign * p.z, p.y),
+                    1 => Vec2::new(p.x, -sign * p.z),
+                    _ => Vec2::new(sign * p.x, p.y),
+                };
+                projected.push((h, uv * scale));
+            }
+        }
+        self.uvs
+            .get_or_insert_with(SecondaryMap::new)
+            .extend(projected);
+        Ok(())
+    }
+}
+
+/// Options for [`pack_atlas`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasOptions {
+    /// Space between charts, relative to the square root of the summed chart area
+    pub margin: f32,
+    /// Keep the existing uv islands as charts and only pack them, instead of
+    /// cutting the meshes along their seams and flattening the charts again
+    pub use_input_uvs: bool,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        Self {
+            margin: 0.02,
+            use_input_uvs: false,
+        }
+    }
+}
+
+/// Face corners of a chart with their unpacked uvs
+type PackChart = Vec<(HalfedgeId, DVec2)>;
+
+/// Unwrap all `meshes` like [`SMesh::unwrap_with_seams`] and pack the charts of all of
+/// them into one shared atlas in the unit square, so a whole scene can use one texture.
+/// Charts keep their relative size and the uvs of different meshes do not overlap.
+/// With [`AtlasOptions::use_input_uvs`] the uv islands are packed as they are, which
+/// fails if a mesh has no uvs.
+pub fn pack_atlas(meshes: &mut [&mut SMesh], options: AtlasOptions) -> SMeshResult<()> {
+    let mut charts = vec![];
+    let mut owners = vec![];
+    for (i, mesh) in meshes.iter_mut().enumerate() {
+        let mesh_charts = if options.use_input_uvs {
+            mesh.input_uv_charts()?
+        } else {
+            mesh.seam_charts()?.0
+        };
+        owners.extend(std::iter::repeat_n(i, mesh_charts.len()));
+        charts.extend(mesh_charts);
+    }
+    let layout = pack_charts(&charts, options.margin as f64);
+    for mesh in meshes.iter_mut() {
+        mesh.uvs = Some(SecondaryMap::new());
+    }
+    for ((chart, (offset, scale)), i) in charts.iter().zip(layout).zip(owners) {
+        meshes[i].write_packed_chart(chart, offset, scale);
+    }
+    Ok(())
+}
+
+/// Seam based unwrapping
+impl SMesh {
+    /// Compute uvs for all faces. The mesh is cut into charts along the edges marked
+    /// with [`SMesh::set_seam`] and along boundaries, every chart is flattened with
+    /// least squares conformal maps and the charts are packed into the unit square.
+    /// Charts should be topological discs, closed charts without seams fold over.
+    /// Returns the faces of every chart, the index of its chart is also stored for
+    /// every face in the `u32` face attribute [`UV_CHART_ATTRIBUTE`].
+    pub fn unwrap_with_seams(&mut self) -> SMeshResult<Vec<MeshSelection>> {
+        let (charts, selections) = self.seam_charts()?;
+        let layout = pack_charts(&charts, AtlasOptions::default().margin as f64);
+        self.uvs = Some(SecondaryMap::new());
+        for (chart, (offset, scale)) in charts.iter().zip(layout) {
+            self.write_packed_chart(chart, offset, scale);
+        }
+        Ok(selections)
+    }
+
+    /// Cut the mesh along seams and flatten every chart, without packing.
+    /// Writes the chart ids, see [`SMesh::unwrap_with_seams`].
+    fn seam_charts(&mut self) -> SMeshResult<(Vec<PackChart>, Vec<MeshSelection>)> {
+        // corners are connected around their vertex across edges that are not seams
+        let mut wedges = UnionFind::default();
+        for h in self.halfedges().keys() {
+            if h.is_boundary(self) {
+                continue;
+            }
+            wedges.add(h);
+            let o = h.opposite().run(self)?;
+            if o.is_boundary(self) || self.is_seam(h) {
+                continue;
+            }
+            wedges.union(h, o.next().run(self)?);
+            wedges.union(h.next().run(self)?, o);
+        }
+
+        let mut charts = vec![];
+        let mut selections = vec![];
+        let mut chart_ids = vec![];
+        let mut assigned = HashSet::new();
+        for seed in self.faces().keys() {
+            if !assigned.insert(seed) {
+                continue;
+            }
+            // faces connected across edges that are not seams
+            let mut faces = vec![seed];
+            let mut i = 0;
+            while i < faces.len() {
+                for h in faces[i].halfedges(self) {
+                    if self.is_seam(h) {
+                        continue;
+                    }
+                    if let Ok(f) = h.opposite().face().run(self) {
+                        if assigned.insert(f) {
+                            faces.push(f);
+                        }
+                    }
+                }
+                i += 1;
+            }
+
+            let mut chart = Chart::default();
+            let mut unknowns = HashMap::new();
+            for f in &faces {
+                chart_ids.push((*f, charts.len() as u32));
+                let mut polygon = vec![];
+                for h in f.halfedges(self) {
+                    let wedge = wedges.find(h);
+                    let v = h.src_vert().run(self)?;
+                    let index = *unknowns.entry(wedge).or_insert_with(|| {
+                        chart.positions.push(self.positions[v]);
+                        chart.positions.len() - 1
+                    });
+                    chart.corners.push((h, index));
+                    polygon.push(index);
+                }
+                chart.add_polygon(&polygon);
+            }
+            let pins = chart.default_pins();
+            let chart_uvs = chart.solve(pins);
+            charts.push(
+                chart
+                    .corners
+                    .iter()
+                    .map(|(h, index)| (*h, chart_uvs[*index]))
+                    .collect(),
+            );
+            selections.push(MeshSelection::from(faces));
+        }
+
+        if let Some(map) = self.add_attribute_map::<FaceId>(UV_CHART_ATTRIBUTE) {
+            for (f, id) in chart_ids {
+                map.insert(f, id);
+            }
+        }
+        Ok((charts, selections))
+    }
+
+    /// The uv islands as charts with their current uvs
+    fn input_uv_charts(&self) -> SMeshResult<Vec<PackChart>> {
+        let Some(uvs) = self.uvs.as_ref() else {
+            bail!(UnsupportedOperation);
+        };
+        let mut charts = vec![];
+        for island in self.uv_islands()? {
+            let corners = self.uv_corners(island)?;
+            charts.push(
+                corners
+                    .into_iter()
+                    .map(|h| (h, uvs[h].as_dvec2()))
+                    .collect(),
+            );
+        }
+        Ok(charts)
+    }
+
+    fn write_packed_chart(&mut self, chart: &PackChart, offset: DVec2, scale: f64) {
+        let uvs = self.uvs.get_or_insert_with(SecondaryMap::new);
+        for (h, uv) in chart {
+            uvs.insert(*h, ((*uv + offset) * scale).as_vec2());
+        }
+    }
+}
+
+/// Parameterization
+impl SMesh {
+    /// Flatten the selected faces with least squares conformal maps, the two `pinned`
+    /// vertices are fixed to the given uvs and determine position, scale and rotation
+    /// of the result. Writes the uvs of all corners of the selected faces, corners of
+    /// the same vertex get the same uv. The faces should form a topological disc.
+    /// Fails if a pinned vertex is not part of the faces or both pins are the same vertex.
+    pub fn lscm_unwrap<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        pinned: [(VertexId, Vec2); 2],
+    ) -> SMeshResult<()> {
+        if pinned[0].0 == pinned[1].0 {
+            bail!(DefaultError);
+        }
+        let (chart, unknowns) = self.vertex_chart(selection)?;
+        let mut pins = [(0, DVec2::ZERO); 2];
+        for ((v, uv), pin) in pinned.into_iter().zip(pins.iter_mut()) {
+            let Some(index) = unknowns.get(&v) else {
+                bail!(VertexNotFound, v);
+            };
+            *pin = (*index, uv.as_dvec2());
+        }
+
+        let chart_uvs = chart.solve(pins);
+        self.write_chart_uvs(&chart, &chart_uvs);
+        Ok(())
+    }
+
+    /// Flatten the selected faces as rigidly as possible, keeping edge lengths and angles
+    /// close to the surface. Starts from a least squares conformal map and runs
+    /// `iterations` local/global iterations. Unlike [`SMesh::lscm_unwrap`] the uvs
+    /// keep the scale of the mesh. Writes the uvs of all corners of the selected faces.
+    pub fn arap_unwrap<S: Into<MeshSelection>>(
+        &mut self,
+        selection: S,
+        iterations: usize,
+    ) -> SMeshResult<()> {
+        let (chart, _) = self.vertex_chart(selection)?;
+        if chart.positions.is_empty() {
+            return Ok(());
+        }
+        let pins = chart.default_pins();
+        let chart_uvs = chart.solve_arap(chart.solve(pins), pins[0].0, iterations);
+        self.write_chart_uvs(&chart, &chart_uvs);
+        Ok(())
+    }
+
+    /// Chart of the selected faces with one point per vertex, together with
+    /// the point index of every vertex
+    fn vertex_chart<S: Into<MeshSelection>>(
+        &self,
+        selection: S,
+    ) -> SMeshResult<(Chart, HashMap<VertexId, usize>)> {
+        let faces = selection
+            .into()
+            .resolve_to_faces(self)?
+            .into_iter()
+            .sorted()
+            .collect_vec();
+
+        let mut chart = Chart::default();
+        let mut unknowns = HashMap::new();
+        for f in faces {
+            let mut polygon = vec![];
+            for h in f.halfedges(self) {
+                let v = h.src_vert().run(self)?;
+                let index = *unknowns.entry(v).or_insert_with(|| {
+                    chart.positions.push(self.positions[v]);
+                    chart.positions.len() - 1
+                });
+                chart.corners.push((h, index));
+                polygon.push(index);
+            }
+            chart.add_polygon(&polygon);
+        }
+        Ok((chart, unknowns))
+    }
+
+    fn write_chart_uvs(&mut self, chart: &Chart, chart_uvs: &[DVec2]) {
+        let uvs = self.uvs.get_or_insert_with(SecondaryMap::new);
+        for (h, index) in &chart.corners {
+            uvs.insert(*h, chart_uvs[*index].as_vec2());
+        }
+    }
+}
+
+#[derive(Default)]
+struct UnionFind {
+    parents: HashMap<HalfedgeId, HalfedgeId>,
+}
+
+impl UnionFind {
+    fn add(&mut self, h: HalfedgeId) {
+        self.parents.entry(h).or_insert(h);
+    }
+
+    fn find(&mut self, h: HalfedgeId) -> HalfedgeId {
+        let parent = *self.parents.entry(h).or_insert(h);
+        if parent == h {
+            return h;
+        }
+        let root = self.find(parent);
+        self.parents.insert(h, root);
+        root
+    }
+
+    fn union(&mut self, a: HalfedgeId, b: HalfedgeId) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents.insert(a, b);
+        }
+    }
+}
+
+/// Triangles of a chart to flatten, indexing into the positions of its points
+#[derive(Default)]
+struct Chart {
+    positions: Vec<Vec3>,
+    /// Face corners of the mesh and their point
+    corners: Vec<(HalfedgeId, usize)>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl Chart {
+    /// Add a polygon, triangulated as a fan
+    fn add_polygon(&mut self, polygon: &[usize]) {
+        for (b, c) in polygon[1..].iter().tuple_windows() {
+            self.triangles.push([polygon[0], *b, *c]);
+        }
+    }
+
+    /// Two points far apart from each other, pinned to their distance along the u axis
+    fn default_pins(&self) -> [(usize, DVec2); 2] {
+        let farthest = |from: usize| {
+            let p = self.positions[from];
+            (0..self.positions.len())
+                .max_by(|a, b| {
+                    p.distance_squared(self.positions[*a])
+                        .total_cmp(&p.distance_squared(self.positions[*b]))
+                })
+                .unwrap_or(from)
+        };
+        let a = farthest(0);
+        let b = farthest(a);
+        let distance = self.positions[a].distance(self.positions[b]).max(1e-6) as f64;
+        [(a, DVec2::ZERO), (b, DVec2::new(distance, 0.0))]
+    }
+
+    /// Least squares conformal map (Lévy et al. 2002) with two pinned points.
+    /// Every triangle adds two equations requiring the gradient of v to be the
+    /// gradient of u rotated by 90 degrees, weighted by the root of its area.
+    fn solve(&self, pins: [(usize, DVec2); 2]) -> Vec<DVec2> {
+        self.solve_least_squares(&pins, |_, gradients, _| {
+            // [gradient x of v + gradient y of u, gradient y of v - gradient x of u]
+            vec![
+                (gradients.map(|g| (g.y, g.x)), 0.0),
+                (gradients.map(|g| (-g.x, g.y)), 0.0),
+            ]
+        })
+    }
+
+    /// As rigid as possible map (Liu et al. 2008) starting from `uvs`, alternating between
+    /// fitting a rotation to the jacobian of every triangle and solving for the uvs that
+    /// match these rotations best. Only the point `pin` is fixed, the scale is kept.
+    fn solve_arap(&self, mut uvs: Vec<DVec2>, pin: usize, iterations: usize) -> Vec<DVec2> {
+        for _ in 0..iterations {
+            let rotations = self
+                .triangles
+                .iter()
+                .map(|triangle| {
+                    let p = triangle.map(|i| self.positions[i].as_dvec3());
+                    let (gradients, _) = triangle_gradients(p)?;
+                    let jacobian = triangle
+                        .iter()
+                        .zip(gradients)
+                        .fold(DMat2::ZERO, |j, (i, g)| {
+                            j + DMat2::from_cols(uvs[*i] * g.x, uvs[*i] * g.y)
+                        });
+                    // closest rotation to the 2x2 jacobian
+                    let angle = (jacobian.x_axis.y - jacobian.y_axis.x)
+                        .atan2(jacobian.x_axis.x + jacobian.y_axis.y);
+                    Some(DMat2::from_angle(angle))
+                })
+                .collect_vec();
+            uvs = self.solve_least_squares(&[(pin, uvs[pin])], |t, gradients, root_area| {
+                let r = rotations[t].unwrap_or(DMat2::IDENTITY) * root_area;
+                vec![
+                    (gradients.map(|g| (g.x, 0.0)), r.x_axis.x),
+                    (gradients.map(|g| (g.y, 0.0)), r.y_axis.x),
+                    (gradients.map(|g| (0.0, g.x)), r.x_axis.y),
+                    (gradients.map(|g| (0.0, g.y)), r.y_axis.y),
+                ]
+            });
+        }
+        uvs
+    }
+
+    /// Solve the equations `Σ cu * u + cv * v = b` over the corners of every triangle in
+    /// the least squares sense, given the area scaled gradients of the triangle basis
+    /// functions and the root of the triangle area. Degenerate triangles are skipped.
+    fn solve_least_squares(
+        &self,
+        pins: &[(usize, DVec2)],
+        equations: impl Fn(usize, [DVec2; 3], f64) -> Vec<([(f64, f64); 3], f64)>,
+    ) -> Vec<DVec2> {
+        let n = self.positions.len();
+        let mut pinned = vec![None; n];
+        for (i, uv) in pins {
+            pinned[*i] = Some(*uv);
+        }
+        let mut columns = vec![usize::MAX; n];
+        let mut free = 0;
+        for i in 0..n {
+            if pinned[i].is_none() {
+                columns[i] = free;
+                free += 1;
+            }
+        }
+
+        let mut rows: Vec<Vec<(usize, f64)>> = vec![];
+        let mut rhs = vec![];
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            let p = triangle.map(|i| self.positions[i].as_dvec3());
+            let Some((gradients, root_area)) = triangle_gradients(p) else {
+                continue;
+            };
+            for (coefficients, mut b) in equations(t, gradients, root_area) {
+                let mut row = vec![];
+                for ((cu, cv), i) in coefficients.iter().zip(triangle) {
+                    match pinned[*i] {
+                        Some(uv) => b -= cu * uv.x + cv * uv.y,
+                        None => {
+                            row.push((2 * columns[*i], *cu));
+                            row.push((2 * columns[*i] + 1, *cv));
+                        }
+                    }
+                }
+                rows.push(row);
+                rhs.push(b);
+            }
+        }
+
+        let x = least_squares(&rows, &rhs, 2 * free);
+        (0..n)
+            .map(|i| {
+                pinned[i].unwrap_or_else(|| DVec2::new(x[2 * columns[i]], x[2 * columns[i] + 1]))
+            })
+            .collect()
+    }
+}
+
+/// Gradients of the three barycentric basis functions of the triangle in a local
+/// frame of its plane, scaled by the root of the triangle area, together with the
+/// root of the area. `None` if degenerate.
+fn triangle_gradients(p: [DVec3; 3]) -> Option<([DVec2; 3], f64)> {
+    let e1 = p[1] - p[0];
+    let e2 = p[2] - p[0];
+    let normal = e1.cross(e2);
+    let double_area = normal.length();
+    if double_area <= f64::EPSILON {
+        return None;
+    }
+    let x = e1.normalize();
+    let y = normal.cross(e1).normalize();
+    let local = [
+        DVec2::ZERO,
+        DVec2::new(e1.dot(x), 0.0),
+        DVec2::new(e2.dot(x), e2.dot(y)),
+    ];
+    let root_area = (0.5 * double_area).sqrt();
+    let gradients = std::array::from_fn(|i| {
+        let edge = local[(i + 2) % 3] - local[(i + 1) % 3];
+        edge.perp() * root_area / double_area
+    });
+    Some((gradients, root_area))
+}
+
+/// Minimize |Ax - b| for the sparse rows of A with conjugate gradients
+/// on the normal equations (CGLS)
+fn least_squares(rows: &[Vec<(usize, f64)>], b: &[f64], n: usize) -> Vec<f64> {
+    let multiply = |x: &[f64]| {
+        rows.iter()
+            .map(|row| row.iter().map(|(j, a)| a * x[*j]).sum::<f64>())
+            .collect_vec()
+    };
+    let multiply_transposed = |y: &[f64]| {
+        let mut result = vec![0.0; n];
+        for (row, y) in rows.iter().zip(y) {
+            for (j, a) in row {
+                result[*j] += a * y;
+            }
+        }
+        result
+    };
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+    let mut x = vec![0.0; n];
+    let mut r = b.to_vec();
+    let mut s = multiply_transposed(&r);
+    let mut p = s.clone();
+    let mut gamma = dot(&s, &s);
+    let initial = gamma;
+    for _ in 0..(10 * n).max(100) {
+        if gamma <= initial * SOLVER_TOLERANCE * SOLVER_TOLERANCE {
+            break;
+        }
+        let q = multiply(&p);
+        let qq = dot(&q, &q);
+        if qq <= f64::MIN_POSITIVE {
+            break;
+        }
+        let alpha = gamma / qq;
+        for (x, p) in x.iter_mut().zip(&p) {
+            *x += alpha * p;
+        }
+        for (r, q) in r.iter_mut().zip(&q) {
+            *r -= alpha * q;
+        }
+        s = multiply_transposed(&r);
+        let next = dot(&s, &s);
+        let beta = next / gamma;
+        gamma = next;
+        for (p, s) in p.iter_mut().zip(&s) {
+            *p = s + beta * *p;
+        }
+    }
+    x
+}
+
+/// Place the charts in rows, sorted by height, and scale them uniformly to fit into
+/// the unit square. `margin` is relative to the square root of the summed chart area.
+/// Returns the offset (applied first) and scale for every chart.
+fn pack_charts(charts: &[PackChart], margin: f64) -> Vec<(DVec2, f64)> {
+    let bounds = charts
+        .iter()
+        .map(|chart| {
+            chart
+                .iter()
+                .fold((DVec2::MAX, DVec2::MIN), |(min, max), (_, uv)| {
+                    (min.min(*uv), max.max(*uv))
+                })
+        })
+        .collect_vec();
+    let sizes = bounds
+        .iter()
+        .map(|(min, max)| (*max - *min).max(DVec2::ZERO))
+        .collect_vec();
+    let area: f64 = sizes.iter().map(|s| s.x * s.y).sum();
+    let margin = area.sqrt() * margin;
+    let row_width = area.sqrt() * 1.2;
+
+    let mut offsets = vec![DVec2::ZERO; bounds.len()];
+    let mut cursor = DVec2::ZERO;
+    let mut row_height: f64 = 0.0;
+    let mut extent = DVec2::ZERO;
+    for i in (0..bounds.len()).sorted_by(|a, b| sizes[*b].y.total_cmp(&sizes[*a].y)) {
+        if cursor.x > 0.0 && cursor.x + sizes[i].x > row_width {
+            cursor = DVec2::new(0.0, cursor.y + row_height + margin);
+            row_height = 0.0;
+        }
+        offsets[i] = cursor - bounds[i].0;
+        extent = extent.max(cursor + sizes[i]);
+        cursor.x += sizes[i].x + margin;
+        row_height = row_height.max(sizes[i].y);
+    }
+    let scale = 1.0 / extent.max_element().max(f64::EPSILON);
+    offsets.into_iter().map(|offset| (offset, scale)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{cube, quad_grid};
+    use glam::{vec2, vec3};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn uv_islands() -> SMeshResult<()> {
+        let mut mesh = quad_grid(3)?;
+        assert_eq!(mesh.uv_islands(), Err(SMeshError::UnsupportedOperation));
+
+        // three charts, column by column
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            let Ok(f) = h.face().run(&mesh) else {
+                continue;
+            };
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            let column = f
+                .vertices(&mesh)
+                .map(|v| mesh.positions[v].x)
+                .fold(f32::MAX, f32::min);
+            uvs.insert(h, vec2(p.x, p.y) + Vec2::X * column * 10.0);
+        }
+        mesh.uvs = Some(uvs);
+        let islands = mesh.uv_islands()?;
+        assert_eq!(islands.len(), 3);
+        for island in &islands {
+            assert_eq!(island.faces().count(), 3);
+            let x = island
+                .faces()
+                .map(|f| f.vertices(&mesh).map(|v| mesh.positions[v].x).sum::<f32>())
+                .collect::<Vec<_>>();
+            assert!(x.iter().all(|s| *s == x[0]));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unwrap_with_seams() -> SMeshResult<()> {
+        // a flat grid flattens without distortion into a single chart
+        let mut mesh = quad_grid(3)?;
+        mesh.unwrap_with_seams()?;
+        let uvs = mesh.uvs.clone().unwrap();
+        let mut scales = vec![];
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(&mesh) {
+                let n = h.next().run(&mesh)?;
+                let length = mesh.positions[h.src_vert().run(&mesh)?]
+                    .distance(mesh.positions[n.src_vert().run(&mesh)?]);
+                scales.push(uvs[h].distance(uvs[n]) / length);
+            }
+        }
+        assert!(scales.iter().all(|s| (s - scales[0]).abs() < 1e-4));
+        assert_eq!(mesh.uv_islands()?.len(), 1);
+
+        // cutting along a seam splits the chart
+        let h = mesh
+            .halfedges()
+            .keys()
+            .filter(|h| !h.is_boundary(&mesh) && !h.opposite().is_boundary(&mesh))
+            .collect_vec();
+        let column = h
+            .iter()
+            .copied()
+            .filter(|h| {
+                let (p0, p1) = (
+                    mesh.positions[h.src_vert().run(&mesh).unwrap()],
+                    mesh.positions[h.dst_vert().run(&mesh).unwrap()],
+                );
+                p0.x == 1.0 && p1.x == 1.0
+            })
+            .collect_vec();
+        for h in column {
+            mesh.set_seam(h, true)?;
+        }
+        let charts = mesh.unwrap_with_seams()?;
+        let islands = mesh.uv_islands()?;
+        assert_eq!(islands.len(), 2);
+        assert_eq!(charts.len(), 2);
+        let ids = mesh.attribute::<FaceId>(UV_CHART_ATTRIBUTE).unwrap();
+        for (i, chart) in charts.iter().enumerate() {
+            assert_eq!(chart.faces().count(), if i == 0 { 3 } else { 6 });
+            assert!(chart.faces().all(|f| ids.get(f) == Some(i as u32)));
+        }
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert!(uvs
+            .values()
+            .all(|uv| uv.min_element() >= -1e-5 && uv.max_element() <= 1.0 + 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn lscm_unwrap() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        let vertex_at = |mesh: &SMesh, x: f32, y: f32| {
+            mesh.vertices()
+                .keys()
+                .find(|v| mesh.positions[*v] == vec3(x, y, 0.0))
+                .unwrap()
+        };
+        let (a, b) = (vertex_at(&mesh, 0.0, 0.0), vertex_at(&mesh, 2.0, 0.0));
+        let selection = mesh.select_faces_where(|_, _| true);
+        assert_eq!(
+            mesh.lscm_unwrap(selection.clone(), [(a, Vec2::ZERO), (a, Vec2::X)]),
+            Err(SMeshError::DefaultError)
+        );
+
+        // a planar patch is reproduced up to the similarity given by the pins
+        mesh.lscm_unwrap(selection, [(a, Vec2::ZERO), (b, vec2(0.0, 1.0))])?;
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for h in mesh.halfedges().keys() {
+            if h.is_boundary(&mesh) {
+                continue;
+            }
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            assert!(uvs[h].distance(vec2(-p.y, p.x) * 0.5) < 1e-4);
+        }
+
+        // the pins have to be part of the faces
+        let far = vertex_at(&mesh, 2.0, 2.0);
+        let f = far.faces(&mesh).next().unwrap();
+        assert!(matches!(
+            mesh.lscm_unwrap(f, [(a, Vec2::ZERO), (far, Vec2::X)]),
+            Err(SMeshError::VertexNotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn arap_unwrap() -> SMeshResult<()> {
+        // a bent strip unrolls into a flat strip of the same size
+        let mut mesh = quad_grid(4)?;
+        for v in mesh.vertices().keys().collect_vec() {
+            let p = mesh.positions[v];
+            let angle = p.x / 4.0 * std::f32::consts::PI;
+            mesh.positions[v] = vec3(angle.cos(), p.y, angle.sin()) * vec3(4.0 / PI, 1.0, 4.0 / PI);
+        }
+        let selection = mesh.select_faces_where(|_, _| true);
+        mesh.arap_unwrap(selection, 10)?;
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(&mesh) {
+                let n = h.next().run(&mesh)?;
+                let length = mesh.positions[h.src_vert().run(&mesh)?]
+                    .distance(mesh.positions[n.src_vert().run(&mesh)?]);
+                assert!((uvs[h].distance(uvs[n]) - length).abs() < 1e-2);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn cube_project_uvs() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 2.0)?;
+        let selection = mesh.select_faces_where(|_, _| true);
+        mesh.cube_project_uvs(selection, 0.5)?;
+        assert_eq!(mesh.uv_islands()?.len(), 6);
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for f in mesh.faces().keys() {
+            let corners = f.halfedges(&mesh).map(|h| uvs[h]).collect_vec();
+            // every side covers a unit square, facing the viewer
+            let (min, max) = corners
+                .iter()
+                .fold((Vec2::MAX, Vec2::MIN), |(min, max), uv| {
+                    (min.min(*uv), max.max(*uv))
+                });
+            assert_eq!(max - min, Vec2::ONE);
+            let signed_area: f32 = corners
+                .iter()
+                .circular_tuple_windows()
+                .map(|(a, b)| a.perp_dot(*b))
+                .sum();
+            assert!(signed_area > 0.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn weld_uvs() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        assert_eq!(mesh.weld_uvs(0.1), Err(SMeshError::UnsupportedOperation));
+
+        // positions as uvs, with a small offset per face
+        let mut uvs = SecondaryMap::new();
+        for (i, f) in mesh.faces().keys().enumerate() {
+            for h in f.halfedges(&mesh) {
+                let p = mesh.positions[h.src_vert().run(&mesh)?];
+                uvs.insert(h, vec2(p.x, p.y) + Vec2::splat(i as f32 * 1e-3));
+            }
+        }
+        mesh.uvs = Some(uvs);
+        assert_eq!(mesh.uv_islands()?.len(), 4);
+        assert_eq!(mesh.weld_uvs(1e-4)?, 0);
+        // the four outer corners belong to a single face each
+        assert_eq!(mesh.weld_uvs(0.01)?, 12);
+        assert_eq!(mesh.uv_islands()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn fit_uvs() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        let selection = mesh.select_faces_where(|_, _| true);
+        assert_eq!(
+            mesh.normalize_uvs(selection.clone()),
+            Err(SMeshError::UnsupportedOperation)
+        );
+        let mut uvs = SecondaryMap::new();
+        for h in mesh.halfedges().keys() {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            uvs.insert(h, vec2(p.x * 2.0 + 3.0, p.y - 1.0));
+        }
+        mesh.uvs = Some(uvs);
+
+        mesh.normalize_uvs(selection.clone())?;
+        let corners = mesh.uv_corners(selection.clone())?;
+        assert_eq!(mesh.uv_bounds(&corners), Some((Vec2::ZERO, vec2(1.0, 0.5))));
+
+        let rect = (vec2(0.5, 0.5), vec2(1.0, 0.75));
+        mesh.fit_uvs_to_rect(selection, rect)?;
+        assert_eq!(mesh.uv_bounds(&corners), Some(rect));
+        Ok(())
+    }
+
+    #[test]
+    fn uv_pivot() -> SMeshResult<()> {
+        let mut mesh = quad_grid(1)?;
+        let f = mesh.faces().keys().next().unwrap();
+        let mut uvs = SecondaryMap::new();
+        for h in f.halfedges(&mesh) {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            uvs.insert(h, vec2(p.x, p.y) * 0.5);
+        }
+        mesh.uvs = Some(uvs);
+        let corners = mesh.uv_corners(f)?;
+
+        mesh.scale_uvs(f, Vec2::splat(2.0), UvPivot::Center)?;
+        assert_eq!(
+            mesh.uv_bounds(&corners),
+            Some((vec2(-0.5, -0.5), vec2(0.5, 0.5)))
+        );
+        mesh.scale_uvs(f, Vec2::splat(0.5), UvPivot::SelectionCenter)?;
+        assert_eq!(
+            mesh.uv_bounds(&corners),
+            Some((vec2(-0.25, -0.25), vec2(0.25, 0.25)))
+        );
+        mesh.scale_uvs(f, vec2(2.0, 1.0), UvPivot::Point(vec2(-0.25, 0.0)))?;
+        assert_eq!(
+            mesh.uv_bounds(&corners),
+            Some((vec2(-0.25, -0.25), vec2(0.75, 0.25)))
+        );
+
+        mesh.rotate_uvs(f, FRAC_PI_2, UvPivot::Point(vec2(-0.25, -0.25)))?;
+        let (min, max) = mesh.uv_bounds(&corners).unwrap();
+        assert!(min.distance(vec2(-0.75, -0.25)) < 1e-5);
+        assert!(max.distance(vec2(-0.25, 0.75)) < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn transform_uv_island() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let selection = mesh.select_faces_where(|_, _| true);
+        mesh.cube_project_uvs(selection, 1.0)?;
+        let before = mesh.uvs.clone().unwrap();
+        let f = mesh.faces().keys().next().unwrap();
+        let corners = mesh.uv_corners(f)?;
+        let (min, max) = mesh.uv_bounds(&corners).unwrap();
+
+        mesh.transform_uv_island(f, Vec2::splat(0.5), FRAC_PI_2, vec2(2.0, 0.0))?;
+        let center = (min + max) * 0.5 + vec2(2.0, 0.0);
+        let (new_min, new_max) = mesh.uv_bounds(&corners).unwrap();
+        assert!(new_min.distance(center - Vec2::splat(0.25)) < 1e-5);
+        assert!(new_max.distance(center + Vec2::splat(0.25)) < 1e-5);
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for (h, uv) in before {
+            if h.face().run(&mesh)? != f {
+                assert_eq!(uvs[h], uv);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equalize_texel_density() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let selection = mesh.select_faces_where(|_, _| true);
+        mesh.cube_project_uvs(selection, 1.0)?;
+        let f = mesh.faces().keys().next().unwrap();
+        mesh.transform_uv_island(f, Vec2::splat(3.0), 0.0, Vec2::ZERO)?;
+
+        mesh.equalize_texel_density(256.0, 512)?;
+        for f in mesh.faces().keys() {
+            let (min, max) = mesh.uv_bounds(&mesh.uv_corners(f)?).unwrap();
+            assert!((max - min).distance(Vec2::splat(0.5)) < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pack_atlas() -> SMeshResult<()> {
+        let mut a = quad_grid(2)?;
+        let mut b = quad_grid(1)?;
+        super::pack_atlas(&mut [&mut a, &mut b], AtlasOptions::default())?;
+        let bounds = |mesh: &SMesh| {
+            let corners = mesh
+                .uv_corners(mesh.select_faces_where(|_, _| true))
+                .unwrap();
+            mesh.uv_bounds(&corners).unwrap()
+        };
+        let ((a_min, a_max), (b_min, b_max)) = (bounds(&a), bounds(&b));
+        // the charts keep their relative size and do not overlap
+        assert!(((a_max - a_min) - (b_max - b_min) * 2.0).length() < 1e-4);
+        assert!(
+            a_max.x <= b_min.x || b_max.x <= a_min.x || a_max.y <= b_min.y || b_max.y <= a_min.y
+        );
+        for (min, max) in [(a_min, a_max), (b_min, b_max)] {
+            assert!(min.min_element() >= -1e-5 && max.max_element() <= 1.0 + 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pack_atlas_input_uvs() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        let options = AtlasOptions {
+            use_input_uvs: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            super::pack_atlas(&mut [&mut mesh], options),
+            Err(SMeshError::UnsupportedOperation)
+        );
+
+        // the six box projected sides get repacked without being cut again
+        mesh.cube_project_uvs(mesh.select_faces_where(|_, _| true), 1.0)?;
+        let f = mesh.faces().keys().next().unwrap();
+        mesh.rotate_uvs(f, 0.3, UvPivot::SelectionCenter)?;
+        let before = mesh.uvs.clone().unwrap();
+        super::pack_atlas(&mut [&mut mesh], options)?;
+        assert_eq!(mesh.uv_islands()?.len(), 6);
+        let uvs = mesh.uvs.as_ref().unwrap();
+        // every island is only moved and uniformly scaled
+        let scale =
+            |a: HalfedgeId, b: HalfedgeId| uvs[a].distance(uvs[b]) / before[a].distance(before[b]);
+        let h = f.halfedge().run(&mesh)?;
+        let expected = scale(h, h.next().run(&mesh)?);
+        for f in mesh.faces().keys() {
+            for h in f.halfedges(&mesh) {
+                assert!((scale(h, h.next().run(&mesh)?) - expected).abs() < 1e-4);
+            }
+        }
+        let (min, max) = mesh
+            .uv_bounds(&mesh.uv_corners(mesh.select_faces_where(|_, _| true))?)
+            .unwrap();
+        assert!(min.min_element() >= -1e-5 && max.max_element() <= 1.0 + 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn uv_layout_mesh() -> SMeshResult<()> {
+        let mut mesh = cube(Vec3::ZERO, 1.0)?;
+        assert_eq!(
+            mesh.uv_layout_mesh().err(),
+            Some(SMeshError::UnsupportedOperation)
+        );
+        mesh.cube_project_uvs(mesh.select_faces_where(|_, _| true), 1.0)?;
+        let layout = mesh.uv_layout_mesh()?;
+        assert_eq!(layout.faces().len(), 6);
+        assert!(layout.positions.values().all(|p| p.z == 0.0));
+        // the six sides of the cube stay separate islands in the layout
+        assert_eq!(layout.uv_islands()?.len(), 6);
+        let layout_corners = layout.uv_corners(layout.select_faces_where(|_, _| true))?;
+        let corners = mesh.uv_corners(mesh.select_faces_where(|_, _| true))?;
+        assert_eq!(layout.uv_bounds(&layout_corners), mesh.uv_bounds(&corners));
+        Ok(())
+    }
+
+    #[test]
+    fn planar_project_uvs() -> SMeshResult<()> {
+        let mut mesh = quad_grid(2)?;
+        let all = mesh.select_faces_where(|_, _| true);
+        assert_eq!(
+            mesh.planar_project_uvs(all.clone(), Vec3::Y, Vec3::NEG_Y),
+            Err(SMeshError::DefaultError)
+        );
+
+        // looking down on the grid from above
+        mesh.planar_project_uvs(all.clone(), Vec3::NEG_Z, Vec3::Y)?;
+        for (h, uv) in mesh.uvs.as_ref().unwrap() {
+            assert_eq!(uv.extend(0.0), mesh.positions[h.src_vert().run(&mesh)?]);
+        }
+
+        // a tilted direction only affects the selected faces
+        let f = mesh.faces().keys().next().unwrap();
+        let before = mesh.uvs.clone().unwrap();
+        mesh.planar_project_uvs(f, vec3(1.0, 0.0, -1.0), Vec3::Y)?;
+        let uvs = mesh.uvs.as_ref().unwrap();
+        for (h, uv) in uvs {
+            let p = mesh.positions[h.src_vert().run(&mesh)?];
+            if h.face().run(&mesh)? == f {
+                assert!(uv.distance(vec2(p.x / 2f32.sqrt(), p.y)) < 1e-5);
+            } else {
+                assert_eq!(*uv, before[h]);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
-use crate::prelude::SMeshResult;
-use crate::smesh::SMesh;
-use glam::vec3;
+#![allow(clippy::excessive_precision)]
+
+use crate::bail;
+use crate::prelude::*;
+use glam::{vec3, Vec3};
 
 pub fn vertex_onering() -> SMeshResult<SMesh> {
     let mut mesh = SMesh::new();
@@ -51,6 +53,131 @@ pub fn edge_onering() -> SMeshResult<SMesh> {
     Ok(mesh)
 }
 
+/// Flat grid of `n` x `n` quads in the xy plane, each split into two triangles
+pub fn triangulated_grid(n: usize) -> SMeshResult<SMesh> {
+    let mut mesh = SMesh::new();
+    let mut ids = vec![];
+    for y in 0..=n {
+        for x in 0..=n {
+            ids.push(mesh.add_vertex(vec3(x as f32, y as f32, 0.0)));
+        }
+    }
+    let row = n + 1;
+    for y in 0..n {
+        for x in 0..n {
+            let i = y * row + x;
+            mesh.add_triangle(ids[i], ids[i + 1], ids[i + row + 1])?;
+            mesh.add_triangle(ids[i], ids[i + row + 1], ids[i + row])?;
+        }
+    }
+    Ok(mesh)
+}
+
+/// Flat grid of `n` x `n` quads in the xy plane
+pub fn quad_grid(n: usize) -> SMeshResult<SMesh> {
+    let mut mesh = SMesh::new();
+    let mut ids = vec![];
+    for y in 0..=n {
+        for x in 0..=n {
+            ids.push(mesh.add_vertex(vec3(x as f32, y as f32, 0.0)));
+        }
+    }
+    let row = n + 1;
+    for y in 0..n {
+        for x in 0..n {
+            let i = y * row + x;
+            mesh.add_quad(ids[i], ids[i + 1], ids[i + row + 1], ids[i + row])?;
+        }
+    }
+    Ok(mesh)
+}
+
+/// Closed axis aligned cube with outward facing quads
+pub fn cube(center: Vec3, size: f32) -> SMeshResult<SMesh> {
+    let mut mesh = SMesh::new();
+    let min = center - Vec3::splat(size * 0.5);
+    let v = [
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+        vec3(1.0, 0.0, 1.0),
+        vec3(1.0, 1.0, 1.0),
+        vec3(0.0, 1.0, 1.0),
+    ]
+    .map(|p| mesh.add_vertex(min + p * size));
+    mesh.add_quad(v[0], v[3], v[2], v[1])?;
+    mesh.add_quad(v[4], v[5], v[6], v[7])?;
+    mesh.add_quad(v[0], v[1], v[5], v[4])?;
+    mesh.add_quad(v[3], v[7], v[6], v[2])?;
+    mesh.add_quad(v[0], v[4], v[7], v[3])?;
+    mesh.add_quad(v[1], v[2], v[6], v[5])?;
+    Ok(mesh)
+}
+
+/// Triangulated sphere with `rings` latitude bands and `segments` longitude segments
+pub fn uv_sphere(radius: f32, rings: usize, segments: usize) -> SMeshResult<SMesh> {
+    let mut mesh = SMesh::new();
+    let top = mesh.add_vertex(vec3(0.0, radius, 0.0));
+    let bottom = mesh.add_vertex(vec3(0.0, -radius, 0.0));
+    let mut ids = vec![];
+    for i in 1..rings {
+        let theta = i as f32 / rings as f32 * std::f32::consts::PI;
+        for j in 0..segments {
+            let phi = j as f32 / segments as f32 * std::f32::consts::TAU;
+            let p = vec3(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                -theta.sin() * phi.sin(),
+            );
+            ids.push(mesh.add_vertex(p * radius));
+        }
+    }
+    let id = |ring: usize, segment: usize| ids[ring * segments + segment % segments];
+    for j in 0..segments {
+        mesh.add_triangle(top, id(0, j), id(0, j + 1))?;
+        mesh.add_triangle(bottom, id(rings - 2, j + 1), id(rings - 2, j))?;
+        for i in 0..rings - 2 {
+            mesh.add_triangle(id(i, j), id(i + 1, j), id(i + 1, j + 1))?;
+            mesh.add_triangle(id(i, j), id(i + 1, j + 1), id(i, j + 1))?;
+        }
+    }
+    Ok(mesh)
+}
+
+/// Check the halfedge connectivity invariants of the mesh
+pub fn check_connectivity(mesh: &SMesh) -> SMeshResult<()> {
+    for h in mesh.halfedges().keys() {
+        let o = h.opposite().run(mesh)?;
+        if o.opposite().run(mesh)? != h {
+            bail!(TopologyError);
+        }
+        if h.next().prev().run(mesh)? != h || h.prev().next().run(mesh)? != h {
+            bail!(TopologyError);
+        }
+        if h.next().src_vert().run(mesh)? != h.dst_vert().run(mesh)? {
+            bail!(TopologyError);
+        }
+        if h.next().face().run(mesh).ok() != h.face().run(mesh).ok() {
+            bail!(TopologyError);
+        }
+    }
+    for v in mesh.vertices().keys() {
+        if let Ok(h) = v.halfedge().run(mesh) {
+            if h.src_vert().run(mesh)? != v {
+                bail!(TopologyError);
+            }
+        }
+    }
+    for f in mesh.faces().keys() {
+        if f.halfedge().face().run(mesh)? != f {
+            bail!(TopologyError);
+        }
+    }
+    Ok(())
+}
+
 pub fn subdivided_icosahedron() {
     todo!()
     // if (icosahedron_mesh.is_empty())
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod smesh_tests {
     use crate::prelude::*;
-    use crate::test_utils::{edge_onering, vertex_onering};
+    use crate::test_utils::{check_connectivity, edge_onering, triangulated_grid, vertex_onering};
     use glam::vec3;
     use slotmap::KeyData;
 
@@ -123,6 +123,117 @@ mod smesh_tests {
         assert_eq!(mesh_2.faces().len(), 1);
     }
 
+    #[test]
+    fn add_faces() -> SMeshResult<()> {
+        let mut mesh = SMesh::new();
+        let ids = (0..16)
+            .map(|i| mesh.add_vertex(vec3((i % 4) as f32, (i / 4) as f32, 0.0)))
+            .collect::<Vec<_>>();
+        let quads = (0..9)
+            .map(|i| {
+                let i = i + i / 3;
+                vec![ids[i], ids[i + 1], ids[i + 5], ids[i + 4]]
+            })
+            .collect::<Vec<_>>();
+        let faces = mesh.add_faces(&quads)?;
+        assert_eq!(faces.len(), 9);
+        assert_eq!(mesh.halfedges().len(), 48);
+        check_connectivity(&mesh)?;
+        for (f, quad) in faces.iter().zip(&quads) {
+            assert_eq!(&f.vertices(&mesh).collect::<Vec<_>>()[..], &quad[..]);
+        }
+        assert!(ids[0].is_boundary(&mesh));
+        assert!(!ids[5].is_boundary(&mesh));
+        assert_eq!(ids[0].halfedges(&mesh).count(), 2);
+        assert_eq!(ids[5].halfedges(&mesh).count(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn add_faces_fallback() -> SMeshResult<()> {
+        let mut mesh = SMesh::new();
+        let (v0, v1, v2, _) = add_triangle(&mut mesh);
+        let v3 = mesh.add_vertex(vec3(1.0, 1.0, 0.0));
+        mesh.add_faces(&[vec![v1, v3, v2]])?;
+        assert_eq!(mesh.faces().len(), 2);
+        check_connectivity(&mesh)?;
+        assert!(!v1.halfedge_to(v2).is_boundary(&mesh));
+        assert_eq!(
+            mesh.add_faces(&[vec![v0, v1]]).err(),
+            Some(SMeshError::DefaultError)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edge_index() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(3)?;
+        mesh.build_edge_index();
+        assert!(mesh.has_edge_index());
+        let v = mesh.add_vertex(vec3(1.5, 1.5, 0.0));
+        let h = mesh
+            .halfedges()
+            .keys()
+            .find(|h| !h.is_boundary(&mesh))
+            .unwrap();
+        mesh.insert_vertex(h, v)?;
+        let h = mesh
+            .halfedges()
+            .keys()
+            .find(|h| mesh.is_collapse_ok(*h).is_ok());
+        mesh.collapse(h.unwrap())?;
+        let (v0, v1, v2) = (
+            mesh.add_vertex(vec3(0.0, 0.0, 1.0)),
+            mesh.add_vertex(vec3(1.0, 0.0, 1.0)),
+            mesh.add_vertex(vec3(0.0, 1.0, 1.0)),
+        );
+        mesh.add_triangle(v0, v1, v2)?;
+        assert!(mesh.has_edge_index());
+
+        for (h, he) in mesh.halfedges() {
+            let src = h.src_vert().run(&mesh)?;
+            assert_eq!(src.halfedge_to(he.vertex).run(&mesh)?, h);
+        }
+        assert!(v0.halfedge_to(v).run(&mesh).is_err());
+
+        mesh.halfedges_mut();
+        assert!(!mesh.has_edge_index());
+        Ok(())
+    }
+
+    #[test]
+    fn edge_index_direct_edit() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(3)?;
+        mesh.build_edge_index();
+        let h = mesh
+            .halfedges()
+            .keys()
+            .find(|h| !h.is_boundary(&mesh))
+            .unwrap();
+        let src = h.src_vert().run(&mesh)?;
+        let dst = h.dst_vert().run(&mesh)?;
+        let v = mesh.add_vertex(vec3(1.5, 1.5, 0.0));
+        // bypasses the index, the missing entry falls back to the ring walk
+        mesh.connectivity.he_mut(h)?.vertex = v;
+        assert!(mesh.has_edge_index());
+        assert_eq!(src.halfedge_to(v).run(&mesh)?, h);
+
+        mesh.he_mut(h).vertex = dst;
+        assert!(!mesh.has_edge_index());
+        assert_eq!(src.halfedge_to(dst).run(&mesh)?, h);
+        assert!(src.halfedge_to(v).run(&mesh).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn compact_connectivity() {
+        // optional ids use the niche of the key version, so they need no extra space
+        assert_eq!(size_of::<Option<HalfedgeId>>(), size_of::<HalfedgeId>());
+        assert_eq!(size_of::<Halfedge>(), 5 * size_of::<HalfedgeId>());
+        assert_eq!(size_of::<Vertex>(), size_of::<HalfedgeId>());
+        assert_eq!(size_of::<Face>(), size_of::<HalfedgeId>());
+    }
+
     // TODO: port tests for properties
 
     #[test]
@@ -198,6 +309,46 @@ mod smesh_tests {
         Ok(())
     }
 
+    #[test]
+    fn tris_to_quads() -> SMeshResult<()> {
+        let mut mesh = triangulated_grid(2)?;
+        assert_eq!(mesh.faces().len(), 8);
+        mesh.tris_to_quads(0.1)?;
+        assert_eq!(mesh.faces().len(), 4);
+        assert!(mesh.is_quad_mesh());
+        check_connectivity(&mesh)?;
+        Ok(())
+    }
+
+    #[test]
+    fn tris_to_quads_angle_limit() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let (_, v1, v2, _) = add_triangle(mesh);
+        // second triangle folded upwards by 90 degrees
+        let v3 = mesh.add_vertex(vec3(0.5, 0.5, 1.0));
+        mesh.add_triangle(v1, v3, v2)?;
+        mesh.tris_to_quads(0.5)?;
+        assert_eq!(mesh.faces().len(), 2);
+        mesh.tris_to_quads(std::f32::consts::PI)?;
+        assert_eq!(mesh.faces().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn edge_crease() -> SMeshResult<()> {
+        let mesh = &mut SMesh::new();
+        let (v0, v1, _, _) = add_triangle(mesh);
+        let h = v0.halfedge_to(v1).run(mesh)?;
+        let o = h.opposite().run(mesh)?;
+        assert_eq!(mesh.edge_crease(h), 0.0);
+        mesh.set_edge_crease(h, 1.5)?;
+        assert_eq!(mesh.edge_crease(h), 1.0);
+        assert_eq!(mesh.edge_crease(o), 1.0);
+        mesh.clear_edge_crease(o)?;
+        assert_eq!(mesh.edge_crease(h), 0.0);
+        Ok(())
+    }
+
     /// Utils
     fn add_triangle(mesh: &mut SMesh) -> (VertexId, VertexId, VertexId, FaceId) {
         let v0 = mesh.add_vertex(vec3(0.0, 0.0, 0.0));